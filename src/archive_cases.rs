@@ -0,0 +1,504 @@
+use crate::util::{current_dir, expand_env, io_context, slugify_label};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Error, FnArg, ItemFn, LitByteStr, LitStr, Result, Type};
+
+const MAX_CASES: usize = 10_000;
+
+// The contents of an entry are either a range of bytes within the archive itself (for entries
+// that are not compressed) or the decompressed bytes of the entry.
+#[derive(Debug, PartialEq, Eq)]
+enum Contents {
+    Range(usize, usize),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Entry {
+    path: String,
+    contents: Contents,
+}
+
+impl Entry {
+    fn bytes<'a>(&'a self, archive: &'a [u8]) -> &'a [u8] {
+        match &self.contents {
+            Contents::Range(start, end) => &archive[*start..*end],
+            Contents::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+// Compressed archives are decompressed at expansion time using flate2, which is only available
+// when the gzip feature is enabled.
+#[cfg(feature = "gzip")]
+fn decompress(data: &[u8], gzip: bool) -> std::result::Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+    let res = match gzip {
+        true => flate2::read::MultiGzDecoder::new(data).read_to_end(&mut buf),
+        false => flate2::read::DeflateDecoder::new(data).read_to_end(&mut buf),
+    };
+
+    res.map(|_| buf)
+        .map_err(|e| format!("unable to decompress: {}", e))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress(_data: &[u8], _gzip: bool) -> std::result::Result<Vec<u8>, String> {
+    Err("compressed archives require the `gzip` feature of simple_test_case to be enabled".into())
+}
+
+fn le_u16(data: &[u8], pos: usize) -> std::result::Result<usize, String> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        .ok_or_else(|| "unexpected end of zip archive".to_string())
+}
+
+fn le_u32(data: &[u8], pos: usize) -> std::result::Result<usize, String> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| "unexpected end of zip archive".to_string())
+}
+
+// Null terminated (or full width) string fields of a tar header
+fn tar_str(field: &[u8]) -> String {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn tar_size(field: &[u8]) -> std::result::Result<usize, String> {
+    let s = tar_str(field);
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(0);
+    }
+
+    usize::from_str_radix(s, 8).map_err(|_| format!("invalid tar entry size: {:?}", s))
+}
+
+// The `path` record of a pax extended header, which is used for names that don't fit in the
+// fixed width name fields. Records are of the form "<len> <key>=<value>\n".
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let space = rest.iter().position(|b| *b == b' ')?;
+        let len: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..len)?.strip_suffix(b"\n")?;
+        if let Some(path) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(path).into_owned());
+        }
+        rest = &rest[len..];
+    }
+
+    None
+}
+
+// Only regular files are returned: directories, links and other special entries are skipped. Long
+// names are supported using either the ustar prefix field, pax extended headers or GNU long name
+// entries.
+fn parse_tar(data: &[u8]) -> std::result::Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    let mut long_name: Option<String> = None;
+
+    while pos + 512 <= data.len() {
+        let header = &data[pos..pos + 512];
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let size = tar_size(&header[124..136])?;
+        let start = pos + 512;
+        let end = start + size;
+        if end > data.len() {
+            return Err(format!("truncated tar entry at offset {}", pos));
+        }
+        pos = start + size.div_ceil(512) * 512;
+
+        let mut path = tar_str(&header[..100]);
+        if &header[257..262] == b"ustar" {
+            let prefix = tar_str(&header[345..500]);
+            if !prefix.is_empty() {
+                path = format!("{}/{}", prefix, path);
+            }
+        }
+
+        match header[156] {
+            b'L' => long_name = Some(tar_str(&data[start..end])),
+            b'x' => long_name = pax_path(&data[start..end]).or(long_name),
+            b'0' | 0 => entries.push(Entry {
+                path: long_name.take().unwrap_or(path),
+                contents: Contents::Range(start, end),
+            }),
+            _ => long_name = None,
+        }
+    }
+
+    Ok(entries)
+}
+
+// Entries are located using the central directory at the end of the archive. Entries may either be
+// stored or compressed using deflate, and directories (names ending in '/') are skipped.
+fn parse_zip(data: &[u8]) -> std::result::Result<Vec<Entry>, String> {
+    const EOCD: &[u8] = b"PK\x05\x06";
+    const CENTRAL: &[u8] = b"PK\x01\x02";
+    const LOCAL: &[u8] = b"PK\x03\x04";
+
+    let eocd = (0..data.len().saturating_sub(21))
+        .rev()
+        .find(|i| data[*i..].starts_with(EOCD))
+        .ok_or("no end of central directory record found")?;
+    let n_entries = le_u16(data, eocd + 10)?;
+    let mut pos = le_u32(data, eocd + 16)?;
+    let mut entries = Vec::with_capacity(n_entries);
+
+    for _ in 0..n_entries {
+        if !data[pos.min(data.len())..].starts_with(CENTRAL) {
+            return Err(format!("invalid central directory entry at offset {}", pos));
+        }
+        let method = le_u16(data, pos + 10)?;
+        let compressed = le_u32(data, pos + 20)?;
+        let name_len = le_u16(data, pos + 28)?;
+        let local = le_u32(data, pos + 42)?;
+        let name = data
+            .get(pos + 46..pos + 46 + name_len)
+            .ok_or("unexpected end of zip archive")?;
+        let path = String::from_utf8_lossy(name).into_owned();
+        pos += 46 + name_len + le_u16(data, pos + 30)? + le_u16(data, pos + 32)?;
+
+        if compressed == u32::MAX as usize || local == u32::MAX as usize {
+            return Err(format!("{}: zip64 archives are not supported", path));
+        }
+        if path.ends_with('/') {
+            continue;
+        }
+
+        if !data[local.min(data.len())..].starts_with(LOCAL) {
+            return Err(format!("{}: invalid local file header", path));
+        }
+        let start = local + 30 + le_u16(data, local + 26)? + le_u16(data, local + 28)?;
+        let end = start + compressed;
+        let raw = data
+            .get(start..end)
+            .ok_or_else(|| format!("{}: unexpected end of zip archive", path))?;
+
+        let contents = match method {
+            0 => Contents::Range(start, end),
+            8 => Contents::Bytes(decompress(raw, false).map_err(|e| format!("{}: {}", path, e))?),
+            m => return Err(format!("{}: unsupported compression method {}", path, m)),
+        };
+        entries.push(Entry { path, contents });
+    }
+
+    Ok(entries)
+}
+
+// Compressed tar archives are decompressed up front, with the contents of each entry being taken
+// from the decompressed archive.
+fn parse_archive(fname: &str, data: &[u8]) -> std::result::Result<Vec<Entry>, String> {
+    let mut entries = if fname.ends_with(".tar") {
+        parse_tar(data)?
+    } else if fname.ends_with(".tar.gz") || fname.ends_with(".tgz") {
+        let tar = decompress(data, true)?;
+        parse_tar(&tar)?
+            .into_iter()
+            .map(|e| Entry {
+                contents: Contents::Bytes(e.bytes(&tar).to_vec()),
+                path: e.path,
+            })
+            .collect()
+    } else if fname.ends_with(".zip") {
+        parse_zip(data)?
+    } else {
+        return Err("unsupported archive format: expected .tar, .tar.gz, .tgz or .zip".to_string());
+    };
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(entries)
+}
+
+// Test functions take either (contents) or (path: &str, contents) where contents is &str or &[u8]
+fn with_path(_fn: &ItemFn) -> Option<(bool, bool)> {
+    let str_ty: Type = parse_quote!(&str);
+    let bytes_ty: Type = parse_quote!(&[u8]);
+    let is = |fnarg: &FnArg, ty: &Type| matches!(fnarg, FnArg::Typed(pt) if *pt.ty == *ty);
+    let is_contents = |fnarg: &FnArg| {
+        if is(fnarg, &str_ty) {
+            Some(true)
+        } else if is(fnarg, &bytes_ty) {
+            Some(false)
+        } else {
+            None
+        }
+    };
+    let inputs: Vec<_> = _fn.sig.inputs.iter().collect();
+
+    match inputs.as_slice() {
+        [contents] => Some((false, is_contents(contents)?)),
+        [path, contents] if is(path, &str_ty) => Some((true, is_contents(contents)?)),
+        _ => None,
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(args as LitStr);
+    let original = parse_macro_input!(input as ItemFn);
+    let span = path.span();
+    let path = path.value();
+
+    let (with_path, as_str) = match with_path(&original) {
+        Some(args) => args,
+        None => {
+            return TokenStream::from(
+                Error::new(
+                    span,
+                    "archive_cases test functions must accept (contents) or (path: &str, contents) as arguments, where contents is a &str or &[u8]",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let res = load_archive(span, &path).and_then(|(abs_path, data, entries)| {
+        let ident = original.sig.ident.to_string();
+        let archive = format_ident!(
+            "__ARCHIVE_{}",
+            ident.strip_prefix("r#").unwrap_or(&ident).to_uppercase()
+        );
+        let mut case_attrs = Vec::with_capacity(entries.len());
+
+        for entry in entries.iter() {
+            let bytes = entry.bytes(&data);
+            let contents = match (&entry.contents, as_str) {
+                (_, true) if std::str::from_utf8(bytes).is_err() => {
+                    return Err(Error::new(
+                        span,
+                        format!("{} in {} is not valid UTF-8", entry.path, path),
+                    ))
+                }
+                (Contents::Range(start, end), true) => quote! {
+                    ::std::str::from_utf8(&#archive[#start..#end]).unwrap()
+                },
+                (Contents::Range(start, end), false) => quote! { &#archive[#start..#end] },
+                (Contents::Bytes(bytes), true) => {
+                    let s = LitStr::new(std::str::from_utf8(bytes).unwrap(), span);
+                    quote! { #s }
+                }
+                (Contents::Bytes(bytes), false) => {
+                    let b = LitByteStr::new(bytes, span);
+                    quote! { #b }
+                }
+            };
+
+            let entry_path = &entry.path;
+            let name = slugify_label(entry_path);
+            case_attrs.push(if with_path {
+                quote! { #[simple_test_case::test_case(#entry_path, #contents; #name)] }
+            } else {
+                quote! { #[simple_test_case::test_case(#contents; #name)] }
+            });
+        }
+
+        Ok(quote! {
+            // Entries that are stored uncompressed are read directly from the archive, which also
+            // ensures that we are recompiled if the archive changes
+            #[allow(dead_code)]
+            static #archive: &[u8] = include_bytes!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+fn load_archive(span: Span, path: &str) -> Result<(String, Vec<u8>, Vec<Entry>)> {
+    let err = |msg: String| {
+        Error::new(
+            span,
+            format!("Error loading test cases from {}: {}", path, msg),
+        )
+    };
+    let root = current_dir().map_err(|e| err(e.to_string()))?;
+    let abs_path = root.join(expand_env(path).map_err(err)?);
+    let data = std::fs::read(&abs_path)
+        .map_err(io_context("read", &abs_path))
+        .map_err(|e| err(e.to_string()))?;
+    let entries = parse_archive(path, &data).map_err(err)?;
+
+    if entries.is_empty() {
+        return Err(err("the archive contains no files".to_string()));
+    } else if entries.len() > MAX_CASES {
+        return Err(err(format!(
+            "the archive contains {} files which is more than the maximum of {} cases",
+            entries.len(),
+            MAX_CASES
+        )));
+    }
+
+    Ok((abs_path.to_string_lossy().into_owned(), data, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_header(path: &str, typeflag: u8, size: usize) -> Vec<u8> {
+        let mut header = vec![0; 512];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        let size = format!("{:011o}", size);
+        header[124..135].copy_from_slice(size.as_bytes());
+        header[156] = typeflag;
+        header[257..263].copy_from_slice(b"ustar\0");
+
+        header
+    }
+
+    fn padded(contents: &[u8]) -> Vec<u8> {
+        let mut block = contents.to_vec();
+        block.resize(contents.len().div_ceil(512) * 512, 0);
+        block
+    }
+
+    #[test]
+    fn tar_entries_are_found() {
+        let long = "a/".repeat(60) + "long.txt";
+        let record = format!(" path={}\n", long);
+        let pax = format!("{}{}", record.len() + 3, record);
+        let mut data = Vec::new();
+        data.extend(tar_header("dir/", b'5', 0));
+        data.extend(tar_header("dir/one.txt", b'0', 3));
+        data.extend(padded(b"one"));
+        data.extend(tar_header("PaxHeader", b'x', pax.len()));
+        data.extend(padded(pax.as_bytes()));
+        data.extend(tar_header("truncated", b'0', 4));
+        data.extend(padded(b"long"));
+        data.extend(vec![0; 1024]);
+
+        assert_eq!(
+            parse_tar(&data).unwrap(),
+            vec![
+                Entry {
+                    path: "dir/one.txt".to_string(),
+                    contents: Contents::Range(1024, 1027),
+                },
+                Entry {
+                    path: long,
+                    contents: Contents::Range(3072, 3076),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_tar_entries_are_an_error() {
+        let mut data = tar_header("one.txt", b'0', 1000);
+        data.extend(padded(b"one"));
+
+        assert!(parse_tar(&data).is_err());
+    }
+
+    // A zip archive containing a single entry with the given compression method
+    fn zip(path: &str, method: u16, contents: &[u8]) -> Vec<u8> {
+        let u16 = |n: usize| (n as u16).to_le_bytes();
+        let u32 = |n: usize| (n as u32).to_le_bytes();
+
+        let mut data = b"PK\x03\x04".to_vec();
+        data.extend([0; 4]);
+        data.extend(method.to_le_bytes());
+        data.extend([0; 8]);
+        data.extend(u32(contents.len()));
+        data.extend(u32(contents.len()));
+        data.extend(u16(path.len()));
+        data.extend(u16(0));
+        data.extend(path.as_bytes());
+        data.extend(contents);
+
+        let central = data.len();
+        data.extend(b"PK\x01\x02");
+        data.extend([0; 6]);
+        data.extend(method.to_le_bytes());
+        data.extend([0; 8]);
+        data.extend(u32(contents.len()));
+        data.extend(u32(contents.len()));
+        data.extend(u16(path.len()));
+        data.extend([0; 12]);
+        data.extend(u32(0));
+        data.extend(path.as_bytes());
+
+        let central_len = data.len() - central;
+        data.extend(b"PK\x05\x06");
+        data.extend([0; 4]);
+        data.extend(u16(1));
+        data.extend(u16(1));
+        data.extend(u32(central_len));
+        data.extend(u32(central));
+        data.extend(u16(0));
+
+        data
+    }
+
+    #[test]
+    fn stored_zip_entries_are_found() {
+        let data = zip("one.txt", 0, b"one");
+
+        assert_eq!(
+            parse_zip(&data).unwrap(),
+            vec![Entry {
+                path: "one.txt".to_string(),
+                contents: Contents::Range(37, 40),
+            }]
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn deflated_zip_entries_are_decompressed() {
+        let data = zip("one.txt", 8, b"\xcb\xcfK\x05\x00");
+
+        assert_eq!(
+            parse_zip(&data).unwrap(),
+            vec![Entry {
+                path: "one.txt".to_string(),
+                contents: Contents::Bytes(b"one".to_vec()),
+            }]
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compressed_tar_entries_are_decompressed() {
+        let data = include_bytes!("../tests/fixtures.tar.gz");
+        let entries = parse_archive("fixtures.tar.gz", data).unwrap();
+        let paths: Vec<_> = entries.iter().map(|e| e.path.as_str()).collect();
+
+        assert_eq!(paths, ["fixtures/nested/two.json", "fixtures/one.json"]);
+        assert_eq!(
+            entries[1].contents,
+            Contents::Bytes(b"{\"a\": 1}\n".to_vec())
+        );
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn compressed_archives_require_a_feature() {
+        let err = parse_zip(&zip("one.txt", 8, b"\xcb\xcfK\x05\x00")).unwrap_err();
+        assert!(err.contains("`gzip` feature"), "{}", err);
+
+        let err = parse_archive("fixtures.tgz", b"").unwrap_err();
+        assert!(err.contains("`gzip` feature"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_formats_are_an_error() {
+        assert!(parse_archive("fixtures.rar", b"").is_err());
+        assert!(parse_archive("fixtures.zip", b"not a zip file").is_err());
+    }
+}
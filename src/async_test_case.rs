@@ -0,0 +1,210 @@
+use crate::case_sets::is_test_fn;
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Expr, Ident, ItemFn, LitStr, Path, Result, Token,
+};
+
+// async_test_case(exprs, ...; "name"; opts, ...) where opts may include `runtime = <runtime>`
+// alongside any of the per-case options accepted by test_case
+struct AsyncCase {
+    args: Punctuated<Expr, Token![,]>,
+    name: LitStr,
+    opts: Vec<proc_macro2::TokenStream>,
+    runtime: Option<Ident>,
+}
+
+impl Parse for AsyncCase {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let args = Punctuated::parse_separated_nonempty_with(input, Expr::parse)?;
+        let _: Token![;] = input.parse()?;
+        let name = input.parse()?;
+        let mut opts = Vec::new();
+        let mut runtime = None;
+
+        if input.peek(Token![;]) {
+            let _: Token![;] = input.parse()?;
+            while !input.is_empty() {
+                if input.peek(Ident) && input.fork().parse::<Ident>()? == "runtime" {
+                    let _: Ident = input.parse()?;
+                    let _: Token![=] = input.parse()?;
+                    runtime = Some(input.parse()?);
+                } else {
+                    let mut opt = proc_macro2::TokenStream::new();
+                    while !input.is_empty() && !input.peek(Token![,]) {
+                        opt.extend([input.parse::<TokenTree>()?]);
+                    }
+                    opts.push(opt);
+                }
+
+                if !input.is_empty() {
+                    let _: Token![,] = input.parse()?;
+                }
+            }
+        }
+
+        Ok(Self {
+            args,
+            name,
+            opts,
+            runtime,
+        })
+    }
+}
+
+fn runtime_attr(runtime: &Ident) -> Result<Attribute> {
+    match runtime.to_string().as_str() {
+        "tokio" => Ok(parse_quote!(#[::tokio::test])),
+        "tokio_multi_thread" => Ok(parse_quote!(#[::tokio::test(flavor = "multi_thread")])),
+        "async_std" => Ok(parse_quote!(#[::async_std::test])),
+        other => Err(Error::new(
+            runtime.span(),
+            format!("unknown async runtime: {}", other),
+        )),
+    }
+}
+
+// Every case needs to be run using the same runtime as there is only a single test attribute for
+// the function, so the runtime may be given on any (or all) of the cases but must not conflict.
+fn resolve_runtime(cases: &[AsyncCase]) -> Result<Attribute> {
+    let mut runtime: Option<&Ident> = None;
+
+    for r in cases.iter().filter_map(|c| c.runtime.as_ref()) {
+        match runtime {
+            Some(existing) if existing != r => {
+                return Err(Error::new(
+                    r.span(),
+                    format!("conflicting async runtimes: {} and {}", existing, r),
+                ))
+            }
+            _ => runtime = Some(r),
+        }
+    }
+
+    match runtime {
+        Some(r) => runtime_attr(r),
+        None => Ok(parse_quote!(#[::tokio::test])),
+    }
+}
+
+fn extract_async_cases(attrs: &mut Vec<Attribute>) -> Result<Vec<AsyncCase>> {
+    let attr: Path = parse_quote!(async_test_case);
+    let qualified_attr: Path = parse_quote!(simple_test_case::async_test_case);
+    let mut cases = Vec::new();
+    let mut res = Ok(());
+
+    attrs.retain(|a| {
+        if a.path() != &attr && a.path() != &qualified_attr {
+            return true;
+        }
+        match a.parse_args::<AsyncCase>() {
+            Ok(case) => cases.push(case),
+            Err(err) => {
+                res = Err(Error::new(
+                    a.span(),
+                    format!("invalid async_test_case: {}", err),
+                ))
+            }
+        }
+        false
+    });
+
+    res.map(|_| cases)
+}
+
+fn render_case(case: &AsyncCase) -> proc_macro2::TokenStream {
+    let args = case.args.iter();
+    let name = &case.name;
+    let opts = &case.opts;
+
+    if opts.is_empty() {
+        quote! { #[simple_test_case::test_case(#(#args),*; #name)] }
+    } else {
+        quote! { #[simple_test_case::test_case(#(#args),*; #name; #(#opts),*)] }
+    }
+}
+
+// The async_test_case attributes are rewritten as test_case attributes followed by the test
+// attribute for the runtime, so that the ordering required by test_case is always met.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let first = parse_macro_input!(args as AsyncCase);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    if original.sig.asyncness.is_none() {
+        return TokenStream::from(
+            Error::new_spanned(
+                &original.sig,
+                "async_test_case can only be used with async test functions",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let res = extract_async_cases(&mut original.attrs).and_then(|rest| {
+        if is_test_fn(&original) {
+            return Err(Error::new_spanned(
+                &original.sig.ident,
+                "async_test_case applies the test attribute for the runtime itself: remove the existing test attribute",
+            ));
+        }
+        let cases: Vec<_> = std::iter::once(first).chain(rest).collect();
+        let runtime = resolve_runtime(&cases)?;
+        let case_attrs = cases.iter().map(render_case);
+
+        Ok(quote! {
+            #(#case_attrs)*
+            #runtime
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_is_split_from_case_options() {
+        let case: AsyncCase =
+            parse_quote!(1, 2; "small"; ignore, runtime = async_std, #[should_panic]);
+
+        assert_eq!(case.runtime.unwrap(), "async_std");
+        assert_eq!(
+            render_case(&AsyncCase {
+                runtime: None,
+                ..case
+            })
+            .to_string(),
+            quote!(#[simple_test_case::test_case(1, 2; "small"; ignore, #[should_panic])])
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn runtimes_must_not_conflict() {
+        let cases: Vec<AsyncCase> = vec![
+            parse_quote!(1; "one"),
+            parse_quote!(2; "two"; runtime = tokio),
+            parse_quote!(3; "three"; runtime = tokio),
+        ];
+        let expected: Attribute = parse_quote!(#[::tokio::test]);
+
+        assert_eq!(resolve_runtime(&cases).unwrap(), expected);
+        assert!(resolve_runtime(&[parse_quote!(1; "one"; runtime = smol)]).is_err());
+        assert!(resolve_runtime(&[
+            parse_quote!(1; "one"; runtime = tokio),
+            parse_quote!(2; "two"; runtime = async_std),
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::util::{case_binding, wrong_arg_count};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Expr, FnArg, ItemFn, LitStr, Path, Result, Token,
+};
+
+// bench_case(exprs, ...; "name for benchmark case")
+struct BenchCase {
+    args: Punctuated<Expr, Token![,]>,
+    name: LitStr,
+    span: Span,
+}
+
+impl Parse for BenchCase {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let args = Punctuated::parse_separated_nonempty_with(input, Expr::parse)?;
+        let _: Token![;] = input.parse()?;
+        let name: LitStr = input.parse()?;
+
+        Ok(Self { args, name, span })
+    }
+}
+
+// Glob up any other `bench_case` attribute macros underneath us in the same way as test_case
+fn extract_other_cases(cases: &mut Vec<BenchCase>, attrs: &mut Vec<Attribute>) -> Result<()> {
+    let bench_case_attr: Path = parse_quote!(bench_case);
+    let qualified_bench_case_attr: Path = parse_quote!(simple_test_case::bench_case);
+    let mut res = Ok(());
+
+    attrs.retain(|a| {
+        if a.path() != &bench_case_attr && a.path() != &qualified_bench_case_attr {
+            return true;
+        }
+        match a.parse_args::<BenchCase>() {
+            Ok(case) => cases.push(case),
+            Err(err) => res = Err(Error::new(a.span(), format!("invalid bench_case: {}", err))),
+        }
+        false
+    });
+
+    res
+}
+
+// The first argument of the benchmark function is the criterion Bencher for the case and the
+// remaining arguments are bound to the values given for the case before it is called.
+fn render_case(_fn: &ItemFn, case: BenchCase) -> Result<proc_macro2::TokenStream> {
+    let BenchCase { args, name, span } = case;
+    let ident = &_fn.sig.ident;
+    let mut inputs = _fn.sig.inputs.iter();
+
+    if !matches!(inputs.next(), Some(FnArg::Typed(_))) {
+        let msg =
+            "bench_case functions must take a &mut criterion::Bencher as their first argument";
+        return Err(Error::new(_fn.sig.span(), msg));
+    }
+
+    if args.len() != inputs.len() {
+        return Err(wrong_arg_count(span, &args, inputs.len()));
+    }
+
+    let bindings = inputs
+        .zip(args.iter())
+        .map(|(fnarg, val)| match fnarg {
+            FnArg::Typed(pt) => Ok(case_binding(&pt.pat, &pt.ty, val)),
+            FnArg::Receiver(_) => Err(Error::new_spanned(
+                fnarg,
+                "self is not permitted for bench cases",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let params = _fn.sig.inputs.iter().skip(1).map(|fnarg| match fnarg {
+        FnArg::Typed(pt) => pt.pat.clone(),
+        FnArg::Receiver(_) => unreachable!("receivers are rejected above"),
+    });
+
+    Ok(quote! {
+        group.bench_function(::criterion::BenchmarkId::from_parameter(#name), |b| {
+            #(#bindings)*
+            #ident(b, #(#params),*)
+        });
+    })
+}
+
+fn render_benches(mut original: ItemFn, cases: Vec<BenchCase>) -> Result<proc_macro2::TokenStream> {
+    if original.sig.asyncness.is_some() {
+        return Err(Error::new(
+            original.sig.asyncness.span(),
+            "bench_case functions can not be async",
+        ));
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for case in cases.iter() {
+        if names.contains(&case.name.value()) {
+            return Err(Error::new(
+                case.name.span(),
+                format!("duplicate bench_case name: {}", case.name.value()),
+            ));
+        }
+        names.push(case.name.value());
+    }
+
+    let ident = original.sig.ident.clone();
+    let group_name = ident.to_string();
+    let rendered = cases
+        .into_iter()
+        .map(|case| render_case(&original, case))
+        .collect::<Result<Vec<_>>>()?;
+    original.attrs.push(parse_quote!(#[allow(dead_code)]));
+
+    Ok(quote! {
+        mod #ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #original
+
+            pub fn bench_cases(c: &mut ::criterion::Criterion) {
+                let mut group = c.benchmark_group(#group_name);
+                #(#rendered)*
+                group.finish();
+            }
+
+            ::criterion::criterion_group!(benches, bench_cases);
+        }
+    })
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut original = parse_macro_input!(input as ItemFn);
+    let first_case = parse_macro_input!(args as BenchCase);
+
+    let mut cases = vec![first_case];
+    let res = extract_other_cases(&mut cases, &mut original.attrs)
+        .and_then(|_| render_benches(original, cases));
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn original() -> ItemFn {
+        parse_quote! {
+            #[bench_case(1_000; "large")]
+            fn sort(b: &mut Bencher, n: usize) {
+                b.iter(|| sorted(n));
+            }
+        }
+    }
+
+    #[test]
+    fn cases_are_rendered_into_a_criterion_group() {
+        let mut original = original();
+        let mut cases = vec![parse_quote!(10; "small")];
+        extract_other_cases(&mut cases, &mut original.attrs).unwrap();
+        let rendered = render_benches(original, cases).unwrap().to_string();
+
+        assert!(rendered.contains(&quote!(benchmark_group("sort")).to_string()));
+        assert!(rendered.contains(&quote!(from_parameter("small")).to_string()));
+        assert!(rendered.contains(&quote!(let n: usize = 1_000;).to_string()));
+        assert!(rendered.contains(&quote!(criterion_group!(benches, bench_cases)).to_string()));
+        assert!(!rendered.contains("bench_case ("));
+    }
+
+    #[test]
+    fn invalid_cases_are_an_error() {
+        let cases: Vec<Vec<BenchCase>> = vec![
+            vec![parse_quote!(1, 2; "too many")],
+            vec![parse_quote!(1; "dup"), parse_quote!(2; "dup")],
+        ];
+
+        for cases in cases {
+            assert!(render_benches(original(), cases).is_err());
+        }
+
+        let no_bencher: ItemFn = parse_quote!(
+            fn sort() {}
+        );
+        assert!(render_benches(no_bencher, vec![parse_quote!(1; "a")]).is_err());
+    }
+}
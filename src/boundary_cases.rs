@@ -0,0 +1,378 @@
+use crate::util::wrong_arg_count;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Expr, FnArg, Ident, ItemFn, LitStr, Pat, Path, Result, Token, Type,
+};
+
+// boundary_cases or boundary_cases(param, ...) with an optional `skip = [name, ...]` for boundary
+// values that should not be used
+struct BoundaryCases {
+    params: Vec<Ident>,
+    skip: Vec<Ident>,
+}
+
+impl Parse for BoundaryCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut params = Vec::new();
+        let mut skip = Vec::new();
+
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if input.peek(Token![=]) {
+                let _: Token![=] = input.parse()?;
+                match ident.to_string().as_str() {
+                    "skip" => {
+                        let content;
+                        bracketed!(content in input);
+                        let names = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                        skip.extend(names);
+                    }
+                    other => {
+                        return Err(Error::new(
+                            ident.span(),
+                            format!("unknown boundary_cases option: {}", other),
+                        ))
+                    }
+                }
+            } else {
+                params.push(ident);
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { params, skip })
+    }
+}
+
+const BOUNDARY_NAMES: &[&str] = &[
+    "min",
+    "min_plus_one",
+    "neg_one",
+    "neg_zero",
+    "zero",
+    "one",
+    "max_minus_one",
+    "max",
+    "min_positive",
+    "epsilon",
+    "infinity",
+    "neg_infinity",
+    "nan",
+];
+
+// The standard boundary values for each of the primitive numeric types, named so that failing
+// cases are easy to identify
+fn boundaries(ty: &Type) -> Option<Vec<(&'static str, Expr)>> {
+    let ident = match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.get_ident()?.to_string(),
+        _ => return None,
+    };
+    let ty: Ident = Ident::new(&ident, ty.span());
+
+    let values = match ident.as_str() {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => vec![
+            ("zero", parse_quote!(0)),
+            ("one", parse_quote!(1)),
+            ("max_minus_one", parse_quote!(#ty::MAX - 1)),
+            ("max", parse_quote!(#ty::MAX)),
+        ],
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => vec![
+            ("min", parse_quote!(#ty::MIN)),
+            ("min_plus_one", parse_quote!(#ty::MIN + 1)),
+            ("neg_one", parse_quote!(-1)),
+            ("zero", parse_quote!(0)),
+            ("one", parse_quote!(1)),
+            ("max_minus_one", parse_quote!(#ty::MAX - 1)),
+            ("max", parse_quote!(#ty::MAX)),
+        ],
+        "f32" | "f64" => vec![
+            ("min", parse_quote!(#ty::MIN)),
+            ("neg_one", parse_quote!(-1.0)),
+            ("neg_zero", parse_quote!(-0.0)),
+            ("zero", parse_quote!(0.0)),
+            ("min_positive", parse_quote!(#ty::MIN_POSITIVE)),
+            ("epsilon", parse_quote!(#ty::EPSILON)),
+            ("one", parse_quote!(1.0)),
+            ("max", parse_quote!(#ty::MAX)),
+            ("infinity", parse_quote!(#ty::INFINITY)),
+            ("neg_infinity", parse_quote!(#ty::NEG_INFINITY)),
+            ("nan", parse_quote!(#ty::NAN)),
+        ],
+        _ => return None,
+    };
+
+    Some(values)
+}
+
+// A test_case attribute given beneath boundary_cases (or loom_cases):
+// test_case(exprs, ...; "name" [; options])
+pub(crate) struct ExplicitCase {
+    pub(crate) args: Punctuated<Expr, Token![,]>,
+    pub(crate) name: LitStr,
+    pub(crate) rest: proc_macro2::TokenStream,
+}
+
+impl Parse for ExplicitCase {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let args = Punctuated::parse_separated_nonempty_with(input, Expr::parse)?;
+        let _: Token![;] = input.parse()?;
+        let name = input.parse()?;
+        let rest = input.parse()?;
+
+        Ok(Self { args, name, rest })
+    }
+}
+
+pub(crate) fn extract_explicit_cases(attrs: &mut Vec<Attribute>) -> Result<Vec<ExplicitCase>> {
+    let test_case_attr: Path = parse_quote!(test_case);
+    let qualified_test_case_attr: Path = parse_quote!(simple_test_case::test_case);
+    let mut cases = Vec::new();
+    let mut res = Ok(());
+
+    attrs.retain(|a| {
+        if a.path() != &test_case_attr && a.path() != &qualified_test_case_attr {
+            return true;
+        }
+        match a.parse_args::<ExplicitCase>() {
+            Ok(case) => cases.push(case),
+            Err(err) => res = Err(Error::new(a.span(), format!("invalid test_case: {}", err))),
+        }
+        false
+    });
+
+    res.map(|_| cases)
+}
+
+// The index and name of a selected parameter along with its boundary values
+type Axis = (usize, String, Vec<(&'static str, Expr)>);
+
+// The index, name and value of each selected parameter for a single case
+type Combination = Vec<(usize, String, Expr)>;
+
+// Every combination of the boundary values of the selected parameters
+fn combinations(axes: &[Axis]) -> Vec<Combination> {
+    let mut combinations = vec![vec![]];
+
+    for (ix, param, values) in axes.iter() {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination: Combination| {
+                values.iter().map(move |(name, value)| {
+                    let mut combination = combination.clone();
+                    combination.push((*ix, format!("{}_{}", param, name), value.clone()));
+                    combination
+                })
+            })
+            .collect();
+    }
+
+    combinations
+}
+
+fn render_cases(
+    span: Span,
+    opts: BoundaryCases,
+    original: &mut ItemFn,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let BoundaryCases { params, skip } = opts;
+
+    if let Some(s) = skip
+        .iter()
+        .find(|s| !BOUNDARY_NAMES.contains(&s.to_string().as_str()))
+    {
+        return Err(Error::new(
+            s.span(),
+            format!(
+                "unknown boundary value: {} (expected one of {})",
+                s,
+                BOUNDARY_NAMES.join(", ")
+            ),
+        ));
+    }
+
+    let inputs = original
+        .sig
+        .inputs
+        .iter()
+        .map(|fnarg| match fnarg {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(p) => Ok((p.ident.clone(), (*pt.ty).clone())),
+                _ => Err(Error::new_spanned(
+                    fnarg,
+                    "boundary_cases test function arguments must be simple identifiers",
+                )),
+            },
+            FnArg::Receiver(_) => Err(Error::new_spanned(
+                fnarg,
+                "self is not permitted for test cases",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(p) = params.iter().find(|p| !inputs.iter().any(|(i, _)| i == *p)) {
+        return Err(Error::new(
+            p.span(),
+            format!("{} is not an argument of the test function", p),
+        ));
+    }
+
+    let all = params.is_empty();
+    let mut axes = Vec::new();
+    for (ix, (ident, ty)) in inputs.iter().enumerate() {
+        if !all && !params.contains(ident) {
+            continue;
+        }
+        let mut values = boundaries(ty).ok_or_else(|| {
+            Error::new(
+                ty.span(),
+                format!(
+                    "boundary values can only be generated for primitive numeric types, not {}",
+                    quote!(#ty)
+                ),
+            )
+        })?;
+        values.retain(|(name, _)| !skip.iter().any(|s| s == name));
+        axes.push((ix, ident.to_string(), values));
+    }
+
+    let combinations = combinations(&axes);
+
+    // With every argument selected the boundary cases are added alongside any explicit cases
+    if axes.len() == inputs.len() {
+        return Ok(combinations
+            .into_iter()
+            .map(|combination| {
+                let args = combination.iter().map(|(_, _, value)| value);
+                let name = combination
+                    .iter()
+                    .map(|(_, name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("_");
+                quote! { #[simple_test_case::test_case(#(#args),*; #name)] }
+            })
+            .collect());
+    }
+
+    // Otherwise the explicit cases provide the remaining arguments and are crossed with the
+    // boundary values of the selected arguments
+    let explicit = extract_explicit_cases(&mut original.attrs)?;
+    if explicit.is_empty() {
+        return Err(Error::new(
+            span,
+            "test_case attributes providing the remaining arguments must be given beneath boundary_cases",
+        ));
+    }
+
+    let mut rendered = Vec::new();
+    for case in explicit.iter() {
+        for combination in combinations.iter() {
+            let mut explicit_args = case.args.iter();
+            let mut args = Vec::new();
+            for ix in 0..inputs.len() {
+                match combination.iter().find(|(i, _, _)| *i == ix) {
+                    Some((_, _, value)) => args.push(value),
+                    None => match explicit_args.next() {
+                        Some(arg) => args.push(arg),
+                        None if combination.iter().all(|(i, _, _)| *i < ix) => break,
+                        None => {
+                            // Every parameter up to the last boundary parameter needs a value
+                            let last = combination.iter().map(|(i, _, _)| *i).max().unwrap_or(0);
+                            let expected = last + 1 - combination.len();
+                            return Err(wrong_arg_count(case.name.span(), &case.args, expected));
+                        }
+                    },
+                }
+            }
+            if explicit_args.next().is_some() {
+                let expected = inputs.len() - combination.len();
+                return Err(wrong_arg_count(case.name.span(), &case.args, expected));
+            }
+
+            let name = combination
+                .iter()
+                .map(|(_, name, _)| name.as_str())
+                .fold(case.name.value(), |acc, n| acc + " " + n);
+            let rest = &case.rest;
+            rendered.push(quote! { #[simple_test_case::test_case(#(#args),*; #name #rest)] });
+        }
+    }
+
+    Ok(rendered)
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let span = Span::call_site();
+    let opts = parse_macro_input!(args as BoundaryCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    match render_cases(span, opts, &mut original) {
+        Ok(case_attrs) => TokenStream::from(quote! {
+            #(#case_attrs)*
+            #original
+        }),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(opts: BoundaryCases, mut original: ItemFn) -> Vec<String> {
+        render_cases(Span::call_site(), opts, &mut original)
+            .unwrap()
+            .into_iter()
+            .map(|ts| ts.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn boundaries_are_generated_for_every_argument() {
+        let cases = rendered(
+            parse_quote!(skip = [max_minus_one]),
+            parse_quote! { fn f(a: u8, b: u8) {} },
+        );
+
+        assert_eq!(cases.len(), 9);
+        assert_eq!(
+            cases[1],
+            quote!(#[simple_test_case::test_case(0, 1; "a_zero_b_one")]).to_string()
+        );
+    }
+
+    #[test]
+    fn explicit_cases_are_crossed_with_boundaries() {
+        let cases = rendered(
+            parse_quote!(n, skip = [min, min_plus_one, max_minus_one]),
+            parse_quote! {
+                #[test_case("a"; "letter"; ignore)]
+                fn f(s: &str, n: i8) {}
+            },
+        );
+
+        assert_eq!(cases.len(), 4);
+        assert_eq!(
+            cases[3],
+            quote!(#[simple_test_case::test_case("a", i8::MAX; "letter n_max"; ignore)])
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn non_numeric_arguments_are_an_error() {
+        let mut original: ItemFn = parse_quote! { fn f(s: String) {} };
+        let res = render_cases(Span::call_site(), parse_quote!(), &mut original);
+
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::test_cases::{parse_case_list, CaseList};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Ident, Item, ItemFn, ItemMod, Path, Result, Token,
+};
+
+// define_cases!(name, [ (exprs, ...) => "name", ... ])
+struct DefineCases {
+    name: Ident,
+    cases: CaseList,
+}
+
+impl Parse for DefineCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let name: Ident = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let cases = parse_case_list(input)?;
+        if cases.is_empty() {
+            return Err(Error::new(
+                name.span(),
+                "define_cases! requires at least one case",
+            ));
+        }
+        if !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+        }
+
+        Ok(Self { name, cases })
+    }
+}
+
+// Proc macros have no way of sharing state between invocations, so a case set is written out as a
+// macro_rules macro that adds its test_case attributes to the item it is given. When applying
+// several sets, the remaining sets are passed along in a `@chain { [path] ... }` prefix so that
+// every set adds its attributes before any of the test_case attributes are expanded.
+pub(crate) fn define_inner(input: TokenStream) -> TokenStream {
+    let DefineCases { name, cases } = parse_macro_input!(input as DefineCases);
+
+    let case_attrs: Vec<_> = cases
+        .iter()
+        .map(|(args, case)| {
+            let args = args.iter();
+            quote! { #[simple_test_case::test_case(#(#args),*; #case)] }
+        })
+        .collect();
+
+    TokenStream::from(quote! {
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            (@chain { [$($next:tt)*] $($rest:tt)* } $($item:tt)*) => {
+                $($next)*! { @chain { $($rest)* } #(#case_attrs)* $($item)* }
+            };
+            (@chain { } $($item:tt)*) => {
+                #(#case_attrs)*
+                $($item)*
+            };
+            ($($item:tt)*) => {
+                #(#case_attrs)*
+                $($item)*
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use #name;
+    })
+}
+
+// use_cases(set, ...)
+struct UseCases {
+    sets: Vec<Path>,
+}
+
+impl Parse for UseCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let sets: Punctuated<Path, Token![,]> = Punctuated::parse_terminated(input)?;
+        if sets.is_empty() {
+            return Err(Error::new(span, "use_cases requires at least one case set"));
+        }
+
+        Ok(Self {
+            sets: sets.into_iter().collect(),
+        })
+    }
+}
+
+// Any other use_cases attributes on the function are folded into this one so that all of the sets
+// are applied before the resulting test_case attributes are expanded.
+fn extract_other_sets(sets: &mut Vec<Path>, attrs: &mut Vec<Attribute>) -> Result<()> {
+    let use_cases_attr: Path = parse_quote!(use_cases);
+    let qualified_use_cases_attr: Path = parse_quote!(simple_test_case::use_cases);
+    let mut res = Ok(());
+
+    attrs.retain(|a| {
+        if a.path() != &use_cases_attr && a.path() != &qualified_use_cases_attr {
+            return true;
+        }
+        match a.parse_args::<UseCases>() {
+            Ok(other) => sets.extend(other.sets),
+            Err(err) => res = Err(Error::new(a.span(), format!("invalid use_cases: {}", err))),
+        }
+        false
+    });
+
+    res
+}
+
+pub(crate) fn use_inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let UseCases { mut sets } = parse_macro_input!(args as UseCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    if let Err(e) = extract_other_sets(&mut sets, &mut original.attrs) {
+        return TokenStream::from(e.into_compile_error());
+    }
+
+    let first = &sets[0];
+    let rest = &sets[1..];
+    TokenStream::from(quote! {
+        #first! { @chain { #([#rest])* } #original }
+    })
+}
+
+// Test functions are identified by having an attribute such as #[test] or #[tokio::test]
+pub(crate) fn is_test_fn(_fn: &ItemFn) -> bool {
+    _fn.attrs.iter().any(|a| {
+        a.path()
+            .segments
+            .last()
+            .map(|seg| seg.ident == "test")
+            .unwrap_or(false)
+    })
+}
+
+// Returns the number of test functions that the sets were applied to, including those in any
+// nested modules.
+fn apply_to_module(sets: &[Path], module: &mut ItemMod) -> Result<usize> {
+    let items = match &mut module.content {
+        Some((_, items)) => items,
+        None => {
+            return Err(Error::new_spanned(
+                &*module,
+                "cases_for_all can only be applied to inline modules",
+            ))
+        }
+    };
+    let mut applied = 0;
+
+    for item in items.iter_mut() {
+        match item {
+            Item::Fn(_fn) if is_test_fn(_fn) => {
+                _fn.attrs
+                    .insert(0, parse_quote!(#[simple_test_case::use_cases(#(#sets),*)]));
+                applied += 1;
+            }
+            Item::Mod(m) if m.content.is_some() => applied += apply_to_module(sets, m)?,
+            _ => (),
+        }
+    }
+
+    Ok(applied)
+}
+
+pub(crate) fn for_all_inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let UseCases { sets } = parse_macro_input!(args as UseCases);
+    let mut module = parse_macro_input!(input as ItemMod);
+
+    match apply_to_module(&sets, &mut module) {
+        Ok(0) => TokenStream::from(
+            Error::new(
+                module.ident.span(),
+                "cases_for_all module does not contain any test functions",
+            )
+            .into_compile_error(),
+        ),
+        Ok(_) => TokenStream::from(quote! { #module }),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacked_use_cases_are_merged() {
+        let mut sets = vec![parse_quote!(documents)];
+        let mut attrs: Vec<Attribute> = vec![
+            parse_quote!(#[use_cases(backends::all, extra)]),
+            parse_quote!(#[test]),
+            parse_quote!(#[simple_test_case::use_cases(more)]),
+        ];
+        extract_other_sets(&mut sets, &mut attrs).unwrap();
+        let expected: Vec<Path> = vec![
+            parse_quote!(documents),
+            parse_quote!(backends::all),
+            parse_quote!(extra),
+            parse_quote!(more),
+        ];
+
+        assert_eq!(sets, expected);
+        assert_eq!(attrs, vec![parse_quote!(#[test])]);
+    }
+
+    #[test]
+    fn define_cases_requires_cases() {
+        assert!(syn::parse2::<DefineCases>(quote!(documents, [])).is_err());
+        assert!(syn::parse2::<DefineCases>(quote!(documents, [(1) => "one"])).is_ok());
+    }
+
+    #[test]
+    fn cases_for_all_only_applies_to_test_functions() {
+        let mut module: ItemMod = parse_quote! {
+            mod backends {
+                fn helper() {}
+
+                #[test]
+                fn one(b: Backend) {}
+
+                mod nested {
+                    #[tokio::test]
+                    async fn two(b: Backend) {}
+                }
+            }
+        };
+        let sets: Vec<Path> = vec![parse_quote!(all_backends)];
+
+        assert_eq!(apply_to_module(&sets, &mut module).unwrap(), 2);
+        let items = &module.content.as_ref().unwrap().1;
+        match &items[0] {
+            Item::Fn(_fn) => assert!(_fn.attrs.is_empty()),
+            _ => panic!("expected a function"),
+        }
+    }
+}
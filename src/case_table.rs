@@ -0,0 +1,186 @@
+use crate::util::snake_case;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    Data, DeriveInput, Error, Fields, Ident, Path, Result, Token,
+};
+
+// #[case_table(rows = CASES, run = check, name = field)] on a struct deriving TestCases: `rows`
+// is a const or static array/slice of the struct, `run` is called with a reference to each row
+// and `name` is an optional field used to identify rows in failure messages.
+struct CaseTable {
+    rows: Path,
+    run: Path,
+    name: Option<Ident>,
+}
+
+impl Parse for CaseTable {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let mut rows = None;
+        let mut run = None;
+        let mut name = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "rows" => rows = Some(input.parse()?),
+                "run" => run = Some(input.parse()?),
+                "name" => name = Some(input.parse()?),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown case_table option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        match (rows, run) {
+            (Some(rows), Some(run)) => Ok(Self { rows, run, name }),
+            _ => Err(Error::new(
+                span,
+                "case_table requires both `rows = TABLE` and `run = function`",
+            )),
+        }
+    }
+}
+
+pub(crate) fn inner(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+fn derive(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "TestCases can only be derived for structs",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "TestCases can not be derived for generic structs",
+        ));
+    }
+
+    let mut tables = input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("case_table"));
+    let table = match (tables.next(), tables.next()) {
+        (Some(attr), None) => attr.parse_args::<CaseTable>()?,
+        (None, _) => {
+            return Err(Error::new(
+                input.ident.span(),
+                "TestCases requires a #[case_table(rows = TABLE, run = function)] attribute",
+            ))
+        }
+        (Some(_), Some(dup)) => {
+            return Err(Error::new(dup.span(), "duplicate case_table attribute"));
+        }
+    };
+
+    let CaseTable { rows, run, name } = table;
+    let label = match name {
+        Some(field) => {
+            let named = matches!(fields, Fields::Named(f) if f.named.iter().any(|f| f.ident.as_ref() == Some(&field)));
+            if !named {
+                return Err(Error::new(
+                    field.span(),
+                    format!("{} has no field named {}", input.ident, field),
+                ));
+            }
+            quote! { format!("{} ({})", i, row.#field) }
+        }
+        None => quote! { i.to_string() },
+    };
+    let test = format_ident!("{}_cases", snake_case(&input.ident.to_string()));
+    let ident = &input.ident;
+
+    // Each row is run in turn with any panics being caught so that every failing row can be
+    // reported at the end rather than stopping at the first failure.
+    Ok(quote! {
+        #[test]
+        fn #test() {
+            let rows: &[#ident] = &#rows;
+            let mut failed = Vec::new();
+
+            for (i, row) in rows.iter().enumerate() {
+                let res = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+                    let () = #run(row);
+                }));
+                if res.is_err() {
+                    failed.push(#label);
+                }
+            }
+
+            if !failed.is_empty() {
+                panic!(
+                    "{} of {} rows of {} failed: {}",
+                    failed.len(),
+                    rows.len(),
+                    stringify!(#rows),
+                    failed.join(", ")
+                );
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn struct_names_are_converted_to_snake_case() {
+        let input: DeriveInput = parse_quote! {
+            #[case_table(rows = CASES, run = check)]
+            struct HTTPRequestCase {
+                path: &'static str,
+            }
+        };
+        let tokens = derive(input).unwrap().to_string();
+
+        assert!(tokens.contains("fn http_request_case_cases"), "{}", tokens);
+    }
+
+    #[test]
+    fn case_table_requires_rows_and_run() {
+        let valid: Result<CaseTable> = syn::parse2(quote!(rows = CASES, run = check, name = name));
+        let missing_run: Result<CaseTable> = syn::parse2(quote!(rows = CASES));
+
+        assert!(valid.is_ok());
+        assert!(missing_run.is_err());
+    }
+
+    #[test]
+    fn name_must_be_a_field() {
+        let input: DeriveInput = parse_quote! {
+            #[case_table(rows = CASES, run = check, name = label)]
+            struct Case {
+                name: &'static str,
+            }
+        };
+
+        assert!(derive(input).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::util::{current_dir, expand_env, io_context, slugify_path};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::fs::read_dir;
+use syn::{parse_macro_input, parse_quote, Error, ItemFn, LitStr, Result};
+
+// The test files in a compile_fail_cases directory, ignoring the expected stderr files and
+// anything else that is stored alongside them.
+fn rust_files(mut file_names: Vec<String>) -> Vec<String> {
+    file_names
+        .retain(|name| matches!(name.rsplit_once('.'), Some((stem, "rs")) if !stem.is_empty()));
+    file_names.sort();
+
+    file_names
+}
+
+fn load_files(span: Span, dir: &str) -> Result<(String, Vec<String>)> {
+    let err = |msg: String| {
+        Error::new(
+            span,
+            format!("Error loading test cases from {}: {}", dir, msg),
+        )
+    };
+    let io_err = |e: std::io::Error| err(e.to_string());
+    let expanded = expand_env(dir).map_err(err)?;
+    let joined = current_dir().map_err(io_err)?.join(&expanded);
+    let abs_dir = joined
+        .canonicalize()
+        .map_err(io_context("canonicalize", &joined))
+        .map_err(io_err)?;
+    if !abs_dir.is_dir() {
+        return Err(err(format!("{} is not a directory", abs_dir.display())));
+    }
+
+    let mut file_names = Vec::new();
+    let entries = read_dir(&abs_dir)
+        .map_err(io_context("read directory", &abs_dir))
+        .map_err(io_err)?;
+    for entry in entries {
+        let entry = entry
+            .map_err(io_context("read an entry in directory", &abs_dir))
+            .map_err(io_err)?;
+        if entry.path().is_file() {
+            file_names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+
+    let files = rust_files(file_names);
+    if files.is_empty() {
+        return Err(err("no .rs files found".to_string()));
+    }
+
+    Ok((expanded, files))
+}
+
+// Each file is passed to the test function as a test case and trybuild is run against it once
+// the body of the function has completed, allowing for any additional setup to be performed
+// before the file is compiled.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(args as LitStr);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    if !original.sig.inputs.is_empty() {
+        return TokenStream::from(
+            Error::new_spanned(
+                &original.sig.inputs,
+                "compile_fail_cases test functions must not take any arguments",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let (expanded, files) = match load_files(dir.span(), &dir.value()) {
+        Ok(res) => res,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs = files.iter().map(|fname| {
+        let path = format!("{}/{}", expanded, fname);
+        let case = slugify_path(path.trim_end_matches(".rs"));
+
+        quote! { #[simple_test_case::test_case(#path; #case)] }
+    });
+
+    let block = &original.block;
+    original.block = parse_quote! {{
+        #[allow(clippy::let_unit_value)]
+        let __compile_fail_res = #block;
+        ::trybuild::TestCases::new().compile_fail(__compile_fail_path);
+        __compile_fail_res
+    }};
+    original
+        .sig
+        .inputs
+        .push(parse_quote!(__compile_fail_path: &str));
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_rust_files_are_cases() {
+        let files = ["b.rs", "b.stderr", "a.rs", "README.md", ".rs", "c.rs.bak"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(rust_files(files), vec!["a.rs", "b.rs"]);
+    }
+}
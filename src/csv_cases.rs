@@ -0,0 +1,326 @@
+use crate::util::{case_attr, param_names_and_types, read_fixture_file};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, Expr, Ident, ItemFn, LitStr, Result, Token, Type,
+};
+
+// csv_cases("path/to/file.csv") with an optional `name = "column"` to name each case
+struct CsvCases {
+    span: Span,
+    path: String,
+    name: Option<String>,
+}
+
+impl Parse for CsvCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let path: LitStr = input.parse()?;
+        let mut name = None;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "name" => name = Some(input.parse::<LitStr>()?.value()),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown csv_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            span,
+            path: path.value(),
+            name,
+        })
+    }
+}
+
+// A minimal RFC 4180 style parser: fields are comma separated, optionally double quoted (with ""
+// as an escaped quote) and records are separated by LF or CRLF. Blank lines are skipped.
+fn parse_csv(raw: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = raw.chars().peekable();
+    let mut in_quotes = false;
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '"' => return Err(format!("unexpected quote on line {}", line)),
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => (),
+            '\n' if !in_quotes => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+// String arguments are passed through as string literals, everything else is parsed as a Rust
+// expression so that the cell contents are type checked against the parameter type by rustc.
+fn cell_to_expr(cell: &str, ty: &Type) -> std::result::Result<Expr, String> {
+    let str_ty: Type = parse_quote!(&str);
+    let string_ty: Type = parse_quote!(String);
+
+    if *ty == str_ty {
+        Ok(parse_quote!(#cell))
+    } else if *ty == string_ty {
+        Ok(parse_quote!(#cell.to_string()))
+    } else {
+        syn::parse_str(cell).map_err(|e| format!("invalid value '{}': {}", cell, e))
+    }
+}
+
+fn resolve_cases(
+    span: Span,
+    records: Vec<Vec<String>>,
+    params: &[(String, Type)],
+    name_col: Option<&str>,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let mut records = records.into_iter();
+    let headers = match records.next() {
+        Some(headers) => headers,
+        None => return Err(Error::new(span, "csv file must contain a header row")),
+    };
+
+    let name_ix = match name_col {
+        Some(col) => match headers.iter().position(|h| h == col) {
+            Some(ix) => Some(ix),
+            None => return Err(Error::new(span, format!("no column named '{}'", col))),
+        },
+        None => None,
+    };
+
+    // Map by header name if every parameter has a matching column, or by position using every
+    // column other than the one used for naming cases if none of them do. Anything in between is
+    // most likely a typo in either the header or an argument name so it is reported as an error.
+    let by_name: Vec<Option<usize>> = params
+        .iter()
+        .map(|(p, _)| headers.iter().position(|h| h == p))
+        .collect();
+    let columns = if by_name.iter().all(|ix| ix.is_some()) {
+        by_name.into_iter().flatten().collect()
+    } else if by_name.iter().any(|ix| ix.is_some()) {
+        let unmatched_params: Vec<_> = params
+            .iter()
+            .zip(&by_name)
+            .filter(|(_, ix)| ix.is_none())
+            .map(|((p, _), _)| p.as_str())
+            .collect();
+        let unmatched_headers: Vec<_> = headers
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != name_ix && !by_name.contains(&Some(i)))
+            .map(|(_, h)| h.as_str())
+            .collect();
+
+        return Err(Error::new(
+            span,
+            format!(
+                "csv columns only partially match the test function arguments: no column for [{}] (unmatched columns: [{}])",
+                unmatched_params.join(", "),
+                unmatched_headers.join(", ")
+            ),
+        ));
+    } else {
+        let ixs: Vec<usize> = (0..headers.len()).filter(|&i| Some(i) != name_ix).collect();
+        if ixs.len() != params.len() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "csv columns do not match test function arguments: expected {} columns but found {}",
+                    params.len(),
+                    ixs.len()
+                ),
+            ));
+        }
+        ixs
+    };
+
+    records
+        .enumerate()
+        .map(|(i, record)| {
+            let row = i + 1;
+            if record.len() != headers.len() {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "row {} has {} fields but the header has {}",
+                        row,
+                        record.len(),
+                        headers.len()
+                    ),
+                ));
+            }
+
+            let args = columns
+                .iter()
+                .zip(params)
+                .map(|(&ix, (_, ty))| {
+                    cell_to_expr(&record[ix], ty).map_err(|e| {
+                        Error::new(
+                            span,
+                            format!("row {}, column '{}': {}", row, headers[ix], e),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<Expr>>>()?;
+
+            let name = match name_ix {
+                Some(ix) => record[ix].clone(),
+                None => format!("row_{}", row),
+            };
+
+            Ok(case_attr(&args, &name))
+        })
+        .collect()
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let CsvCases { span, path, name } = parse_macro_input!(args as CsvCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let res = param_names_and_types(&original, "csv_cases").and_then(|params| {
+        let (raw, abs_path) = read_fixture_file(span, &path)?;
+        let records = parse_csv(&raw)
+            .map_err(|e| Error::new(span, format!("invalid csv file {}: {}", path, e)))?;
+        let case_attrs = resolve_cases(span, records, &params, name.as_deref())?;
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the csv file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_simple_csv() {
+        let records = parse_csv("a,b\n1,2\r\n3,4").unwrap();
+
+        assert_eq!(
+            records,
+            vec![vec!["a", "b"], vec!["1", "2"], vec!["3", "4"]]
+        );
+    }
+
+    #[test]
+    fn parse_quoted_fields() {
+        let records = parse_csv("a,b\n\"x, \"\"y\"\"\",\"multi\nline\"\n").unwrap();
+
+        assert_eq!(records[1], vec!["x, \"y\"", "multi\nline"]);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        let records = parse_csv("a\n\n1\n\n").unwrap();
+
+        assert_eq!(records, vec![vec!["a"], vec!["1"]]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_an_error() {
+        assert!(parse_csv("a\n\"oops\n").is_err());
+    }
+
+    fn params(names: &[&str]) -> Vec<(String, Type)> {
+        names
+            .iter()
+            .map(|n| (n.to_string(), parse_quote!(usize)))
+            .collect()
+    }
+
+    #[test]
+    fn columns_are_mapped_by_name_or_position() {
+        let records = parse_csv("expected,n\n2,1\n").unwrap();
+        let by_name = resolve_cases(
+            Span::call_site(),
+            records.clone(),
+            &params(&["n", "expected"]),
+            None,
+        )
+        .unwrap();
+        let by_position =
+            resolve_cases(Span::call_site(), records, &params(&["a", "b"]), None).unwrap();
+
+        assert_eq!(
+            by_name[0].to_string(),
+            quote!(#[simple_test_case::test_case(1, 2; "row_1")]).to_string()
+        );
+        assert_eq!(
+            by_position[0].to_string(),
+            quote!(#[simple_test_case::test_case(2, 1; "row_1")]).to_string()
+        );
+    }
+
+    #[test]
+    fn partially_matching_headers_are_an_error() {
+        let records = parse_csv("name,n,expcted\na,1,2\n").unwrap();
+        let res = resolve_cases(
+            Span::call_site(),
+            records,
+            &params(&["n", "expected"]),
+            Some("name"),
+        );
+
+        match res {
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "csv columns only partially match the test function arguments: no column for [expected] (unmatched columns: [expcted])"
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
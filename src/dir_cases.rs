@@ -1,103 +1,2786 @@
-use crate::util::slugify_path;
+use crate::{
+    front_matter::{parse_front_matter, value_tokens},
+    json::Value,
+    util::{
+        current_dir, expand_env, fnv1a, io_context, parse_size, read_fixture_file, slug_ident,
+        slugify_path, stable_hash, workspace_root,
+    },
+};
 use proc_macro::TokenStream;
-use proc_macro2::Span;
+use proc_macro2::{Ident, Span};
 use quote::quote;
-use std::fs::read_dir;
+use regex::Regex;
+use std::{collections::HashMap, fs::read_dir, io::ErrorKind, path::PathBuf};
 use syn::{
+    bracketed, parenthesized,
     parse::{Parse, ParseStream},
     parse_macro_input, parse_quote,
     punctuated::Punctuated,
-    Error, FnArg, ItemFn, LitStr, Token, Type,
+    token, Error, Expr, FnArg, ItemFn, Lit, LitStr, Path, Token, Type, Visibility,
 };
 
 struct DirCases {
     span: Span,
     dirs: Vec<String>,
+    tags: Option<Vec<String>>,
+    manifest: Option<String>,
+    fixtures: Option<Path>,
+    format: Option<Format>,
+    discovery: Discovery,
+    naming: Naming,
+    max_size: Option<(u64, Oversized)>,
+    extra_args: Vec<Expr>,
+    each: Vec<(Ident, Expr)>,
+    stem: bool,
+    ignore_matching: Option<Regex>,
+    parse_with: Option<Path>,
+    sample: Option<Sample>,
+    prefix: Option<String>,
+    check_drift: bool,
+    common: Option<String>,
+    split: Option<String>,
+    front_matter: Option<Vec<Ident>>,
+    path_arg: PathArg,
+}
+
+// Only the first n fixtures are run by default with the remainder being ignored, unless the
+// (optional) cfg predicate holds: sample = 10, sample_unless = cfg(feature = "full-corpus")
+struct Sample {
+    n: usize,
+    unless: Option<proc_macro2::TokenStream>,
+}
+
+// A labelled value for running every fixture under: `label = expr`
+struct EachValue {
+    label: Ident,
+    value: Expr,
+}
+
+impl Parse for EachValue {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let label: Ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let value: Expr = input.parse()?;
+
+        Ok(Self { label, value })
+    }
+}
+
+// What to do with fixtures larger than the max_size option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Oversized {
+    // Refuse to compile
+    Error,
+    // Generate an ignored test that reads the fixture at runtime rather than embedding it
+    Ignore,
+}
+
+impl Parse for Oversized {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let lit: LitStr = input.parse()?;
+        match lit.value().as_str() {
+            "error" => Ok(Self::Error),
+            "ignore" => Ok(Self::Ignore),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "unknown oversized behaviour: {} (expected \"error\" or \"ignore\")",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+// The form of the path passed to the test function for each fixture
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PathArg {
+    // Relative to the directory (or manifest) the fixture was found in: `case.txt`
+    Name,
+    // The directory as given to dir_cases followed by the file: `tests/data/case.txt`
+    #[default]
+    Relative,
+    // The canonical absolute path of the fixture
+    Absolute,
+}
+
+impl Parse for PathArg {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let lit: LitStr = input.parse()?;
+        match lit.value().as_str() {
+            "name" => Ok(Self::Name),
+            "relative" => Ok(Self::Relative),
+            "absolute" => Ok(Self::Absolute),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "unknown path_arg form: {} (expected \"name\", \"relative\" or \"absolute\")",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+impl PathArg {
+    fn for_fixture(&self, fixture: &Fixture) -> String {
+        match self {
+            Self::Name if fixture.rel_dir.is_empty() => fixture.file_name.clone(),
+            Self::Name => format!("{}/{}", fixture.rel_dir, fixture.file_name),
+            Self::Relative => fixture.path.clone(),
+            Self::Absolute => fixture.abs_path.clone(),
+        }
+    }
+}
+
+// Which files within each directory are used as fixtures
+#[derive(Debug, Clone)]
+struct Discovery {
+    extensions: Option<Vec<String>>,
+    matching: Option<Regex>,
+    recursive: bool,
+    follow_symlinks: bool,
+    // Resolve directories relative to the workspace root rather than the current directory
+    workspace_root: bool,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self {
+            extensions: None,
+            matching: None,
+            recursive: false,
+            follow_symlinks: true,
+            workspace_root: false,
+        }
+    }
+}
+
+impl Discovery {
+    // Whether any of the options controlling which files are found differ from the defaults
+    fn filters_files(&self) -> bool {
+        self.extensions.is_some()
+            || self.matching.is_some()
+            || self.recursive
+            || !self.follow_symlinks
+    }
+
+    // Whether a file with the given name is used as a fixture
+    fn wants(&self, file_name: &str) -> bool {
+        let wanted_ext = match (
+            &self.extensions,
+            std::path::Path::new(file_name).extension(),
+        ) {
+            (None, _) => true,
+            (Some(exts), Some(ext)) => exts.iter().any(|e| ext == e.as_str()),
+            (Some(_), None) => false,
+        };
+        let wanted_name = match &self.matching {
+            None => true,
+            Some(re) => re.is_match(file_name),
+        };
+
+        wanted_ext && wanted_name
+    }
+
+    fn resolve(&self, dir: &str) -> Result<String, String> {
+        let expanded = expand_env(dir)?;
+
+        match self.workspace_root {
+            true => Ok(format!("{}/{}", workspace_root()?, expanded)),
+            false => Ok(expanded),
+        }
+    }
+}
+
+// How generated case names are derived from the path of each fixture file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Naming {
+    strip_extension: bool,
+    strip_prefix: Option<StripPrefix>,
+    nested: bool,
+    group_by_extension: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StripPrefix {
+    // Drop the directory path that is shared by all fixtures
+    Dir,
+    // Drop the directory path and the given prefix of the file name
+    Literal(String),
+}
+
+impl Parse for StripPrefix {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        match input.parse::<Lit>()? {
+            Lit::Bool(b) if b.value => Ok(Self::Dir),
+            Lit::Str(s) => Ok(Self::Literal(s.value())),
+            other => Err(Error::new(
+                other.span(),
+                "strip_prefix must be either `true` or a string literal",
+            )),
+        }
+    }
+}
+
+impl Naming {
+    // When nesting, subdirectories become modules so the name only needs to identify the file.
+    // Grouping by extension places each case inside of an outer module named after the extension.
+    fn case_module(&self, fixture: &Fixture) -> Vec<String> {
+        let mut module = Vec::new();
+        if self.group_by_extension {
+            if let Some((_, ext)) = fixture
+                .file_name
+                .rsplit_once('.')
+                .filter(|(s, _)| !s.is_empty())
+            {
+                module.push(slugify_path(ext));
+            }
+        }
+
+        match fixture.rel_dir.as_str() {
+            rel_dir if self.nested && !rel_dir.is_empty() => {
+                module.extend(rel_dir.split('/').map(slugify_path));
+            }
+            _ => (),
+        }
+
+        module
+    }
+
+    fn case_name(&self, fixture: &Fixture) -> String {
+        slugify_path(&self.raw_case_name(fixture))
+    }
+
+    // The case name prior to being converted into a valid identifier
+    fn raw_case_name(&self, fixture: &Fixture) -> String {
+        if let Some(name) = &fixture.name {
+            return name.clone();
+        }
+
+        let mut file_name = fixture.file_name.as_str();
+        let sub_dir = |name: &str| match fixture.rel_dir.as_str() {
+            _ if self.nested => name.to_string(),
+            "" => name.to_string(),
+            rel_dir => format!("{}/{}", rel_dir, name),
+        };
+
+        if self.strip_extension {
+            if let Some((stem, _)) = file_name.rsplit_once('.').filter(|(s, _)| !s.is_empty()) {
+                file_name = stem;
+            }
+        }
+
+        let name = match &self.strip_prefix {
+            None if self.nested => file_name.to_string(),
+            None if fixture.dir.is_empty() => sub_dir(file_name),
+            None => format!("{}/{}", fixture.dir, sub_dir(file_name)),
+            Some(StripPrefix::Dir) => sub_dir(file_name),
+            Some(StripPrefix::Literal(prefix)) => {
+                sub_dir(file_name.strip_prefix(prefix.as_str()).unwrap_or(file_name))
+            }
+        };
+
+        name
+    }
+}
+
+// Distinct file names can slugify to the same case name (e.g. `report-2024.txt` and
+// `report_2024.txt`), in which case each of the colliding names is given a suffix derived from
+// its unslugified name. The suffix only depends on the name of the fixture itself so adding or
+// removing other fixtures will not rename existing cases. Fixtures with identical unslugified
+// names are left alone to be reported by check_for_collisions.
+fn dedup_slugs(names: &mut [(Vec<String>, String, String)]) {
+    let mut raw_names: HashMap<(&[String], &str), Vec<&str>> = HashMap::new();
+    for (module, name, raw) in names.iter() {
+        let raws = raw_names.entry((module, name)).or_default();
+        if !raws.contains(&raw.as_str()) {
+            raws.push(raw);
+        }
+    }
+
+    let colliding: Vec<(Vec<String>, String)> = raw_names
+        .into_iter()
+        .filter(|(_, raws)| raws.len() > 1)
+        .map(|((module, name), _)| (module.to_vec(), name.to_string()))
+        .collect();
+
+    for (module, name, raw) in names.iter_mut() {
+        if colliding.iter().any(|(m, n)| m == module && n == name) {
+            *name = format!("{}_{}", name, stable_hash(raw));
+        }
+    }
+}
+
+// Serialization formats that fixture files can be deserialized from into the type of the contents
+// argument of the test function. The generated code calls out to the relevant crate so it needs to
+// be a dependency of the crate using `dir_cases`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Parse for Format {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let lit: LitStr = input.parse()?;
+        match lit.value().as_str() {
+            "json" => Ok(Self::Json),
+            #[cfg(feature = "yaml")]
+            "yaml" => Ok(Self::Yaml),
+            #[cfg(not(feature = "yaml"))]
+            "yaml" => Err(Error::new(
+                lit.span(),
+                "yaml fixtures require the `yaml` feature of simple_test_case to be enabled",
+            )),
+            #[cfg(feature = "toml")]
+            "toml" => Ok(Self::Toml),
+            #[cfg(not(feature = "toml"))]
+            "toml" => Err(Error::new(
+                lit.span(),
+                "toml fixtures require the `toml` feature of simple_test_case to be enabled",
+            )),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "unknown fixture format: {} (expected \"json\", \"yaml\" or \"toml\")",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+impl Format {
+    fn deserialize(
+        &self,
+        ty: &Type,
+        path: &str,
+        contents: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let from_str = match self {
+            Self::Json => quote! { ::serde_json::from_str::<#ty> },
+            #[cfg(feature = "yaml")]
+            Self::Yaml => quote! { ::serde_yaml::from_str::<#ty> },
+            #[cfg(feature = "toml")]
+            Self::Toml => quote! { ::toml::from_str::<#ty> },
+        };
+
+        quote! {
+            #from_str(#contents).unwrap_or_else(|e| {
+                panic!("unable to deserialize test fixture {}: {}", #path, e)
+            })
+        }
+    }
+}
+
+// Compressed fixtures are detected by their file extension and decompressed at runtime before
+// being passed to the test function. As with formats, the generated code calls out to the
+// relevant crate so it needs to be a dependency of the crate using `dir_cases`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn for_file(file_name: &str) -> Option<Self> {
+        match file_name.rsplit_once('.') {
+            Some((_, "gz")) => Some(Self::Gzip),
+            Some((_, "zst")) => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    fn feature(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        match self {
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    // Decompress the given bytes into an owned String (or Vec<u8> if the contents argument is a
+    // &[u8]) which is borrowed as the contents argument. The owned value is a temporary in the
+    // initializer of the argument binding so it lives for as long as the test body, but formats
+    // and parsers are only given a borrow for the duration of the call so they need to produce
+    // owned values.
+    fn decompress(
+        &self,
+        span: Span,
+        path: &str,
+        bytes: proc_macro2::TokenStream,
+        as_bytes: bool,
+    ) -> syn::Result<proc_macro2::TokenStream> {
+        if !self.enabled() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "compressed fixture {} requires the `{}` feature of simple_test_case to be enabled",
+                    path,
+                    self.feature()
+                ),
+            ));
+        }
+
+        let decompressed = match self {
+            Self::Gzip => quote! {
+                {
+                    let mut buf = Vec::new();
+                    ::std::io::Read::read_to_end(&mut ::flate2::read::GzDecoder::new(&#bytes[..]), &mut buf)
+                        .map(|_| buf)
+                }
+            },
+            Self::Zstd => quote! { ::zstd::stream::decode_all(&#bytes[..]) },
+        };
+
+        let decompressed = quote! {
+            #decompressed
+                .unwrap_or_else(|e| panic!("unable to decompress test fixture {}: {}", #path, e))
+        };
+
+        match as_bytes {
+            true => Ok(quote! { &#decompressed }),
+            false => Ok(quote! {
+                &::std::string::String::from_utf8(#decompressed)
+                    .unwrap_or_else(|e| panic!("test fixture {} is not valid UTF-8: {}", #path, e))
+            }),
+        }
+    }
 }
 
 impl Parse for DirCases {
     fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
         let span = input.span();
+        let mut dirs = Vec::new();
+        let mut tags = Vec::new();
+        let mut manifest = None;
+        let mut fixtures = None;
+        let mut format = None;
+        let mut discovery = Discovery::default();
+        let mut naming = Naming::default();
+        let mut max_size = None;
+        let mut oversized = None;
+        let mut ignore_over = None;
+        let mut extra_args = Vec::new();
+        let mut each = Vec::new();
+        let mut stem = false;
+        let mut ignore_matching = None;
+        let mut parse_with = None;
+        let mut sample = None;
+        let mut sample_unless = None;
+        let mut prefix = None;
+        let mut check_drift = false;
+        let mut common = None;
+        let mut split = None;
+        let mut front_matter = None;
+        let mut path_arg = PathArg::default();
+
+        while !input.is_empty() {
+            if input.peek(LitStr) {
+                let dir: LitStr = input.parse()?;
+                dirs.push(dir.value());
+            } else if input.peek2(token::Paren) && input.fork().parse::<Ident>()? == "tagged" {
+                // tagged(tag = "path/to/dir", ...)
+                let _: Ident = input.parse()?;
+                let content;
+                parenthesized!(content in input);
+                while !content.is_empty() {
+                    let tag: Ident = content.parse()?;
+                    let _: Token![=] = content.parse()?;
+                    let dir: LitStr = content.parse()?;
+                    dirs.push(dir.value());
+                    tags.push(tag.to_string());
+                    if !content.is_empty() {
+                        let _: Token![,] = content.parse()?;
+                    }
+                }
+            } else {
+                let key: Ident = input.parse()?;
+                let _: Token![=] = input.parse()?;
+                match key.to_string().as_str() {
+                    "fixtures" => fixtures = Some(input.parse()?),
+                    "manifest" => manifest = Some(input.parse::<LitStr>()?.value()),
+                    "format" => format = Some(input.parse()?),
+                    "extensions" => {
+                        let content;
+                        bracketed!(content in input);
+                        let exts: Punctuated<LitStr, Token![,]> =
+                            Punctuated::parse_terminated(&content)?;
+                        discovery.extensions = Some(exts.iter().map(|e| e.value()).collect());
+                    }
+                    "matching" => discovery.matching = Some(parse_regex(input, "matching")?),
+                    "ignore_matching" => {
+                        ignore_matching = Some(parse_regex(input, "ignore_matching")?)
+                    }
+                    "recursive" => discovery.recursive = input.parse::<syn::LitBool>()?.value,
+                    "workspace_root" => {
+                        discovery.workspace_root = input.parse::<syn::LitBool>()?.value
+                    }
+                    "follow_symlinks" => {
+                        discovery.follow_symlinks = input.parse::<syn::LitBool>()?.value
+                    }
+                    "strip_extension" => {
+                        naming.strip_extension = input.parse::<syn::LitBool>()?.value
+                    }
+                    "strip_prefix" => naming.strip_prefix = Some(input.parse()?),
+                    "nested" => naming.nested = input.parse::<syn::LitBool>()?.value,
+                    "group_by_extension" => {
+                        naming.group_by_extension = input.parse::<syn::LitBool>()?.value
+                    }
+                    "max_size" => {
+                        let lit: LitStr = input.parse()?;
+                        let size =
+                            parse_size(&lit.value()).map_err(|e| Error::new(lit.span(), e))?;
+                        max_size = Some(size);
+                    }
+                    "oversized" => oversized = Some(input.parse()?),
+                    // Shorthand for: max_size = "..", oversized = "ignore"
+                    "ignore_over" => {
+                        let lit: LitStr = input.parse()?;
+                        let size =
+                            parse_size(&lit.value()).map_err(|e| Error::new(lit.span(), e))?;
+                        ignore_over = Some(size);
+                    }
+                    "args" => {
+                        let content;
+                        parenthesized!(content in input);
+                        let args: Punctuated<Expr, Token![,]> =
+                            Punctuated::parse_terminated(&content)?;
+                        extra_args = args.into_iter().collect();
+                    }
+                    "parse_with" => parse_with = Some(input.parse()?),
+                    "stem" => stem = input.parse::<syn::LitBool>()?.value,
+                    "prefix" => prefix = Some(input.parse::<LitStr>()?.value()),
+                    "check_drift" => check_drift = input.parse::<syn::LitBool>()?.value,
+                    "common" => common = Some(input.parse::<LitStr>()?.value()),
+                    "front_matter" => front_matter = parse_front_matter_keys(input)?,
+                    "path_arg" => path_arg = input.parse()?,
+                    "split" => {
+                        let lit: LitStr = input.parse()?;
+                        if lit.value().is_empty() {
+                            return Err(Error::new(
+                                lit.span(),
+                                "split delimiter must not be empty",
+                            ));
+                        }
+                        split = Some(lit.value());
+                    }
+                    "sample" => sample = Some(input.parse::<syn::LitInt>()?.base10_parse()?),
+                    "sample_unless" => {
+                        let cfg: Ident = input.parse()?;
+                        if cfg != "cfg" {
+                            return Err(Error::new(
+                                cfg.span(),
+                                "sample_unless must be of the form cfg(...)",
+                            ));
+                        }
+                        let content;
+                        parenthesized!(content in input);
+                        sample_unless = Some(content.parse::<proc_macro2::TokenStream>()?);
+                    }
+                    "each" => {
+                        let content;
+                        bracketed!(content in input);
+                        let values: Punctuated<EachValue, Token![,]> =
+                            Punctuated::parse_terminated(&content)?;
+                        each = values.into_iter().map(|v| (v.label, v.value)).collect();
+                    }
+                    other => {
+                        return Err(Error::new(
+                            key.span(),
+                            format!("unknown dir_cases option: {}", other),
+                        ))
+                    }
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        match (dirs.is_empty(), &manifest) {
+            (true, None) => {
+                return Err(Error::new(
+                    span,
+                    "at least one directory or a manifest must be provided",
+                ))
+            }
+            (false, Some(_)) => {
+                return Err(Error::new(
+                    span,
+                    "directories cannot be given when using a manifest",
+                ))
+            }
+            (true, Some(_)) if discovery.filters_files() => {
+                return Err(Error::new(
+                    span,
+                    "extensions, matching, recursive and follow_symlinks cannot be used with a manifest",
+                ))
+            }
+            _ => (),
+        }
+
+        let tags = match tags.len() {
+            0 => None,
+            n if n == dirs.len() => Some(tags),
+            _ => {
+                return Err(Error::new(
+                    span,
+                    "either all directories must be given a tag or none of them",
+                ))
+            }
+        };
+
+        if format.is_some() && parse_with.is_some() {
+            return Err(Error::new(
+                span,
+                "format and parse_with cannot be used together",
+            ));
+        }
+
+        let max_size = match (max_size, oversized, ignore_over) {
+            (None, _, None) => None,
+            (None, None, Some(size)) => Some((size, Oversized::Ignore)),
+            (Some(size), oversized, None) => Some((size, oversized.unwrap_or(Oversized::Error))),
+            (_, _, Some(_)) => {
+                return Err(Error::new(
+                    span,
+                    "ignore_over cannot be used with max_size or oversized",
+                ))
+            }
+        };
+
+        if check_drift && manifest.is_some() {
+            return Err(Error::new(
+                span,
+                "check_drift cannot be used with a manifest",
+            ));
+        }
+
+        if sample_unless.is_some() && sample.is_none() {
+            return Err(Error::new(span, "sample_unless requires sample to be set"));
+        }
+
+        if naming.nested && !discovery.recursive {
+            return Err(Error::new(span, "nested = true requires recursive = true"));
+        }
+
+        Ok(Self {
+            span,
+            dirs,
+            tags,
+            manifest,
+            fixtures,
+            format,
+            discovery,
+            naming,
+            max_size,
+            extra_args,
+            each,
+            stem,
+            ignore_matching,
+            parse_with,
+            sample: sample.map(|n| Sample {
+                n,
+                unless: sample_unless,
+            }),
+            prefix,
+            check_drift,
+            common,
+            split,
+            front_matter,
+            path_arg,
+        })
+    }
+}
+
+// Either `front_matter = true` or a list of keys whose values should be passed to the test
+// function: `front_matter = [expected, retries]`
+fn parse_front_matter_keys(input: ParseStream<'_>) -> syn::parse::Result<Option<Vec<Ident>>> {
+    if input.peek(syn::LitBool) {
+        let enabled = input.parse::<syn::LitBool>()?.value;
+        return Ok(enabled.then(Vec::new));
+    }
+
+    let content;
+    bracketed!(content in input);
+    let keys: Punctuated<Ident, Token![,]> = Punctuated::parse_terminated(&content)?;
+    for key in keys.iter() {
+        if FRONT_MATTER_ATTRS.contains(&key.to_string().as_str()) {
+            return Err(Error::new(
+                key.span(),
+                format!(
+                    "{} is used to set a test attribute and can't be passed as an argument",
+                    key
+                ),
+            ));
+        }
+    }
+
+    Ok(Some(keys.into_iter().collect()))
+}
+
+// Front matter keys that are used to set attributes on the generated test case
+const FRONT_MATTER_ATTRS: [&str; 2] = ["ignore", "should_panic"];
+
+fn parse_regex(input: ParseStream<'_>, opt: &str) -> syn::parse::Result<Regex> {
+    let lit: LitStr = input.parse()?;
+
+    Regex::new(&lit.value())
+        .map_err(|e| Error::new(lit.span(), format!("invalid {} pattern: {}", opt, e)))
+}
+
+// A shared module of fixture contents of the form: dir_fixtures!(vis mod name = "dir", ...)
+struct DirFixtures {
+    span: Span,
+    vis: Visibility,
+    module: Ident,
+    dirs: Vec<String>,
+}
+
+impl Parse for DirFixtures {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let span = input.span();
+        let vis: Visibility = input.parse()?;
+        let _: Token![mod] = input.parse()?;
+        let module: Ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
         let dirs: Punctuated<LitStr, Token![,]> = Punctuated::parse_separated_nonempty(input)?;
         let dirs: Vec<String> = dirs.iter().map(|d| d.value()).collect();
 
-        Ok(Self { span, dirs })
+        Ok(Self {
+            span,
+            vis,
+            module,
+            dirs,
+        })
+    }
+}
+
+// A single file found in one of the directories passed to dir_cases. When searching recursively,
+// rel_dir is the path of the subdirectory containing the file relative to dir. Fixtures listed in
+// a manifest may also be given an explicit case name.
+struct Fixture {
+    root: usize,
+    dir: String,
+    rel_dir: String,
+    file_name: String,
+    path: String,
+    abs_path: String,
+    size: u64,
+    name: Option<String>,
+}
+
+// A file found while walking a fixture directory, with rel_dir being the path of the subdirectory
+// containing it relative to the directory being walked
+struct DirFile {
+    rel_dir: String,
+    file_name: String,
+    valid_utf8: bool,
+    path: PathBuf,
+}
+
+impl DirFile {
+    fn rel_path(&self) -> String {
+        match self.rel_dir.as_str() {
+            "" => self.file_name.clone(),
+            rel_dir => format!("{}/{}", rel_dir, self.file_name),
+        }
     }
 }
 
-fn get_cases(dir: &str) -> Result<Vec<(String, String, String)>, std::io::Error> {
-    let mut cases = vec![];
-    let root = std::env::current_dir()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string();
+fn non_utf8_error(path: &std::path::Path) -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "file name is not valid UTF-8: {} (rename the file or exclude it using the extensions or matching options)",
+            path.display()
+        ),
+    )
+}
+
+// List every file in a directory, which is shared between finding fixtures and checking for drift
+// so that both see the same files. Symlinks are either followed or skipped entirely depending on
+// the follow_symlinks option rather than relying on the platform specific behaviour of read_dir.
+// When following symlinks, the canonical paths of the directories currently being searched are
+// tracked in `ancestors` so that cycles are reported rather than recursing forever.
+fn walk_dir(
+    dir: &str,
+    rel_dir: &str,
+    discovery: &Discovery,
+    ancestors: &mut Vec<PathBuf>,
+    files: &mut Vec<DirFile>,
+) -> Result<(), std::io::Error> {
+    let search_dir = match rel_dir {
+        "" => dir.to_string(),
+        rel_dir => format!("{}/{}", dir, rel_dir),
+    };
+    let search_path = std::path::Path::new(&search_dir);
 
-    for entry in read_dir(dir)? {
-        let entry = entry?;
+    let entries = read_dir(search_path).map_err(io_context("read directory", search_path))?;
+    for entry in entries {
+        let entry = entry.map_err(io_context("read an entry in directory", search_path))?;
         let path = entry.path();
-        if path.is_file() {
-            let fname = entry.file_name().into_string().unwrap();
-            let case = slugify_path(&format!("{}/{}", dir, fname));
+        // Fixtures are embedded using include_str which requires a UTF-8 path, but a file with a
+        // non-UTF-8 name is only an error if it would otherwise have been used as a fixture
+        let (fname, valid_utf8) = match entry.file_name().into_string() {
+            Ok(fname) => (fname, true),
+            Err(os_str) => (os_str.to_string_lossy().into_owned(), false),
+        };
 
-            cases.push((
-                format!("{}/{}", dir, fname),
-                format!("{}/{}/{}", root, dir, fname),
-                case,
-            ));
+        let file_type = entry
+            .file_type()
+            .map_err(io_context("read the file type of", &path))?;
+        if file_type.is_symlink() {
+            if !discovery.follow_symlinks {
+                continue;
+            }
+            if std::fs::metadata(&path).is_err() {
+                return Err(std::io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("dangling symlink: {}", path.display()),
+                ));
+            }
+        }
+
+        if path.is_dir() && discovery.recursive {
+            let canonical = path
+                .canonicalize()
+                .map_err(io_context("canonicalize", &path))?;
+            if ancestors.contains(&canonical) {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("symlink cycle: {}", path.display()),
+                ));
+            }
+
+            if !valid_utf8 {
+                return Err(non_utf8_error(&path));
+            }
+            let sub_dir = match rel_dir {
+                "" => fname,
+                rel_dir => format!("{}/{}", rel_dir, fname),
+            };
+            ancestors.push(canonical);
+            walk_dir(dir, &sub_dir, discovery, ancestors, files)?;
+            ancestors.pop();
+            continue;
+        }
+
+        if path.is_file() {
+            files.push(DirFile {
+                rel_dir: rel_dir.to_string(),
+                file_name: fname,
+                valid_utf8,
+                path,
+            });
         }
     }
 
-    Ok(cases)
+    Ok(())
 }
 
-fn has_correct_args(_fn: &ItemFn) -> bool {
-    let str_ty: Type = parse_quote!(&str);
-    let valid = |fnarg: &FnArg| matches!(fnarg, FnArg::Typed(pt) if *pt.ty == str_ty);
+// `canonical_dir` is the canonical form of `dir` which is used for the absolute paths of the
+// fixtures that are found.
+fn get_cases(
+    dir: &str,
+    canonical_dir: &std::path::Path,
+    discovery: &Discovery,
+    cases: &mut Vec<Fixture>,
+) -> Result<(), std::io::Error> {
+    let mut files = Vec::new();
+    walk_dir(
+        dir,
+        "",
+        discovery,
+        &mut vec![canonical_dir.to_path_buf()],
+        &mut files,
+    )?;
+
+    for file in files.into_iter() {
+        if !discovery.wants(&file.file_name) {
+            continue;
+        }
+        if !file.valid_utf8 {
+            return Err(non_utf8_error(&file.path));
+        }
+        let path = match file.rel_dir.as_str() {
+            "" => format!("{}/{}", dir, file.file_name),
+            rel_dir => format!("{}/{}/{}", dir, rel_dir, file.file_name),
+        };
+
+        cases.push(Fixture {
+            root: 0,
+            dir: dir.to_string(),
+            path,
+            abs_path: canonical_dir
+                .join(&file.rel_dir)
+                .join(&file.file_name)
+                .to_string_lossy()
+                .into_owned(),
+            size: std::fs::metadata(&file.path)
+                .map_err(io_context("read the metadata of", &file.path))?
+                .len(),
+            rel_dir: file.rel_dir,
+            file_name: file.file_name,
+            name: None,
+        });
+    }
 
-    _fn.sig.inputs.len() == 2 && _fn.sig.inputs.iter().all(valid)
+    Ok(())
 }
 
-pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
-    let DirCases { span, dirs } = parse_macro_input!(args as DirCases);
-    let original = parse_macro_input!(input as ItemFn);
+// Fixtures are sorted by path so that the generated cases do not depend on the order in which
+// the filesystem returns directory entries.
+fn get_all_cases(span: Span, dirs: &[String], discovery: &Discovery) -> syn::Result<Vec<Fixture>> {
+    let mut case_details = Vec::new();
+
+    for (root, dir) in dirs.iter().enumerate() {
+        let expanded = discovery.resolve(dir).map_err(|e| Error::new(span, e))?;
+        if let Err(e) = std::fs::metadata(&expanded).and_then(|m| match m.is_dir() {
+            true => Ok(()),
+            false => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "not a directory",
+            )),
+        }) {
+            return Err(missing_dir_error(span, dir, &expanded, e));
+        }
 
-    if !has_correct_args(&original) {
-        return TokenStream::from(
+        // The directory is canonicalized up front so that the paths we embed are not affected by
+        // symlinks or `..` components and any IO error is reported against the entry involved.
+        let err = |e: std::io::Error| {
             Error::new(
                 span,
-                "dir_cases test functions must accept (path: &str, contents: &str) as arguments"
-                    .to_string(),
+                format!("Error loading test cases from {}: {}", dir, e),
             )
-            .into_compile_error(),
-        );
+        };
+        let expanded_path = std::path::Path::new(&expanded);
+        let canonical = expanded_path
+            .canonicalize()
+            .map_err(io_context("canonicalize", expanded_path))
+            .map_err(err)?;
+        let n_before = case_details.len();
+        get_cases(&expanded, &canonical, discovery, &mut case_details).map_err(err)?;
+
+        // Case names are based on the directory as written rather than the expanded path so that
+        // they don't depend on the environment of the machine running the tests.
+        for fixture in case_details[n_before..].iter_mut() {
+            fixture.root = root;
+            fixture.dir = dir.clone();
+        }
     }
+    case_details.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let mut case_details = Vec::new();
+    Ok(case_details)
+}
 
-    for dir in dirs.iter() {
-        match get_cases(dir) {
-            Ok(details) => case_details.extend(details),
-            Err(e) => {
-                return TokenStream::from(
-                    Error::new(span, format!("Error loading test cases: {}", e))
-                        .into_compile_error(),
-                )
-            }
+// Relative paths are resolved against the current directory of the compiler rather than the
+// file containing the test, which is a common source of confusion so we report exactly where we
+// looked along with how to fix things if the directory exists relative to the crate instead.
+fn missing_dir_error(span: Span, dir: &str, resolved: &str, e: std::io::Error) -> Error {
+    let cwd = std::env::current_dir()
+        .map(|d| d.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "<unknown>".to_string());
+    let searched = std::path::Path::new(&cwd).join(resolved);
+    let mut msg = format!(
+        "Error loading test cases from {}: {}\n  searched: {}\n  current directory: {}",
+        dir,
+        e,
+        searched.display(),
+        cwd
+    );
+
+    if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+        msg.push_str(&format!("\n  CARGO_MANIFEST_DIR: {}", manifest_dir));
+        let from_manifest = std::path::Path::new(&manifest_dir).join(resolved);
+        if from_manifest != searched && from_manifest.is_dir() {
+            msg.push_str(&format!(
+                "\nhint: {} exists relative to CARGO_MANIFEST_DIR: try \"${{CARGO_MANIFEST_DIR}}/{}\"",
+                dir, dir
+            ));
+            return Error::new(span, msg);
+        }
+    }
+
+    msg.push_str(
+        "\nhint: relative paths are resolved from the current directory of the compiler (normally \
+         the root of your cargo workspace) rather than the file containing the test",
+    );
+
+    Error::new(span, msg)
+}
+
+// A manifest lists the fixtures to use (relative to the directory containing the manifest) one
+// per line, optionally followed by an explicit case name: `path/to/file = name`. Blank lines and
+// lines starting with `#` are skipped. Fixtures are used in the order they are listed.
+fn get_manifest_cases(
+    span: Span,
+    manifest: &str,
+    discovery: &Discovery,
+) -> syn::Result<(Vec<Fixture>, String)> {
+    let resolved = discovery
+        .resolve(manifest)
+        .map_err(|e| Error::new(span, e))?;
+    let (raw, abs_path) = read_fixture_file(span, &resolved)?;
+    let parent = |p: &str| {
+        std::path::Path::new(p)
+            .parent()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    // As with directories, case names use the path as written and the path passed to each test
+    // uses the expanded path.
+    let dir = parent(manifest);
+    let expanded_dir = parent(&resolved);
+    let abs_dir = std::path::Path::new(&abs_path)
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let mut cases = Vec::new();
+
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (entry, name) = match line.rsplit_once(" = ") {
+            Some((entry, name)) => (entry.trim(), Some(name.trim().to_string())),
+            None => (line, None),
+        };
+        let err = |msg: String| {
+            Error::new(
+                span,
+                format!(
+                    "invalid manifest entry on line {} of {}: {}",
+                    i + 1,
+                    manifest,
+                    msg
+                ),
+            )
+        };
+
+        let entry_path = abs_dir.join(entry);
+        let metadata = std::fs::metadata(&entry_path)
+            .map_err(|e| err(format!("unable to read {}: {}", entry, e)))?;
+        if !metadata.is_file() {
+            return Err(err(format!("{} is not a file", entry)));
+        }
+
+        let (rel_dir, file_name) = match entry.rsplit_once('/') {
+            Some((rel_dir, file_name)) => (rel_dir.to_string(), file_name.to_string()),
+            None => (String::new(), entry.to_string()),
+        };
+        let path = match expanded_dir.as_str() {
+            "" => entry.to_string(),
+            dir => format!("{}/{}", dir, entry),
         };
+
+        cases.push(Fixture {
+            root: 0,
+            dir: dir.clone(),
+            rel_dir,
+            file_name,
+            path,
+            abs_path: entry_path.to_string_lossy().into_owned(),
+            size: metadata.len(),
+            name,
+        });
     }
 
-    let case_attrs: Vec<_> = case_details
-        .into_iter()
-        .map(|(path, abs_path, case)| {
-            quote! {
-                #[simple_test_case::test_case(#path, include_str!(#abs_path); #case)]
-            }
-        })
-        .collect();
+    Ok((cases, abs_path))
+}
 
-    TokenStream::from(quote! {
-        #(#case_attrs)*
-        #original
-    })
+// Files in different directories can end up with the same case name (for example `a/case.txt`
+// and `b/case.txt` when stripping the directory prefix) which would otherwise result in a
+// confusing duplicate definition error inside of the generated code.
+fn check_for_collisions(span: Span, names: &[(String, String)]) -> syn::Result<()> {
+    let mut seen: HashMap<&str, &str> = HashMap::new();
+
+    for (name, path) in names.iter() {
+        if let Some(other) = seen.insert(name, path) {
+            return Err(Error::new(
+                span,
+                format!(
+                    "test case name `{}` is generated for both {} and {}: use different naming options or rename one of the files",
+                    name, other, path
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Shared fixture contents are keyed by their path relative to the workspace root so that
+// `dir_fixtures` and `dir_cases` agree on the name of each constant.
+fn fixture_const(path: &str) -> Ident {
+    Ident::new(&slugify_path(path).to_ascii_uppercase(), Span::call_site())
+}
+
+// Fixtures may begin with a front matter block which is stripped from the contents passed to the
+// test. The values of the requested keys are passed as additional arguments and the `ignore` and
+// `should_panic` keys set the corresponding attributes on the generated test case.
+fn apply_front_matter(
+    span: Span,
+    fixture: &Fixture,
+    keys: &[Ident],
+    contents: proc_macro2::TokenStream,
+) -> syn::Result<(
+    proc_macro2::TokenStream,
+    Vec<proc_macro2::TokenStream>,
+    Vec<syn::Attribute>,
+)> {
+    let abs_path = std::path::Path::new(&fixture.abs_path);
+    let raw = std::fs::read_to_string(abs_path)
+        .map_err(io_context("read", abs_path))
+        .map_err(|e| {
+            Error::new(
+                span,
+                format!("Error loading test cases from {}: {}", fixture.path, e),
+            )
+        })?;
+    let parsed = parse_front_matter(&raw).map_err(|e| {
+        Error::new(
+            span,
+            format!("invalid front matter in {}: {}", fixture.path, e),
+        )
+    })?;
+    let parsed = match parsed {
+        Some(parsed) => parsed,
+        None if keys.is_empty() => return Ok((contents, Vec::new(), Vec::new())),
+        None => {
+            return Err(Error::new(
+                span,
+                format!("fixture {} does not have any front matter", fixture.path),
+            ))
+        }
+    };
+
+    let args = keys
+        .iter()
+        .map(|key| {
+            let value = parsed.get(&key.to_string()).ok_or_else(|| {
+                Error::new(
+                    span,
+                    format!(
+                        "front matter in {} is missing the key `{}`",
+                        fixture.path, key
+                    ),
+                )
+            })?;
+
+            value_tokens(value).map_err(|e| {
+                Error::new(
+                    span,
+                    format!("invalid front matter in {}: {}", fixture.path, e),
+                )
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut attrs: Vec<syn::Attribute> = Vec::new();
+    for key in FRONT_MATTER_ATTRS {
+        let attr = match (key, parsed.get(key)) {
+            (_, None | Some(Value::Bool(false))) => continue,
+            ("ignore", Some(Value::Bool(true))) => parse_quote!(#[ignore]),
+            ("ignore", Some(Value::Str(reason))) => parse_quote!(#[ignore = #reason]),
+            ("should_panic", Some(Value::Bool(true))) => parse_quote!(#[should_panic]),
+            ("should_panic", Some(Value::Str(expected))) => {
+                parse_quote!(#[should_panic(expected = #expected)])
+            }
+            (key, Some(_)) => {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "front matter in {}: `{}` must be either a bool or a string",
+                        fixture.path, key
+                    ),
+                ))
+            }
+        };
+        attrs.push(attr);
+    }
+
+    let offset = parsed.body_offset;
+    let contents = quote! { &(#contents)[#offset..] };
+
+    Ok((contents, args, attrs))
+}
+
+// When splitting fixtures, the file is read at expansion time to determine the segments that it
+// contains but the contents of each segment are extracted from the full contents when the test is
+// run. Segments are numbered from 1 and empty segments (e.g. following a trailing delimiter) are
+// skipped. When front matter is being used, the contents have already had it stripped so the
+// segments are found in the body following it.
+fn split_segments(
+    span: Span,
+    fixture: &Fixture,
+    delim: &str,
+    case: &str,
+    contents: proc_macro2::TokenStream,
+    strip_front_matter: bool,
+) -> syn::Result<Vec<(String, proc_macro2::TokenStream)>> {
+    let abs_path = std::path::Path::new(&fixture.abs_path);
+    let raw = std::fs::read_to_string(abs_path)
+        .map_err(io_context("read", abs_path))
+        .map_err(|e| {
+            Error::new(
+                span,
+                format!("Error loading test cases from {}: {}", fixture.path, e),
+            )
+        })?;
+    let body_offset = match strip_front_matter {
+        true => parse_front_matter(&raw)
+            .ok()
+            .flatten()
+            .map(|parsed| parsed.body_offset)
+            .unwrap_or(0),
+        false => 0,
+    };
+
+    let segments = raw[body_offset..]
+        .split(delim)
+        .enumerate()
+        .filter(|(_, segment)| !segment.trim().is_empty())
+        .map(|(n, _)| {
+            let case = format!("{}__segment_{}", case, n + 1);
+            let contents = quote! {
+                (#contents).split(#delim).nth(#n).unwrap()
+            };
+            (case, contents)
+        })
+        .collect();
+
+    Ok(segments)
+}
+
+// Contents arguments that are not a &str are parsed using their FromStr implementation
+fn from_str(ty: &Type, path: &str, contents: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        <#ty as ::std::str::FromStr>::from_str(#contents).unwrap_or_else(|e| {
+            panic!("unable to parse test fixture {}: {:?}", #path, e)
+        })
+    }
+}
+
+// Panics from within a user provided parser are caught so that we can report which fixture failed
+// to parse along with the original panic message.
+fn parse_contents(
+    parser: &Path,
+    path: &str,
+    contents: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        match ::std::panic::catch_unwind(|| #parser(#contents)) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let msg = e
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| e.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                panic!("unable to parse test fixture {}: {}", #path, msg)
+            }
+        }
+    }
+}
+
+// The arguments that dir_cases test functions are expected to accept, in order:
+//   ([tag: &str], path: &str, [stem: &str], contents, [common: &str], [front matter...], [args...],
+//    [each])
+//
+// When a format or parser has been specified the contents argument may be of any type (it is
+// produced from the file contents), otherwise it must either be a &str or implement FromStr.
+// Values for any additional arguments are provided by the `args` and `each` options so they may
+// be of any type.
+struct ArgSpec {
+    tagged: bool,
+    stem: bool,
+    typed_contents: bool,
+    common: bool,
+    n_front_matter: usize,
+    n_extra: usize,
+    each: bool,
+}
+
+impl ArgSpec {
+    fn contents_ix(&self) -> usize {
+        usize::from(self.tagged) + 1 + usize::from(self.stem)
+    }
+
+    fn path_ix(&self) -> usize {
+        usize::from(self.tagged)
+    }
+
+    fn matches(&self, _fn: &ItemFn) -> bool {
+        let str_tys: [Type; 2] = [parse_quote!(&str), parse_quote!(String)];
+        let is_str = |fnarg: &FnArg| matches!(fnarg, FnArg::Typed(pt) if str_tys.contains(&*pt.ty));
+        let inputs: Vec<_> = _fn.sig.inputs.iter().collect();
+        let n_expected = self.contents_ix()
+            + 1
+            + usize::from(self.common)
+            + self.n_front_matter
+            + self.n_extra
+            + usize::from(self.each);
+
+        inputs.len() == n_expected
+            && inputs[..self.contents_ix()].iter().all(|arg| is_str(arg))
+            && (!self.common || is_str(inputs[self.contents_ix() + 1]))
+    }
+
+    fn describe(&self) -> String {
+        let mut args = Vec::new();
+        if self.tagged {
+            args.push("tag: &str".to_string());
+        }
+        args.push("path: &str".to_string());
+        if self.stem {
+            args.push("stem: &str".to_string());
+        }
+        match self.typed_contents {
+            true => args.push("contents: T".to_string()),
+            false => args.push("contents: &str".to_string()),
+        }
+        if self.common {
+            args.push("common: &str".to_string());
+        }
+        match self.n_front_matter {
+            0 => (),
+            1 => args.push("<1 argument from front_matter>".to_string()),
+            n => args.push(format!("<{} arguments from front_matter>", n)),
+        }
+        match self.n_extra {
+            0 => (),
+            1 => args.push("<1 argument from args>".to_string()),
+            n => args.push(format!("<{} arguments from args>", n)),
+        }
+        if self.each {
+            args.push("<1 argument from each>".to_string());
+        }
+
+        format!("({})", args.join(", "))
+    }
+
+    fn contents_ty<'a>(&self, _fn: &'a ItemFn) -> Option<&'a Type> {
+        arg_ty(_fn, self.contents_ix())
+    }
+
+    // Owned String arguments are accepted anywhere that a &str is expected, in which case the
+    // value needs converting when it is bound
+    fn str_arg(
+        &self,
+        _fn: &ItemFn,
+        ix: usize,
+        value: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match arg_ty(_fn, ix) {
+            Some(ty) if *ty == parse_quote!(String) => quote! { #value.to_string() },
+            _ => value,
+        }
+    }
+}
+
+fn arg_ty(_fn: &ItemFn, ix: usize) -> Option<&Type> {
+    match _fn.sig.inputs.iter().nth(ix) {
+        Some(FnArg::Typed(pt)) => Some(&pt.ty),
+        _ => None,
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let first = parse_macro_input!(args as DirCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+    let span = first.span;
+
+    // Any other dir_cases attributes beneath us are expanded here as well so that they are not
+    // left on each of the generated test cases.
+    let mut all = vec![first];
+    match extract_other_dir_cases(&mut original) {
+        Ok(others) => all.extend(others),
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    }
+
+    let mut items = Vec::new();
+    let mut case_attrs = Vec::new();
+    let mut names = Vec::new();
+
+    for dir_cases in all.into_iter() {
+        match render_cases(dir_cases, &original) {
+            Ok(rendered) => {
+                items.extend(rendered.items);
+                case_attrs.extend(rendered.case_attrs);
+                names.extend(rendered.names);
+            }
+            Err(e) => return TokenStream::from(e.into_compile_error()),
+        }
+    }
+
+    if let Err(e) = check_for_collisions(span, &names) {
+        return TokenStream::from(e.into_compile_error());
+    }
+
+    TokenStream::from(quote! {
+        #(#items)*
+        #(#case_attrs)*
+        #original
+    })
+}
+
+// Pull out any other dir_cases attributes on the test function
+fn extract_other_dir_cases(original: &mut ItemFn) -> syn::Result<Vec<DirCases>> {
+    let dir_cases_attr: Path = parse_quote!(dir_cases);
+    let qualified_dir_cases_attr: Path = parse_quote!(simple_test_case::dir_cases);
+    let is_dir_cases =
+        |a: &syn::Attribute| a.path() == &dir_cases_attr || a.path() == &qualified_dir_cases_attr;
+
+    let others = original
+        .attrs
+        .iter()
+        .filter(|a| is_dir_cases(a))
+        .map(|a| a.parse_args::<DirCases>())
+        .collect::<syn::Result<Vec<_>>>()?;
+    original.attrs.retain(|a| !is_dir_cases(a));
+
+    Ok(others)
+}
+
+// The test_case attributes generated for a single dir_cases attribute along with any additional
+// items that need to be emitted (rebuild tracking and drift detection) and the (qualified) case
+// names used for each fixture.
+struct Rendered {
+    items: Vec<proc_macro2::TokenStream>,
+    case_attrs: Vec<proc_macro2::TokenStream>,
+    names: Vec<(String, String)>,
+}
+
+fn render_cases(dir_cases: DirCases, original: &ItemFn) -> syn::Result<Rendered> {
+    let DirCases {
+        span,
+        dirs,
+        tags,
+        manifest,
+        fixtures,
+        format,
+        discovery,
+        naming,
+        max_size,
+        extra_args,
+        each,
+        stem,
+        ignore_matching,
+        parse_with,
+        sample,
+        prefix,
+        check_drift,
+        common,
+        split,
+        front_matter,
+        path_arg: path_form,
+    } = dir_cases;
+
+    let spec = ArgSpec {
+        tagged: tags.is_some(),
+        stem,
+        typed_contents: format.is_some() || parse_with.is_some(),
+        common: common.is_some(),
+        n_front_matter: front_matter.as_ref().map(|keys| keys.len()).unwrap_or(0),
+        n_extra: extra_args.len(),
+        each: !each.is_empty(),
+    };
+    if !spec.matches(original) {
+        let msg = format!(
+            "dir_cases test functions must accept {} as arguments",
+            spec.describe()
+        );
+
+        return Err(Error::new(span, msg));
+    }
+    // Compressed fixtures can be passed through as raw bytes rather than being decoded as UTF-8
+    let bytes_ty: Type = parse_quote!(&[u8]);
+    let as_bytes = !spec.typed_contents && spec.contents_ty(original) == Some(&bytes_ty);
+
+    let (case_details, manifest_path) = match &manifest {
+        Some(manifest) => {
+            let (details, abs_path) = get_manifest_cases(span, manifest, &discovery)?;
+            (details, Some(abs_path))
+        }
+        None => (get_all_cases(span, &dirs, &discovery)?, None),
+    };
+    let case_details: Vec<_> = case_details
+        .into_iter()
+        .filter(|fixture| common.as_deref() != Some(fixture.file_name.as_str()))
+        .collect();
+
+    let mut names: Vec<_> = case_details
+        .iter()
+        .map(|fixture| {
+            let (name, raw) = match &prefix {
+                Some(prefix) => {
+                    let raw = format!("{}_{}", prefix, naming.raw_case_name(fixture));
+                    (slugify_path(&raw), raw)
+                }
+                None => (naming.case_name(fixture), naming.raw_case_name(fixture)),
+            };
+            (naming.case_module(fixture), name, raw)
+        })
+        .collect();
+    dedup_slugs(&mut names);
+
+    let named: Vec<_> = names
+        .into_iter()
+        .zip(case_details.iter())
+        .map(|((module, name, _), fixture)| (module, name, fixture))
+        .collect();
+
+    let names: Vec<_> = named
+        .iter()
+        .map(|(module, name, fixture)| {
+            let path: Vec<_> = module.iter().chain([name]).cloned().collect();
+            (path.join("::"), fixture.path.clone())
+        })
+        .collect();
+
+    let mut case_attrs = Vec::with_capacity(named.len());
+
+    for (i, (module, case, fixture)) in named.into_iter().enumerate() {
+        let Fixture { path, abs_path, .. } = fixture;
+        let tag_arg = match &tags {
+            Some(tags) => {
+                let tag = &tags[fixture.root];
+                let tag = spec.str_arg(original, 0, quote! { #tag });
+                quote! { #tag, }
+            }
+            None => quote! {},
+        };
+        let fixture_path = path_form.for_fixture(fixture);
+        let path_arg = spec.str_arg(original, spec.path_ix(), quote! { #fixture_path });
+        let stem_arg = if stem {
+            let file_stem = std::path::Path::new(&fixture.file_name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let file_stem = spec.str_arg(original, spec.path_ix() + 1, quote! { #file_stem });
+            quote! { #file_stem, }
+        } else {
+            quote! {}
+        };
+        let oversized = match max_size {
+            Some((max, behaviour)) if fixture.size > max => Some((max, behaviour)),
+            _ => None,
+        };
+
+        let compression = Compression::for_file(&fixture.file_name);
+        if as_bytes && compression.is_none() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "&[u8] contents are only supported for compressed fixtures: {}",
+                    path
+                ),
+            ));
+        }
+        let contents = match (oversized, compression, &fixtures) {
+            (Some((max, Oversized::Error)), _, _) => {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "fixture {} is {} bytes which exceeds the max_size of {} bytes",
+                        path, fixture.size, max
+                    ),
+                ))
+            }
+            // Oversized fixtures are loaded when the (ignored) test is run rather than being
+            // embedded in the test binary, with the owned contents borrowed in the same way as
+            // for decompressed fixtures
+            (Some((_, Oversized::Ignore)), None, _) => quote! {
+                &::std::fs::read_to_string(#abs_path)
+                    .unwrap_or_else(|e| panic!("unable to read test fixture {}: {}", #path, e))
+            },
+            (Some((_, Oversized::Ignore)), Some(compression), _) => compression.decompress(
+                span,
+                path,
+                quote! {
+                    ::std::fs::read(#abs_path)
+                        .unwrap_or_else(|e| panic!("unable to read test fixture {}: {}", #path, e))
+                },
+                as_bytes,
+            )?,
+            (None, Some(compression), _) => compression.decompress(
+                span,
+                path,
+                quote! { include_bytes!(#abs_path) },
+                as_bytes,
+            )?,
+            (None, None, Some(module)) => {
+                let ident = fixture_const(path);
+                quote! { #module::#ident }
+            }
+            (None, None, None) => quote! { include_str!(#abs_path) },
+        };
+        // The owned contents of fixtures that are read at runtime only live for as long as the
+        // argument binding when borrowed directly, so the body and segments are sliced from them
+        // inside of a block and copied out.
+        let (contents, owned) = match oversized {
+            Some((_, Oversized::Ignore)) if front_matter.is_some() || split.is_some() => {
+                (quote! { contents }, Some(contents))
+            }
+            _ => (contents, None),
+        };
+        let (contents, front_matter_args, front_matter_attrs) = match &front_matter {
+            Some(_) if compression.is_some() => {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "front_matter cannot be used with compressed fixtures: {}",
+                        path
+                    ),
+                ))
+            }
+            Some(keys) => apply_front_matter(span, fixture, keys, contents)?,
+            None => (contents, Vec::new(), Vec::new()),
+        };
+        let segments = match &split {
+            Some(delim) if compression.is_some() => {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "split cannot be used with compressed fixtures: {} (delimiter {:?})",
+                        path, delim
+                    ),
+                ))
+            }
+            Some(delim) => split_segments(
+                span,
+                fixture,
+                delim,
+                &case,
+                contents,
+                front_matter.is_some(),
+            )?,
+            None => vec![(case, contents)],
+        };
+        let segments = match owned {
+            Some(owned) => segments
+                .into_iter()
+                .map(|(case, contents)| {
+                    let contents = quote! {
+                        &{
+                            let contents: &str = #owned;
+                            ::std::string::ToString::to_string(#contents)
+                        }
+                    };
+                    (case, contents)
+                })
+                .collect(),
+            None => segments,
+        };
+        let segments = segments.into_iter().map(|(case, contents)| {
+            let contents = match (format, &parse_with, spec.contents_ty(original)) {
+                (Some(format), _, Some(ty)) => format.deserialize(ty, path, contents),
+                (_, Some(parser), _) => parse_contents(parser, path, contents),
+                (None, None, Some(ty)) if *ty != parse_quote!(&str) && !as_bytes => {
+                    from_str(ty, path, contents)
+                }
+                _ => contents,
+            };
+            (case, contents)
+        });
+
+        let common_arg = match &common {
+            Some(common) => {
+                let abs_path = find_common(fixture, common).ok_or_else(|| {
+                    Error::new(
+                        span,
+                        format!("no {} file was found for fixture {}", common, path),
+                    )
+                })?;
+                let common = quote! { include_str!(#abs_path) };
+                let common = spec.str_arg(original, spec.contents_ix() + 1, common);
+                quote! { , #common }
+            }
+            None => quote! {},
+        };
+
+        let mut case_opts = Vec::new();
+        let has_ignore = front_matter_attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("ignore"));
+        case_opts.extend(front_matter_attrs.iter().map(|attr| quote! { #attr }));
+        let ignore_reason = match (oversized, &ignore_matching) {
+            _ if has_ignore => None,
+            (Some((max, _)), _) => Some(format!("fixture exceeds max_size of {} bytes", max)),
+            (_, Some(re)) if re.is_match(&fixture.file_name) => {
+                Some("fixture file name matches ignore_matching".to_string())
+            }
+            _ => None,
+        };
+        match (ignore_reason, &sample) {
+            (Some(reason), _) => case_opts.push(quote! { #[ignore = #reason] }),
+            (None, Some(Sample { n, unless })) if i >= *n && !has_ignore => {
+                let reason = format!("fixture is not in the first {} sampled fixtures", n);
+                case_opts.push(match unless {
+                    Some(cfg) => quote! { #[cfg_attr(not(#cfg), ignore = #reason)] },
+                    None => quote! { #[ignore = #reason] },
+                });
+            }
+            _ => (),
+        }
+        if !module.is_empty() {
+            let module = module.iter().map(|m| slug_ident(m, Span::call_site()));
+            case_opts.push(quote! { module = #(#module)::* });
+        }
+
+        for (case, contents) in segments {
+            if each.is_empty() {
+                case_attrs.push(quote! {
+                    #[simple_test_case::test_case(#tag_arg #path_arg, #stem_arg #contents #common_arg #(, #front_matter_args)* #(, #extra_args)*; #case; #(#case_opts),*)]
+                });
+            }
+
+            for (label, value) in each.iter() {
+                let case = format!("{}__{}", case, label);
+                case_attrs.push(quote! {
+                    #[simple_test_case::test_case(#tag_arg #path_arg, #stem_arg #contents #common_arg #(, #front_matter_args)* #(, #extra_args)*, #value; #case; #(#case_opts),*)]
+                });
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    if let Some(abs_path) = manifest_path {
+        // Ensure that we are recompiled if the manifest changes
+        items.push(quote! { const _: &str = include_str!(#abs_path); });
+    }
+    if check_drift {
+        items.push(drift_test(
+            span,
+            original,
+            prefix.as_deref(),
+            &dirs,
+            &discovery,
+        )?);
+    }
+
+    Ok(Rendered {
+        items,
+        case_attrs,
+        names,
+    })
+}
+
+// The common file for a fixture is the closest one found in the directory containing the fixture
+// or any of its parent directories up to the directory passed to dir_cases.
+fn find_common(fixture: &Fixture, common: &str) -> Option<String> {
+    let depth = match fixture.rel_dir.as_str() {
+        "" => 0,
+        rel_dir => rel_dir.split('/').count(),
+    };
+
+    std::path::Path::new(&fixture.abs_path)
+        .ancestors()
+        .skip(1)
+        .take(depth + 1)
+        .map(|dir| dir.join(common))
+        .find(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+// Cargo has no way of knowing that adding or removing a file from a fixture directory should
+// cause dir_cases to be re-expanded, so a stale build can silently skip new fixtures. The drift
+// test lists every file in each directory at runtime and compares a hash of the listing with the
+// hash of the listing taken when the cases were generated. The listing is deliberately not
+// filtered down to the files used as fixtures, so that the check can't disagree with the cases
+// that were generated: adding any file to the directory is reported until the tests are rebuilt.
+fn drift_test(
+    span: Span,
+    original: &ItemFn,
+    prefix: Option<&str>,
+    dirs: &[String],
+    discovery: &Discovery,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut checks = Vec::with_capacity(dirs.len());
+    for dir in dirs.iter() {
+        let resolved = discovery.resolve(dir).map_err(|e| Error::new(span, e))?;
+        let abs_dir = current_dir()
+            .map_err(|e| Error::new(span, e.to_string()))?
+            .join(&resolved)
+            .to_string_lossy()
+            .into_owned();
+        let files = list_files(&abs_dir, discovery).map_err(|e| {
+            Error::new(
+                span,
+                format!("Error loading test cases from {}: {}", dir, e),
+            )
+        })?;
+        let hash = fnv1a(files.join("\n"));
+        checks.push(quote! { (#dir, #abs_dir, #hash) });
+    }
+
+    let recursive = discovery.recursive;
+    let follow_symlinks = discovery.follow_symlinks;
+    let ident = match prefix {
+        Some(prefix) => format!(
+            "{}_{}_fixture_drift",
+            original.sig.ident,
+            slugify_path(prefix)
+        ),
+        None => format!("{}_fixture_drift", original.sig.ident),
+    };
+    let ident = Ident::new(&ident, original.sig.ident.span());
+
+    // The runtime listing follows the same rules as walk_dir. Anything that walk_dir would report
+    // as an error (a dangling symlink or a cycle) is included in the listing so that it shows up
+    // as drift and the error is then reported when the tests are rebuilt.
+    Ok(quote! {
+        #[test]
+        fn #ident() {
+            fn list_files(
+                dir: &::std::path::Path,
+                rel_dir: &str,
+                ancestors: &mut Vec<::std::path::PathBuf>,
+                files: &mut Vec<String>,
+            ) {
+                let entries = ::std::fs::read_dir(dir)
+                    .unwrap_or_else(|e| panic!("unable to read fixture directory {}: {}", dir.display(), e));
+                for entry in entries {
+                    let entry = entry.unwrap_or_else(|e| panic!("unable to read fixture directory {}: {}", dir.display(), e));
+                    let path = entry.path();
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    let rel_path = match rel_dir {
+                        "" => name,
+                        rel_dir => format!("{}/{}", rel_dir, name),
+                    };
+                    if entry.file_type().map(|t| t.is_symlink()).unwrap_or(false) {
+                        if !#follow_symlinks {
+                            continue;
+                        }
+                        if ::std::fs::metadata(&path).is_err() {
+                            files.push(rel_path);
+                            continue;
+                        }
+                    }
+                    if path.is_dir() && #recursive {
+                        match path.canonicalize() {
+                            Ok(canonical) if !ancestors.contains(&canonical) => {
+                                ancestors.push(canonical);
+                                list_files(&path, &rel_path, ancestors, files);
+                                ancestors.pop();
+                            }
+                            _ => files.push(rel_path),
+                        }
+                    } else if path.is_file() {
+                        files.push(rel_path);
+                    }
+                }
+            }
+
+            let dirs: &[(&str, &str, u32)] = &[#(#checks),*];
+            for (dir, abs_dir, expected) in dirs.iter() {
+                let abs_dir = ::std::path::Path::new(abs_dir);
+                let mut ancestors: Vec<_> = abs_dir.canonicalize().into_iter().collect();
+                let mut files = Vec::new();
+                list_files(abs_dir, "", &mut ancestors, &mut files);
+                files.sort();
+                let hash = files.join("\n").bytes().fold(0x811c9dc5_u32, |hash, b| {
+                    (hash ^ u32::from(b)).wrapping_mul(0x01000193)
+                });
+                if hash != *expected {
+                    panic!(
+                        "the files in {} have changed since the test cases were generated: rebuild the tests to regenerate them",
+                        dir
+                    );
+                }
+            }
+        }
+    })
+}
+
+// The sorted paths (relative to dir) of every file in a directory, as seen by walk_dir. This needs
+// to match the listing performed by the generated drift test.
+fn list_files(dir: &str, discovery: &Discovery) -> std::io::Result<Vec<String>> {
+    let canonical = std::path::Path::new(dir)
+        .canonicalize()
+        .map_err(io_context("canonicalize", std::path::Path::new(dir)))?;
+    let mut files = Vec::new();
+    walk_dir(dir, "", discovery, &mut vec![canonical], &mut files)?;
+
+    let mut files: Vec<_> = files.iter().map(|f| f.rel_path()).collect();
+    files.sort();
+
+    Ok(files)
+}
+
+pub(crate) fn fixtures_inner(input: TokenStream) -> TokenStream {
+    let DirFixtures {
+        span,
+        vis,
+        module,
+        dirs,
+    } = parse_macro_input!(input as DirFixtures);
+
+    let case_details = match get_all_cases(span, &dirs, &Discovery::default()) {
+        Ok(details) => details,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let consts: Vec<_> = case_details
+        .into_iter()
+        .map(|Fixture { path, abs_path, .. }| {
+            let ident = fixture_const(&path);
+            quote! {
+                pub const #ident: &str = include_str!(#abs_path);
+            }
+        })
+        .collect();
+
+    TokenStream::from(quote! {
+        #[allow(dead_code)]
+        #vis mod #module {
+            #(#consts)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_arg_forms() {
+        let mut f = fixture("tests/data", "case.txt");
+        f.rel_dir = "sub".to_string();
+        f.path = "tests/data/sub/case.txt".to_string();
+        let parsed: DirCases = parse_quote!("tests/data", path_arg = "name");
+
+        assert_eq!(parsed.path_arg, PathArg::Name);
+        assert_eq!(PathArg::Name.for_fixture(&f), "sub/case.txt");
+        assert_eq!(PathArg::Relative.for_fixture(&f), "tests/data/sub/case.txt");
+        assert_eq!(PathArg::Absolute.for_fixture(&f), f.abs_path);
+    }
+
+    #[test]
+    fn parse_dir_cases_with_format() {
+        let parsed: DirCases = parse_quote!("tests/data", format = "json");
+
+        assert_eq!(parsed.dirs, vec!["tests/data".to_string()]);
+        assert_eq!(parsed.format, Some(Format::Json));
+    }
+
+    #[test]
+    fn unknown_format_is_an_error() {
+        let res: syn::Result<DirCases> = syn::parse2(quote! { "tests/data", format = "xml" });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn parse_dir_cases_with_extensions() {
+        let parsed: DirCases = parse_quote!("tests/data", extensions = ["yaml", "yml"]);
+
+        assert_eq!(
+            parsed.discovery.extensions,
+            Some(vec!["yaml".to_string(), "yml".to_string()])
+        );
+    }
+
+    fn fixture(dir: &str, file_name: &str) -> Fixture {
+        Fixture {
+            root: 0,
+            dir: dir.to_string(),
+            rel_dir: String::new(),
+            file_name: file_name.to_string(),
+            path: format!("{}/{}", dir, file_name),
+            abs_path: format!("/root/{}/{}", dir, file_name),
+            size: 0,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn default_case_names_use_the_full_path() {
+        let name = Naming::default().case_name(&fixture("tests/data", "utf8_bom.txt"));
+
+        assert_eq!(name, "tests_data_utf8_bom_txt");
+    }
+
+    #[test]
+    fn case_names_can_strip_extension_and_prefix() {
+        let parsed: DirCases =
+            parse_quote!("tests/data", strip_extension = true, strip_prefix = true);
+        let name = parsed
+            .naming
+            .case_name(&fixture("tests/data", "utf8_bom.txt"));
+
+        assert_eq!(name, "utf8_bom");
+    }
+
+    #[test]
+    fn case_names_can_strip_a_literal_prefix() {
+        let parsed: DirCases = parse_quote!("tests/data", strip_prefix = "vec_");
+        let name = parsed
+            .naming
+            .case_name(&fixture("tests/data", "vec_01.bin"));
+
+        assert_eq!(name, "_01_bin");
+    }
+
+    #[test]
+    fn stripped_case_names_include_subdirectories() {
+        let parsed: DirCases = parse_quote!("tests/data", recursive = true, strip_prefix = true);
+        let mut f = fixture("tests/data", "case.txt");
+        f.rel_dir = "nested/dir".to_string();
+
+        assert_eq!(parsed.naming.case_name(&f), "nested_dir_case_txt");
+    }
+
+    #[test]
+    fn names_that_slugify_identically_are_given_stable_suffixes() {
+        let mut names = vec![
+            (
+                vec![],
+                "report_2024_txt".to_string(),
+                "report-2024.txt".to_string(),
+            ),
+            (
+                vec![],
+                "report_2024_txt".to_string(),
+                "report_2024.txt".to_string(),
+            ),
+            (vec![], "other_txt".to_string(), "other.txt".to_string()),
+        ];
+        dedup_slugs(&mut names);
+        let deduped: Vec<_> = names.iter().map(|(_, name, _)| name.as_str()).collect();
+
+        assert_eq!(
+            deduped,
+            vec![
+                format!("report_2024_txt_{}", stable_hash("report-2024.txt")),
+                format!("report_2024_txt_{}", stable_hash("report_2024.txt")),
+                "other_txt".to_string(),
+            ]
+        );
+        assert_ne!(deduped[0], deduped[1]);
+    }
+
+    #[test]
+    fn identical_raw_names_are_not_deduplicated() {
+        let mut names = vec![
+            (vec![], "case_txt".to_string(), "case.txt".to_string()),
+            (vec![], "case_txt".to_string(), "case.txt".to_string()),
+        ];
+        dedup_slugs(&mut names);
+
+        assert!(names.iter().all(|(_, name, _)| name == "case_txt"));
+    }
+
+    #[test]
+    fn colliding_case_names_are_an_error() {
+        let names = vec![
+            ("case_txt".to_string(), "a/case.txt".to_string()),
+            ("case_txt".to_string(), "b/case.txt".to_string()),
+        ];
+
+        let err = check_for_collisions(Span::call_site(), &names).unwrap_err();
+
+        assert!(err.to_string().contains("both a/case.txt and b/case.txt"));
+    }
+
+    #[test]
+    fn invalid_matching_pattern_is_an_error() {
+        let res: syn::Result<DirCases> = syn::parse2(quote! { "tests/data", matching = "(a" });
+
+        match res {
+            Err(e) => assert!(e.to_string().contains("invalid matching pattern")),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[cfg(unix)]
+    fn symlink_test_dir(name: &str) -> String {
+        use std::os::unix::fs::symlink;
+
+        let dir =
+            std::env::temp_dir().join(format!("simple_test_case_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("real.txt"), "real").unwrap();
+        symlink(dir.join("real.txt"), dir.join("link.txt")).unwrap();
+
+        dir.to_string_lossy().into_owned()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_can_be_followed_or_skipped() {
+        let dir = symlink_test_dir("follow");
+        let names = |follow_symlinks| {
+            let discovery = Discovery {
+                follow_symlinks,
+                ..Discovery::default()
+            };
+            let cases =
+                get_all_cases(Span::call_site(), std::slice::from_ref(&dir), &discovery).unwrap();
+            cases.into_iter().map(|f| f.file_name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(true), vec!["link.txt", "real.txt"]);
+        assert_eq!(names(false), vec!["real.txt"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_files_matches_the_fixtures_found() {
+        let dir = symlink_test_dir("drift");
+        let sub_dir = std::path::Path::new(&dir).join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        std::fs::write(sub_dir.join("case.txt"), "case").unwrap();
+        std::os::unix::fs::symlink(&sub_dir, std::path::Path::new(&dir).join("linked")).unwrap();
+
+        for follow_symlinks in [true, false] {
+            let discovery = Discovery {
+                recursive: true,
+                follow_symlinks,
+                ..Discovery::default()
+            };
+            let cases =
+                get_all_cases(Span::call_site(), std::slice::from_ref(&dir), &discovery).unwrap();
+            let fixtures: Vec<_> = cases
+                .iter()
+                .map(|f| f.path.strip_prefix(&format!("{}/", dir)).unwrap())
+                .collect();
+
+            assert_eq!(list_files(&dir, &discovery).unwrap(), fixtures);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn files_are_rejected_as_directories() {
+        let res = get_all_cases(
+            Span::call_site(),
+            &["tests/cases.list".to_string()],
+            &Discovery::default(),
+        );
+
+        match res {
+            Err(e) => assert!(e.to_string().contains("not a directory"), "{}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fixture_paths_are_canonical() {
+        let res = get_all_cases(
+            Span::call_site(),
+            &["tests/../tests/test_data".to_string()],
+            &Discovery::default(),
+        )
+        .unwrap();
+        let expected = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/test_data")
+            .canonicalize()
+            .unwrap();
+
+        assert!(!res.is_empty());
+        for fixture in res.iter() {
+            assert!(fixture.path.starts_with("tests/../tests/test_data/"));
+            assert!(
+                fixture.abs_path.starts_with(&*expected.to_string_lossy()),
+                "{}",
+                fixture.abs_path
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_symlinks_are_an_error() {
+        let dir = symlink_test_dir("dangling");
+        std::fs::remove_file(std::path::Path::new(&dir).join("real.txt")).unwrap();
+
+        let res = get_all_cases(
+            Span::call_site(),
+            std::slice::from_ref(&dir),
+            &Discovery::default(),
+        );
+
+        match res {
+            Err(e) => assert!(e.to_string().contains("dangling symlink"), "{}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn each_takes_an_additional_argument() {
+        let parsed: DirCases = parse_quote!(
+            "tests/data",
+            args = (1),
+            each = [strict = Mode::Strict, lenient = Mode::Lenient]
+        );
+        let labels: Vec<_> = parsed.each.iter().map(|(l, _)| l.to_string()).collect();
+
+        let spec = ArgSpec {
+            tagged: false,
+            stem: false,
+            typed_contents: false,
+            common: false,
+            n_front_matter: 0,
+            n_extra: 1,
+            each: true,
+        };
+
+        assert_eq!(labels, vec!["strict", "lenient"]);
+        assert_eq!(
+            spec.describe(),
+            "(path: &str, contents: &str, <1 argument from args>, <1 argument from each>)"
+        );
+    }
+
+    #[test]
+    fn stem_is_passed_before_contents() {
+        let f: ItemFn = parse_quote! { fn t(path: &str, stem: &str, case: Vector) {} };
+        let spec = ArgSpec {
+            tagged: false,
+            stem: true,
+            typed_contents: true,
+            common: false,
+            n_front_matter: 0,
+            n_extra: 0,
+            each: false,
+        };
+        let expected: Type = parse_quote!(Vector);
+
+        assert!(spec.matches(&f));
+        assert_eq!(spec.contents_ty(&f), Some(&expected));
+        assert_eq!(spec.describe(), "(path: &str, stem: &str, contents: T)");
+    }
+
+    #[test]
+    fn manifest_and_dirs_are_mutually_exclusive() {
+        let parsed: DirCases = parse_quote!(manifest = "tests/cases.list", strip_prefix = true);
+        let both: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", manifest = "tests/cases.list" });
+        let searching: syn::Result<DirCases> =
+            syn::parse2(quote! { manifest = "tests/cases.list", recursive = true });
+
+        assert_eq!(parsed.manifest.as_deref(), Some("tests/cases.list"));
+        assert!(both.is_err());
+        assert!(searching.is_err());
+    }
+
+    #[test]
+    fn manifest_entries_are_listed_in_order() {
+        let (cases, _) =
+            get_manifest_cases(Span::call_site(), "tests/cases.list", &Discovery::default())
+                .unwrap();
+        let paths: Vec<_> = cases.iter().map(|f| f.path.as_str()).collect();
+        let names: Vec<_> = cases.iter().map(|f| f.name.as_deref()).collect();
+
+        assert_eq!(
+            paths,
+            vec!["tests/test_data/six.txt", "tests/test_data_2/one.txt"]
+        );
+        assert_eq!(names, vec![None, Some("one from second dir")]);
+    }
+
+    #[test]
+    fn parse_sample() {
+        let parsed: DirCases = parse_quote!(
+            "tests/data",
+            sample = 10,
+            sample_unless = cfg(feature = "full")
+        );
+        let sample = parsed.sample.unwrap();
+
+        assert_eq!(sample.n, 10);
+        assert_eq!(
+            sample.unless.map(|cfg| cfg.to_string()),
+            Some(quote!(feature = "full").to_string())
+        );
+    }
+
+    #[test]
+    fn sample_unless_requires_sample() {
+        let res: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", sample_unless = cfg(feature = "full") });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn workspace_root_dirs_are_resolved_from_the_root() {
+        let parsed: DirCases = parse_quote!("tests/data", workspace_root = true);
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+        assert_eq!(
+            parsed.discovery.resolve("tests/data"),
+            Ok(format!("{}/tests/data", root))
+        );
+    }
+
+    #[test]
+    fn missing_dirs_report_where_they_were_searched_for() {
+        let res = get_all_cases(
+            Span::call_site(),
+            &["tests/does_not_exist".to_string()],
+            &Discovery::default(),
+        );
+        let cwd = std::env::current_dir().unwrap();
+
+        match res {
+            Err(e) => {
+                let msg = e.to_string();
+                let searched = cwd.join("tests/does_not_exist");
+                assert!(
+                    msg.contains(&searched.to_string_lossy().into_owned()),
+                    "{}",
+                    msg
+                );
+                assert!(msg.contains("hint:"), "{}", msg);
+            }
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_file_names_are_an_error_unless_excluded() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("simple_test_case_utf8_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("valid.txt"), "valid").unwrap();
+        let invalid = std::ffi::OsStr::from_bytes(b"invalid_\xff.bin");
+        if std::fs::write(dir.join(invalid), "invalid").is_err() {
+            // Not all filesystems permit non-UTF-8 file names
+            std::fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+
+        let dirs = [dir.to_string_lossy().into_owned()];
+        let all = get_all_cases(Span::call_site(), &dirs, &Discovery::default());
+        let txt_only = Discovery {
+            extensions: Some(vec!["txt".to_string()]),
+            ..Discovery::default()
+        };
+        let filtered = get_all_cases(Span::call_site(), &dirs, &txt_only);
+
+        match all {
+            Err(e) => assert!(e.to_string().contains("not valid UTF-8"), "{}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+        assert_eq!(filtered.unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compression_is_detected_by_extension() {
+        assert_eq!(
+            Compression::for_file("corpus.json.gz"),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::for_file("corpus.zst"), Some(Compression::Zstd));
+        assert_eq!(Compression::for_file("corpus.json"), None);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_fixtures_are_decompressed() {
+        let tokens = Compression::Gzip
+            .decompress(
+                Span::call_site(),
+                "a.gz",
+                quote!(include_bytes!("/a.gz")),
+                false,
+            )
+            .unwrap();
+
+        let tokens = tokens.to_string();
+        assert!(tokens.contains(":: flate2 :: read :: GzDecoder"));
+        assert!(!tokens.contains("leak"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compressed_fixtures_can_be_passed_as_bytes() {
+        let tokens = Compression::Gzip
+            .decompress(
+                Span::call_site(),
+                "a.gz",
+                quote!(include_bytes!("/a.gz")),
+                true,
+            )
+            .unwrap();
+
+        let tokens = tokens.to_string();
+        assert!(tokens.contains(":: flate2 :: read :: GzDecoder"));
+        assert!(!tokens.contains("from_utf8"));
+    }
+
+    #[test]
+    fn bytes_are_only_supported_for_compressed_fixtures() {
+        let dir_cases: DirCases = parse_quote!("tests/test_data");
+        let original: ItemFn = parse_quote! {
+            fn example(path: &str, contents: &[u8]) {}
+        };
+
+        match render_cases(dir_cases, &original) {
+            Err(e) => assert!(
+                e.to_string().contains("only supported for compressed"),
+                "{}",
+                e
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn compressed_fixtures_require_a_feature() {
+        let res = Compression::Zstd.decompress(
+            Span::call_site(),
+            "a.zst",
+            quote!(include_bytes!("/a.zst")),
+            false,
+        );
+
+        match res {
+            Err(e) => assert!(e.to_string().contains("`zstd` feature"), "{}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn list_files_includes_subdirectories_when_recursive() {
+        let dir = "tests/test_data_nested";
+
+        let recursive = Discovery {
+            recursive: true,
+            ..Discovery::default()
+        };
+
+        assert_eq!(
+            list_files(dir, &Discovery::default()).unwrap(),
+            vec!["top.txt"]
+        );
+        assert_eq!(
+            list_files(dir, &recursive).unwrap(),
+            vec!["a/case.txt", "b/case.txt", "top.txt"]
+        );
+    }
+
+    #[test]
+    fn list_files_ignores_fixture_filters() {
+        let parsed: DirCases = parse_quote!("tests/test_data_mixed", extensions = ["case"]);
+
+        assert_eq!(
+            list_files("tests/test_data_mixed", &parsed.discovery).unwrap(),
+            vec!["README.md", "three.case"]
+        );
+    }
+
+    #[test]
+    fn check_drift_is_not_supported_with_a_manifest() {
+        let res: syn::Result<DirCases> =
+            syn::parse2(quote! { manifest = "tests/cases.list", check_drift = true });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn common_files_are_found_in_parent_directories() {
+        let root = std::env::current_dir().unwrap();
+        let mut f = fixture("tests/test_data_common", "case.txt");
+        f.rel_dir = "nested".to_string();
+        f.abs_path = root
+            .join("tests/test_data_common/nested/case.txt")
+            .to_string_lossy()
+            .into_owned();
+        let expected = root.join("tests/test_data_common/_common.txt");
+
+        assert_eq!(
+            find_common(&f, "_common.txt"),
+            Some(expected.to_string_lossy().into_owned())
+        );
+        assert_eq!(find_common(&f, "_missing.txt"), None);
+    }
+
+    #[test]
+    fn common_is_passed_after_contents() {
+        let spec = ArgSpec {
+            tagged: false,
+            stem: false,
+            typed_contents: false,
+            common: true,
+            n_front_matter: 0,
+            n_extra: 1,
+            each: false,
+        };
+
+        assert_eq!(
+            spec.describe(),
+            "(path: &str, contents: &str, common: &str, <1 argument from args>)"
+        );
+    }
+
+    #[test]
+    fn segments_are_numbered_from_one_skipping_empty_segments() {
+        let root = std::env::current_dir().unwrap();
+        let mut f = fixture("tests/test_data_split", "suite.txt");
+        f.abs_path = root
+            .join("tests/test_data_split/suite.txt")
+            .to_string_lossy()
+            .into_owned();
+
+        let segments = split_segments(
+            Span::call_site(),
+            &f,
+            "\n---\n",
+            "suite_txt",
+            quote!(c),
+            false,
+        )
+        .unwrap();
+        let names: Vec<_> = segments.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "suite_txt__segment_1",
+                "suite_txt__segment_2",
+                "suite_txt__segment_3"
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_split_delimiters_are_an_error() {
+        let res: syn::Result<DirCases> = syn::parse2(quote! { "tests/data", split = "" });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn ignore_over_is_shorthand_for_ignoring_oversized_fixtures() {
+        let parsed: DirCases = parse_quote!("tests/data", ignore_over = "10MB");
+        let both: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", ignore_over = "10MB", max_size = "1MB" });
+
+        assert_eq!(parsed.max_size, Some((10 * 1024 * 1024, Oversized::Ignore)));
+        assert!(both.is_err());
+    }
+
+    #[test]
+    fn cases_can_be_grouped_by_extension() {
+        let parsed: DirCases = parse_quote!(
+            "tests/data",
+            recursive = true,
+            nested = true,
+            group_by_extension = true
+        );
+        let mut f = fixture("tests/data", "case.json");
+        f.rel_dir = "nested".to_string();
+
+        assert_eq!(parsed.naming.case_module(&f), vec!["json", "nested"]);
+        assert!(parsed
+            .naming
+            .case_module(&fixture("tests/data", "README"))
+            .is_empty());
+    }
+
+    #[test]
+    fn parse_front_matter_option() {
+        let keys: DirCases = parse_quote!("tests/data", front_matter = [expected, retries]);
+        let attrs_only: DirCases = parse_quote!("tests/data", front_matter = true);
+        let reserved: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", front_matter = [ignore] });
+
+        assert_eq!(
+            keys.front_matter.unwrap(),
+            vec![
+                Ident::new("expected", Span::call_site()),
+                Ident::new("retries", Span::call_site())
+            ]
+        );
+        assert_eq!(attrs_only.front_matter, Some(vec![]));
+        assert!(reserved.is_err());
+    }
+
+    #[test]
+    fn parse_prefix() {
+        let parsed: DirCases = parse_quote!("tests/data", prefix = "valid");
+
+        assert_eq!(parsed.prefix.as_deref(), Some("valid"));
+    }
+
+    #[test]
+    fn parse_tagged_dirs() {
+        let parsed: DirCases = parse_quote!(
+            tagged(good = "tests/valid", bad = "tests/invalid"),
+            stem = true
+        );
+
+        assert_eq!(parsed.dirs, vec!["tests/valid", "tests/invalid"]);
+        assert_eq!(
+            parsed.tags,
+            Some(vec!["good".to_string(), "bad".to_string()])
+        );
+        assert!(parsed.stem);
+    }
+
+    #[test]
+    fn unknown_string_options_are_an_error() {
+        for tokens in [
+            quote! { "tests/data", strip_prefx = "x" },
+            quote! { prefix = "tests/valid", common = "tests/invalid" },
+        ] {
+            let res: syn::Result<DirCases> = syn::parse2(tokens);
+            assert!(res.is_err());
+        }
+
+        let res: syn::Result<DirCases> = syn::parse2(quote! { "tests/data", strip_prefx = "x" });
+        match res {
+            Err(e) => assert_eq!(e.to_string(), "unknown dir_cases option: strip_prefx"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn tags_can_share_names_with_options() {
+        let parsed: DirCases =
+            parse_quote!(tagged(prefix = "tests/valid", common = "tests/invalid"));
+
+        assert_eq!(
+            parsed.tags,
+            Some(vec!["prefix".to_string(), "common".to_string()])
+        );
+        assert_eq!(parsed.prefix, None);
+    }
+
+    #[test]
+    fn tags_must_be_given_for_all_dirs() {
+        let res: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", tagged(bad = "tests/invalid") });
+
+        match res {
+            Err(e) => assert!(e.to_string().contains("all directories"), "{}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn parse_ignore_matching() {
+        let parsed: DirCases = parse_quote!("tests/data", ignore_matching = r"^_|\.skip$");
+        let re = parsed.ignore_matching.unwrap();
+
+        assert!(re.is_match("_wip.txt"));
+        assert!(re.is_match("broken.txt.skip"));
+        assert!(!re.is_match("fine.txt"));
+    }
+
+    #[test]
+    fn format_and_parse_with_are_exclusive() {
+        let res: syn::Result<DirCases> =
+            syn::parse2(quote! { "tests/data", format = "json", parse_with = parse });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn contents_may_be_any_type() {
+        let f: ItemFn = parse_quote! { fn t(path: &str, case: Vector) {} };
+        let spec = |typed_contents| ArgSpec {
+            tagged: false,
+            stem: false,
+            typed_contents,
+            common: false,
+            n_front_matter: 0,
+            n_extra: 0,
+            each: false,
+        };
+
+        // Without a format or parser the contents are parsed using FromStr
+        assert!(spec(true).matches(&f));
+        assert!(spec(false).matches(&f));
+    }
+
+    #[test]
+    fn owned_strings_are_accepted() {
+        let f: ItemFn = parse_quote! { fn t(path: String, stem: &str, contents: String) {} };
+        let spec = ArgSpec {
+            tagged: false,
+            stem: true,
+            typed_contents: false,
+            common: false,
+            n_front_matter: 0,
+            n_extra: 0,
+            each: false,
+        };
+
+        assert!(spec.matches(&f));
+        assert_eq!(
+            spec.str_arg(&f, 0, quote!("a.txt")).to_string(),
+            quote!("a.txt".to_string()).to_string()
+        );
+        assert_eq!(
+            spec.str_arg(&f, 1, quote!("a")).to_string(),
+            quote!("a").to_string()
+        );
+    }
+
+    #[test]
+    fn extra_args_must_match_the_signature() {
+        let parsed: DirCases = parse_quote!("tests/data", args = (Mode::Strict, 3));
+        let f: ItemFn = parse_quote! { fn t(path: &str, contents: &str, mode: Mode, n: usize) {} };
+        let spec = |n_extra| ArgSpec {
+            tagged: false,
+            stem: false,
+            typed_contents: false,
+            common: false,
+            n_front_matter: 0,
+            n_extra,
+            each: false,
+        };
+
+        assert_eq!(parsed.extra_args.len(), 2);
+        assert!(spec(2).matches(&f));
+        assert!(!spec(1).matches(&f));
+        assert_eq!(
+            spec(2).describe(),
+            "(path: &str, contents: &str, <2 arguments from args>)"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_format_deserializes_with_toml() {
+        let parsed: DirCases = parse_quote!("tests/data", format = "toml");
+        let ty: Type = parse_quote!(Config);
+        let tokens = parsed
+            .format
+            .unwrap()
+            .deserialize(&ty, "a.toml", quote! { "" });
+
+        assert!(tokens
+            .to_string()
+            .contains(":: toml :: from_str :: < Config >"));
+    }
 }
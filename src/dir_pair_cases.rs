@@ -0,0 +1,230 @@
+use crate::util::{current_dir, expand_env, io_context, slugify_path};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::{collections::BTreeMap, fs::read_dir};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, FnArg, Ident, ItemFn, LitStr, Result, Token, Type,
+};
+
+// dir_pair_cases("path/to/dir") with optional `input = "in"` and `output = "out"` extensions
+struct DirPairCases {
+    span: Span,
+    dir: String,
+    input: String,
+    output: String,
+}
+
+impl Parse for DirPairCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let dir: LitStr = input.parse()?;
+        let mut input_ext = "in".to_string();
+        let mut output_ext = "out".to_string();
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "input" => input_ext = input.parse::<LitStr>()?.value(),
+                "output" => output_ext = input.parse::<LitStr>()?.value(),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown dir_pair_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        if input_ext == output_ext {
+            return Err(Error::new(
+                span,
+                "the input and output extensions must be different",
+            ));
+        }
+
+        Ok(Self {
+            span,
+            dir: dir.value(),
+            input: input_ext,
+            output: output_ext,
+        })
+    }
+}
+
+// A matched pair of input and expected output files sharing the same stem
+#[derive(Debug, PartialEq, Eq)]
+struct Pair {
+    stem: String,
+    input: String,
+    output: String,
+}
+
+// Files with extensions other than the input and output extensions are ignored but any input or
+// output file without a counterpart is an error so that cases are not silently skipped.
+fn get_pairs(
+    file_names: &[String],
+    input: &str,
+    output: &str,
+) -> std::result::Result<Vec<Pair>, String> {
+    let mut stems: BTreeMap<&str, (Option<&str>, Option<&str>)> = BTreeMap::new();
+
+    for name in file_names.iter() {
+        match name.rsplit_once('.') {
+            Some((stem, ext)) if ext == input => stems.entry(stem).or_default().0 = Some(name),
+            Some((stem, ext)) if ext == output => stems.entry(stem).or_default().1 = Some(name),
+            _ => (),
+        }
+    }
+
+    stems
+        .into_iter()
+        .map(|(stem, pair)| match pair {
+            (Some(i), Some(o)) => Ok(Pair {
+                stem: stem.to_string(),
+                input: i.to_string(),
+                output: o.to_string(),
+            }),
+            (Some(i), None) => Err(format!("{} has no matching {}.{} file", i, stem, output)),
+            (None, Some(o)) => Err(format!("{} has no matching {}.{} file", o, stem, input)),
+            (None, None) => unreachable!("stems are only inserted with a file"),
+        })
+        .collect()
+}
+
+fn has_correct_args(_fn: &ItemFn) -> bool {
+    let str_ty: Type = parse_quote!(&str);
+    let is_str = |fnarg: &FnArg| matches!(fnarg, FnArg::Typed(pt) if *pt.ty == str_ty);
+
+    _fn.sig.inputs.len() == 3 && _fn.sig.inputs.iter().all(is_str)
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let DirPairCases {
+        span,
+        dir,
+        input: input_ext,
+        output: output_ext,
+    } = parse_macro_input!(args as DirPairCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    if !has_correct_args(&original) {
+        return TokenStream::from(
+            Error::new(
+                span,
+                "dir_pair_cases test functions must accept (path: &str, input: &str, expected: &str) as arguments",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let pairs = match load_pairs(span, &dir, &input_ext, &output_ext) {
+        Ok(pairs) => pairs,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs: Vec<_> = pairs
+        .into_iter()
+        .map(|(pair, expanded, abs_dir)| {
+            let path = format!("{}/{}", expanded, pair.input);
+            let input_path = format!("{}/{}", abs_dir, pair.input);
+            let output_path = format!("{}/{}", abs_dir, pair.output);
+            let case = slugify_path(&format!("{}/{}", dir, pair.stem));
+
+            quote! {
+                #[simple_test_case::test_case(#path, include_str!(#input_path), include_str!(#output_path); #case)]
+            }
+        })
+        .collect();
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+fn load_pairs(
+    span: Span,
+    dir: &str,
+    input: &str,
+    output: &str,
+) -> Result<Vec<(Pair, String, String)>> {
+    let err = |msg: String| {
+        Error::new(
+            span,
+            format!("Error loading test cases from {}: {}", dir, msg),
+        )
+    };
+    let io_err = |e: std::io::Error| err(e.to_string());
+    let expanded = expand_env(dir).map_err(err)?;
+    let joined = current_dir().map_err(io_err)?.join(&expanded);
+    let abs_dir = joined
+        .canonicalize()
+        .map_err(io_context("canonicalize", &joined))
+        .map_err(io_err)?;
+    if !abs_dir.is_dir() {
+        return Err(err(format!("{} is not a directory", abs_dir.display())));
+    }
+
+    let mut file_names = Vec::new();
+    let entries = read_dir(&abs_dir)
+        .map_err(io_context("read directory", &abs_dir))
+        .map_err(io_err)?;
+    for entry in entries {
+        let entry = entry
+            .map_err(io_context("read an entry in directory", &abs_dir))
+            .map_err(io_err)?;
+        if entry.path().is_file() {
+            file_names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+
+    let abs_dir = abs_dir.to_string_lossy().into_owned();
+    let pairs = get_pairs(&file_names, input, output).map_err(err)?;
+
+    Ok(pairs
+        .into_iter()
+        .map(|pair| (pair, expanded.clone(), abs_dir.clone()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn pairs_are_matched_by_stem() {
+        let files = names(&["b.out", "a.in", "README.md", "a.out", "b.in"]);
+        let pairs = get_pairs(&files, "in", "out").unwrap();
+        let stems: Vec<_> = pairs.iter().map(|p| p.stem.as_str()).collect();
+
+        assert_eq!(stems, vec!["a", "b"]);
+        assert_eq!(pairs[1].input, "b.in");
+        assert_eq!(pairs[1].output, "b.out");
+    }
+
+    #[test]
+    fn unmatched_files_are_an_error() {
+        let missing_output = get_pairs(&names(&["a.in", "a.out", "b.in"]), "in", "out");
+        let missing_input = get_pairs(&names(&["c.expected"]), "src", "expected");
+
+        assert_eq!(
+            missing_output,
+            Err("b.in has no matching b.out file".to_string())
+        );
+        assert_eq!(
+            missing_input,
+            Err("c.expected has no matching c.src file".to_string())
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::util::from_str_value;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Error, FnArg, ItemFn, LitStr};
+
+// The variables matching the prefix, sorted by name, along with the case name for each of them
+// (the remainder of the variable name after the prefix).
+fn matching_vars(
+    prefix: &str,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Vec<(String, String, String)> {
+    let mut matching: Vec<_> = vars
+        .filter_map(|(var, value)| {
+            let case = var.strip_prefix(prefix)?.to_lowercase();
+            if case.is_empty() {
+                return None;
+            }
+            Some((var, case, value))
+        })
+        .collect();
+    matching.sort();
+
+    matching
+}
+
+// Environment variables are read when the test function is expanded so changes to the value of a
+// variable that was already set trigger a rebuild but newly set variables will not.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let prefix = parse_macro_input!(args as LitStr);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    let ty = match original.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] => (*pt.ty).clone(),
+        _ => {
+            return TokenStream::from(
+                Error::new(
+                    prefix.span(),
+                    "env_cases test functions must accept a single argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let vars = std::env::vars_os()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)));
+    let matching = matching_vars(&prefix.value(), vars);
+
+    // With no matching variables there are no cases to run but the body of the test function is
+    // still checked by the compiler.
+    if matching.is_empty() {
+        original.attrs = vec![parse_quote!(#[allow(dead_code)])];
+        return TokenStream::from(quote! { #original });
+    }
+
+    let vars = matching.iter().map(|(var, _, _)| var);
+    let case_attrs = matching.iter().map(|(var, case, value)| {
+        let arg = from_str_value(var, value, &ty);
+        quote! { #[simple_test_case::test_case(#arg; #case)] }
+    });
+
+    TokenStream::from(quote! {
+        #(const _: Option<&str> = option_env!(#vars);)*
+
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vars_are_matched_by_prefix() {
+        let vars = [
+            ("MATRIX_SQLITE", "sqlite::memory:"),
+            ("PATH", "/usr/bin"),
+            ("MATRIX_", "empty"),
+            ("MATRIX_POSTGRES", "postgres://localhost"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        let cases: Vec<(String, String)> = matching_vars("MATRIX_", vars)
+            .into_iter()
+            .map(|(_, case, value)| (case, value))
+            .collect();
+
+        assert_eq!(
+            cases,
+            vec![
+                ("postgres".to_string(), "postgres://localhost".to_string()),
+                ("sqlite".to_string(), "sqlite::memory:".to_string()),
+            ]
+        );
+    }
+}
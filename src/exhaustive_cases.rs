@@ -0,0 +1,84 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse::Nothing, parse_macro_input, parse_quote, Error, FnArg, ItemFn, Type};
+
+// 2^13 = 8192 cases, the largest power of two within the 10,000 case limit
+const MAX_PARAMS: usize = 13;
+
+// Every combination of n bools, counting up in binary from all false to all true with the first
+// parameter as the most significant bit.
+fn combinations(n: usize) -> Vec<Vec<bool>> {
+    (0..1usize << n)
+        .map(|i| (0..n).rev().map(|bit| i & (1 << bit) != 0).collect())
+        .collect()
+}
+
+fn all_bools(_fn: &ItemFn) -> bool {
+    let bool_ty: Type = parse_quote!(bool);
+
+    _fn.sig
+        .inputs
+        .iter()
+        .all(|fnarg| matches!(fnarg, FnArg::Typed(pt) if *pt.ty == bool_ty))
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    parse_macro_input!(args as Nothing);
+    let original = parse_macro_input!(input as ItemFn);
+    let n = original.sig.inputs.len();
+
+    if n == 0 || n > MAX_PARAMS || !all_bools(&original) {
+        return TokenStream::from(
+            Error::new_spanned(
+                &original.sig,
+                format!(
+                    "exhaustive_cases test functions must accept between 1 and {} bool arguments",
+                    MAX_PARAMS
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = combinations(n).into_iter().map(|values| {
+        let name = values
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join("_");
+        quote! { #[simple_test_case::test_case(#(#values),*; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_combinations_are_generated() {
+        assert_eq!(
+            combinations(2),
+            vec![
+                vec![false, false],
+                vec![false, true],
+                vec![true, false],
+                vec![true, true],
+            ]
+        );
+        assert_eq!(combinations(MAX_PARAMS).len(), 8192);
+    }
+
+    #[test]
+    fn only_bool_arguments_are_supported() {
+        let bools: ItemFn = parse_quote! { fn f(a: bool, b: bool) {} };
+        let mixed: ItemFn = parse_quote! { fn f(a: bool, b: u8) {} };
+
+        assert!(all_bools(&bools));
+        assert!(!all_bools(&mixed));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::util::{
+    case_attr, current_dir, expand_env, from_str_value, io_context, param_names_and_types,
+    slugify_label,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::fs;
+use syn::{parse_macro_input, Error, ItemFn, LitStr, Result, Type};
+
+// A single row of an Examples table along with the scenario it belongs to
+#[derive(Debug, PartialEq, Eq)]
+struct Example {
+    scenario: String,
+    line: usize,
+    cells: Vec<(String, String)>,
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let inner = line.trim().trim_start_matches('|');
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = vec![String::new()];
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('|') => cells.last_mut().unwrap().push('|'),
+                Some('n') => cells.last_mut().unwrap().push('\n'),
+                Some('\\') => cells.last_mut().unwrap().push('\\'),
+                Some(c) => cells.last_mut().unwrap().extend(['\\', c]),
+                None => cells.last_mut().unwrap().push('\\'),
+            },
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+
+    cells.iter().map(|c| c.trim().to_string()).collect()
+}
+
+// Only the parts of Gherkin needed to locate example tables are understood: each Examples table
+// belongs to the most recent Scenario (Outline) and everything else in the file is ignored.
+fn parse_feature(raw: &str) -> std::result::Result<Vec<Example>, String> {
+    let mut examples = Vec::new();
+    let mut scenario: Option<String> = None;
+    let mut in_examples = false;
+    let mut header: Option<Vec<String>> = None;
+
+    for (i, line) in raw.lines().enumerate() {
+        let n = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with('|') && in_examples {
+            let row = split_row(trimmed);
+            match &header {
+                None => header = Some(row),
+                Some(cols) if cols.len() != row.len() => {
+                    return Err(format!(
+                        "line {}: expected {} cells but found {}",
+                        n,
+                        cols.len(),
+                        row.len()
+                    ))
+                }
+                Some(cols) => examples.push(Example {
+                    scenario: scenario.clone().unwrap_or_default(),
+                    line: n,
+                    cells: cols.iter().cloned().zip(row).collect(),
+                }),
+            }
+            continue;
+        }
+
+        in_examples = false;
+        header = None;
+        let (keyword, rest) = trimmed.split_once(':').unwrap_or((trimmed, ""));
+        match keyword.trim() {
+            "Scenario" | "Scenario Outline" | "Scenario Template" | "Example" => {
+                scenario = Some(rest.trim().to_string())
+            }
+            "Examples" | "Scenarios" if scenario.is_none() => {
+                return Err(format!("line {}: Examples found outside of a Scenario", n))
+            }
+            "Examples" | "Scenarios" => in_examples = true,
+            _ => (),
+        }
+    }
+
+    Ok(examples)
+}
+
+fn load_features(span: Span, dir: &str) -> Result<Vec<(String, String, String)>> {
+    let err = |msg: String| {
+        Error::new(
+            span,
+            format!("Error loading test cases from {}: {}", dir, msg),
+        )
+    };
+    let io_err = |e: std::io::Error| err(e.to_string());
+    let expanded = expand_env(dir).map_err(err)?;
+    let joined = current_dir().map_err(io_err)?.join(expanded);
+    let abs_dir = joined
+        .canonicalize()
+        .map_err(io_context("canonicalize", &joined))
+        .map_err(io_err)?;
+    if !abs_dir.is_dir() {
+        return Err(err(format!("{} is not a directory", abs_dir.display())));
+    }
+
+    let mut features = Vec::new();
+    for entry in fs::read_dir(&abs_dir)
+        .map_err(io_context("read directory", &abs_dir))
+        .map_err(io_err)?
+    {
+        let path = entry
+            .map_err(io_context("read an entry in directory", &abs_dir))
+            .map_err(io_err)?
+            .path();
+        let stem = match path.file_stem() {
+            Some(stem) if path.extension().is_some_and(|ext| ext == "feature") => {
+                stem.to_string_lossy().into_owned()
+            }
+            _ => continue,
+        };
+        let raw = fs::read_to_string(&path)
+            .map_err(io_context("read", &path))
+            .map_err(io_err)?;
+        features.push((stem, path.to_string_lossy().into_owned(), raw));
+    }
+    features.sort();
+
+    if features.is_empty() {
+        return Err(err("no .feature files found".to_string()));
+    }
+
+    Ok(features)
+}
+
+// Cells are passed to the argument with the same name as their column, with cells being parsed
+// into the type of the argument where needed. Columns that are not used by the test function are
+// ignored.
+fn example_attr(
+    span: Span,
+    fname: &str,
+    stem: &str,
+    n: usize,
+    example: &Example,
+    params: &[(String, Type)],
+) -> Result<proc_macro2::TokenStream> {
+    let arg = |param: &String, ty: &Type| {
+        let (_, value) = example
+            .cells
+            .iter()
+            .find(|(col, _)| col == param)
+            .ok_or_else(|| {
+                let msg = format!(
+                    "{} line {}: the examples for scenario '{}' have no '{}' column",
+                    fname, example.line, example.scenario, param
+                );
+                Error::new(span, msg)
+            })?;
+        let source = format!("column '{}' of {} line {}", param, fname, example.line);
+
+        Ok(from_str_value(&source, value, ty))
+    };
+    let args = params
+        .iter()
+        .map(|(param, ty)| arg(param, ty))
+        .collect::<Result<Vec<_>>>()?;
+
+    let case = [stem, &example.scenario, &n.to_string()]
+        .iter()
+        .map(|s| slugify_label(s))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    Ok(case_attr(&args, &case))
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(args as LitStr);
+    let original = parse_macro_input!(input as ItemFn);
+    let span = dir.span();
+
+    let res = param_names_and_types(&original, "feature_cases").and_then(|params| {
+        let features = load_features(span, &dir.value())?;
+        let mut case_attrs = Vec::new();
+        let mut abs_paths = Vec::new();
+
+        for (stem, abs_path, raw) in features.iter() {
+            let fname = format!("{}.feature", stem);
+            let examples = parse_feature(raw)
+                .map_err(|e| Error::new(span, format!("invalid feature file {}: {}", fname, e)))?;
+
+            let mut counts: Vec<(&str, usize)> = Vec::new();
+            for example in examples.iter() {
+                let n = match counts.iter_mut().find(|(s, _)| *s == example.scenario) {
+                    Some((_, n)) => {
+                        *n += 1;
+                        *n
+                    }
+                    None => {
+                        counts.push((&example.scenario, 1));
+                        1
+                    }
+                };
+                case_attrs.push(example_attr(span, &fname, stem, n, example, &params)?);
+            }
+            abs_paths.push(abs_path);
+        }
+
+        if case_attrs.is_empty() {
+            return Err(Error::new(span, "no Examples tables found"));
+        }
+
+        Ok(quote! {
+            // Ensure that we are recompiled if any of the feature files change
+            #(const _: &str = include_str!(#abs_paths);)*
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &str = "
+Feature: Addition
+  # A comment
+  Scenario Outline: adding two numbers
+    Given I have entered <a> and <b>
+    Then the result should be <sum>
+
+    Examples:
+      | a | b  | sum |
+      | 1 | 2  | 3   |
+      | 0 | -1 | -1  |
+
+    Examples: escaped
+      | a | b   | sum  |
+      | x | y\\|z | xy\\|z |
+";
+
+    fn cells(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(c, v)| (c.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn examples_are_parsed() {
+        let examples = parse_feature(RAW).unwrap();
+
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[0].scenario, "adding two numbers");
+        assert_eq!(examples[1].line, 11);
+        assert_eq!(
+            examples[1].cells,
+            cells(&[("a", "0"), ("b", "-1"), ("sum", "-1")])
+        );
+        assert_eq!(
+            examples[2].cells,
+            cells(&[("a", "x"), ("b", "y|z"), ("sum", "xy|z")])
+        );
+    }
+
+    #[test]
+    fn invalid_tables_are_an_error() {
+        let mismatched = "Scenario: a\nExamples:\n| a | b |\n| 1 |\n";
+        let no_scenario = "Feature: a\nExamples:\n| a |\n| 1 |\n";
+
+        assert!(parse_feature(mismatched).is_err());
+        assert!(parse_feature(no_scenario).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    Error, Ident, ItemFn, Result, ReturnType, Type,
+};
+
+// The name of the function generated alongside each fixture that test cases call in order to
+// obtain its value.
+const SETUP_FN: &str = "__simple_test_case_setup";
+
+// fixture or fixture(guard) for fixtures returning a (value, guard) pair. Shared fixtures are only
+// created by suite for the functions it marks as shared.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FixtureOpts {
+    guard: bool,
+    shared: bool,
+}
+
+impl Parse for FixtureOpts {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut opts = Self::default();
+
+        while !input.is_empty() {
+            let opt: Ident = input.parse()?;
+            match opt.to_string().as_str() {
+                "guard" => opts.guard = true,
+                other => {
+                    return Err(Error::new(
+                        opt.span(),
+                        format!("unknown fixture option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: syn::Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(opts)
+    }
+}
+
+// A fixture is a function with no arguments that returns the value to inject into tests. It is
+// left as written and a module sharing its name is generated to hold the setup function used by
+// test cases. Modules and functions live in different namespaces so importing the fixture by name
+// (or via a glob import) brings both into scope.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let opts = parse_macro_input!(args as FixtureOpts);
+    let original = parse_macro_input!(input as ItemFn);
+
+    match setup_module(&original, &opts) {
+        Ok(module) => TokenStream::from(quote! {
+            #original
+            #module
+        }),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+// The setup function returns the guard before the value so that the binding generated for each
+// test case declares the guard first, resulting in it being dropped after the value.
+fn setup_module(_fn: &ItemFn, opts: &FixtureOpts) -> Result<proc_macro2::TokenStream> {
+    let sig = &_fn.sig;
+
+    if let Some(asyncness) = sig.asyncness {
+        return Err(Error::new(asyncness.span(), "fixtures can not be async"));
+    }
+    if !sig.inputs.is_empty() {
+        return Err(Error::new(
+            sig.inputs.span(),
+            "fixtures can not accept any arguments",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "fixtures can not be generic",
+        ));
+    }
+    let ty = match &sig.output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => {
+            return Err(Error::new(sig.ident.span(), "fixtures must return a value"))
+        }
+    };
+
+    let vis = &_fn.vis;
+    let name = &sig.ident;
+    let setup = format_ident!("{}", SETUP_FN);
+
+    let (ret, body) = if opts.shared {
+        (
+            quote! { ((), &'static #ty) },
+            quote! {
+                static SHARED: ::std::sync::OnceLock<#ty> = ::std::sync::OnceLock::new();
+                ((), SHARED.get_or_init(super::#name))
+            },
+        )
+    } else if opts.guard {
+        let (value_ty, guard_ty) = match &**ty {
+            Type::Tuple(t) if t.elems.len() == 2 => (&t.elems[0], &t.elems[1]),
+            ty => {
+                return Err(Error::new(
+                    ty.span(),
+                    "fixture(guard) functions must return a (value, guard) pair",
+                ))
+            }
+        };
+        (
+            quote! { (#guard_ty, #value_ty) },
+            quote! {
+                let (value, guard) = super::#name();
+                (guard, value)
+            },
+        )
+    } else {
+        (quote! { ((), #ty) }, quote! { ((), super::#name()) })
+    };
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis mod #name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub fn #setup() -> #ret {
+                #body
+            }
+        }
+    })
+}
+
+// The setup module for a fixture whose value is created once (on first use) and then shared by
+// reference between every test case that uses it
+pub(crate) fn shared_setup_module(_fn: &ItemFn) -> Result<proc_macro2::TokenStream> {
+    let opts = FixtureOpts {
+        guard: false,
+        shared: true,
+    };
+
+    setup_module(_fn, &opts)
+}
+
+// Test function arguments marked with #[fixture] are bound to the value of the fixture with the
+// same name. The guard returned alongside it is held until the end of the test.
+pub(crate) fn fixture_binding(pat: &syn::Pat, ty: &syn::Type) -> Result<proc_macro2::TokenStream> {
+    let name = match pat {
+        syn::Pat::Ident(p) if p.by_ref.is_none() && p.subpat.is_none() => &p.ident,
+        _ => {
+            return Err(Error::new(
+                pat.span(),
+                "arguments without a value in the test case must be named after a fixture",
+            ))
+        }
+    };
+    let setup = format_ident!("{}", SETUP_FN, span = name.span());
+    let guard = format_ident!("__fixture_guard_{}", name);
+
+    Ok(quote! {
+        let (#guard, #pat): (_, #ty) = #name::#setup();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn invalid_fixtures_are_rejected() {
+        let fns: Vec<ItemFn> = vec![
+            parse_quote!(
+                async fn f() -> u8 {
+                    1
+                }
+            ),
+            parse_quote!(
+                fn f(n: u8) -> u8 {
+                    n
+                }
+            ),
+            parse_quote!(
+                fn f<T: Default>() -> T {
+                    T::default()
+                }
+            ),
+            parse_quote!(
+                fn f() {}
+            ),
+        ];
+
+        for f in fns.iter() {
+            let opts = FixtureOpts::default();
+            assert!(setup_module(f, &opts).is_err(), "{}", quote!(#f));
+        }
+    }
+
+    #[test]
+    fn guarded_fixtures_must_return_a_pair() {
+        let opts: FixtureOpts = parse_quote!(guard);
+        let pair: ItemFn = parse_quote!(
+            fn f() -> (u8, Guard) {
+                (1, Guard)
+            }
+        );
+        let single: ItemFn = parse_quote!(
+            fn f() -> u8 {
+                1
+            }
+        );
+
+        assert_eq!(
+            opts,
+            FixtureOpts {
+                guard: true,
+                shared: false
+            }
+        );
+        assert!(setup_module(&pair, &opts).is_ok());
+        assert!(setup_module(&single, &opts).is_err());
+    }
+
+    #[test]
+    fn fixture_arguments_must_be_plain_names() {
+        let ty: syn::Type = parse_quote!(u8);
+
+        assert!(fixture_binding(&parse_quote!(db), &ty).is_ok());
+        assert!(fixture_binding(&parse_quote!(mut db), &ty).is_ok());
+        assert!(fixture_binding(&parse_quote!((a, b)), &ty).is_err());
+    }
+}
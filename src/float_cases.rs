@@ -0,0 +1,155 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Error, Expr, FnArg, Ident, ItemFn, Result, Token, Type,
+};
+
+// float_cases with an optional `skip = [name, ...]` for values that should not be used
+struct FloatCases {
+    skip: Vec<Ident>,
+}
+
+impl Parse for FloatCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut skip = Vec::new();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "skip" => {
+                    let content;
+                    bracketed!(content in input);
+                    skip.extend(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown float_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { skip })
+    }
+}
+
+// The special values of a float type, with the values that are awkward to write out (subnormals
+// and the neighbours of one) being built from their bit patterns.
+fn special_values(ty: &Ident) -> Vec<(&'static str, Expr)> {
+    let max_subnormal: Expr = match ty.to_string().as_str() {
+        "f32" => parse_quote!(f32::from_bits(0x007f_ffff)),
+        _ => parse_quote!(f64::from_bits(0x000f_ffff_ffff_ffff)),
+    };
+
+    vec![
+        ("nan", parse_quote!(#ty::NAN)),
+        ("neg_nan", parse_quote!(-#ty::NAN)),
+        ("infinity", parse_quote!(#ty::INFINITY)),
+        ("neg_infinity", parse_quote!(#ty::NEG_INFINITY)),
+        ("zero", parse_quote!(0.0)),
+        ("neg_zero", parse_quote!(-0.0)),
+        ("min_subnormal", parse_quote!(#ty::from_bits(1))),
+        ("neg_min_subnormal", parse_quote!(-#ty::from_bits(1))),
+        ("max_subnormal", max_subnormal),
+        ("min_positive", parse_quote!(#ty::MIN_POSITIVE)),
+        ("neg_min_positive", parse_quote!(-#ty::MIN_POSITIVE)),
+        ("epsilon", parse_quote!(#ty::EPSILON)),
+        ("one", parse_quote!(1.0)),
+        ("neg_one", parse_quote!(-1.0)),
+        ("one_plus_epsilon", parse_quote!(1.0 + #ty::EPSILON)),
+        (
+            "one_minus_half_epsilon",
+            parse_quote!(#ty::from_bits((1.0 as #ty).to_bits() - 1)),
+        ),
+        ("max", parse_quote!(#ty::MAX)),
+        ("min", parse_quote!(#ty::MIN)),
+    ]
+}
+
+fn float_type(_fn: &ItemFn) -> Option<Ident> {
+    let ty = match _fn.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] => (*pt.ty).clone(),
+        _ => return None,
+    };
+    let f32_ty: Type = parse_quote!(f32);
+    let f64_ty: Type = parse_quote!(f64);
+
+    match ty {
+        ty if ty == f32_ty => Some(parse_quote!(f32)),
+        ty if ty == f64_ty => Some(parse_quote!(f64)),
+        _ => None,
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let FloatCases { skip } = parse_macro_input!(args as FloatCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let ty = match float_type(&original) {
+        Some(ty) => ty,
+        None => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "float_cases test functions must accept a single f32 or f64 argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let values = special_values(&ty);
+    if let Some(s) = skip.iter().find(|s| !values.iter().any(|(n, _)| *s == n)) {
+        let names: Vec<_> = values.iter().map(|(n, _)| *n).collect();
+        return TokenStream::from(
+            Error::new(
+                s.span(),
+                format!(
+                    "unknown float case: {} (expected one of {})",
+                    s,
+                    names.join(", ")
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = values
+        .iter()
+        .filter(|(name, _)| !skip.iter().any(|s| s == name))
+        .map(|(name, value)| quote! { #[simple_test_case::test_case(#value; #name)] });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_single_float_arguments_are_supported() {
+        let cases: Vec<(ItemFn, Option<Ident>)> = vec![
+            (parse_quote! { fn f(x: f32) {} }, Some(parse_quote!(f32))),
+            (parse_quote! { fn f(x: f64) {} }, Some(parse_quote!(f64))),
+            (parse_quote! { fn f(x: u8) {} }, None),
+            (parse_quote! { fn f(x: f64, y: f64) {} }, None),
+        ];
+
+        for (_fn, expected) in cases {
+            assert_eq!(float_type(&_fn), expected);
+        }
+    }
+}
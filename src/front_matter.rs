@@ -0,0 +1,162 @@
+// Parsing of the front matter block that can be included at the top of dir_cases fixture files:
+//
+//   +++
+//   # comments are allowed
+//   expected = "some value"
+//   retries = 3
+//   ignore = "flaky on CI"
+//   +++
+//   the remainder of the file is the fixture body
+//
+// The block is parsed using the same TOML parser as regression_cases, but only scalar values
+// (strings, numbers and booleans) are supported as each value is passed directly as a test
+// argument.
+use crate::{json::Value, toml::parse_toml};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+const DELIMITER: &str = "+++";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FrontMatter {
+    pub(crate) values: Vec<(String, Value)>,
+    // The byte offset of the start of the body following the front matter
+    pub(crate) body_offset: usize,
+}
+
+impl FrontMatter {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        self.values.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+// The expression for a front matter value when it is passed as a test argument
+pub(crate) fn value_tokens(value: &Value) -> Result<TokenStream, String> {
+    match value {
+        Value::Str(s) => Ok(quote! { #s }),
+        Value::Bool(b) => Ok(quote! { #b }),
+        Value::Number(n) => {
+            let (neg, digits) = match n.strip_prefix('-') {
+                Some(digits) => (quote! { - }, digits),
+                None => (quote! {}, n.as_str()),
+            };
+            let lit: Literal = digits
+                .parse()
+                .map_err(|_| format!("invalid number: {}", n))?;
+            Ok(quote! { #neg #lit })
+        }
+        value => Err(format!("unsupported value: {}", value.kind())),
+    }
+}
+
+// Returns None if the file does not start with a front matter block
+pub(crate) fn parse_front_matter(raw: &str) -> Result<Option<FrontMatter>, String> {
+    let mut lines = raw.split_inclusive('\n');
+    let start = match lines.next() {
+        Some(first) if first.trim_end() == DELIMITER => first.len(),
+        _ => return Ok(None),
+    };
+    let mut offset = start;
+
+    for line in lines {
+        if line.trim() == DELIMITER {
+            // The opening delimiter is replaced by a blank line so that line numbers in any
+            // errors are relative to the start of the file
+            let block = format!("\n{}", &raw[start..offset]);
+            let values = match parse_toml(&block)? {
+                Value::Object(values) => values,
+                _ => unreachable!("TOML documents are always tables"),
+            };
+            if let Some((key, value)) = values
+                .iter()
+                .find(|(_, v)| matches!(v, Value::Array(_) | Value::Object(_)))
+            {
+                return Err(format!(
+                    "unsupported value for `{}`: expected a string, number or boolean but found {}",
+                    key,
+                    value.kind()
+                ));
+            }
+
+            return Ok(Some(FrontMatter {
+                values,
+                body_offset: offset + line.len(),
+            }));
+        }
+        offset += line.len();
+    }
+
+    Err(format!(
+        "unclosed front matter: expected a closing {}",
+        DELIMITER
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_matter_is_parsed() {
+        let raw = "+++\n# comment\nname = \"a \\\"b\\\"\"\nraw = 'c\\d'\nn = -1_000\nf = 1.5\nok = true # trailing\n+++\nbody\n";
+        let fm = parse_front_matter(raw).unwrap().unwrap();
+
+        assert_eq!(
+            fm.values,
+            vec![
+                ("name".to_string(), Value::Str("a \"b\"".to_string())),
+                ("raw".to_string(), Value::Str("c\\d".to_string())),
+                ("n".to_string(), Value::Number("-1000".to_string())),
+                ("f".to_string(), Value::Number("1.5".to_string())),
+                ("ok".to_string(), Value::Bool(true)),
+            ]
+        );
+        assert_eq!(&raw[fm.body_offset..], "body\n");
+    }
+
+    #[test]
+    fn errors_report_the_line_in_the_file() {
+        let err = parse_front_matter("+++\nkey = 1\nkey = 2\n+++\n").unwrap_err();
+
+        assert!(err.starts_with("line 3:"), "{}", err);
+    }
+
+    #[test]
+    fn values_are_converted_to_tokens() {
+        let cases = [
+            (Value::Str("a".to_string()), "\"a\""),
+            (Value::Bool(true), "true"),
+            (Value::Number("-1.5".to_string()), "- 1.5"),
+            (Value::Number("0xff".to_string()), "0xff"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(value_tokens(&value).unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn files_without_front_matter_are_skipped() {
+        assert_eq!(parse_front_matter("just a body\n+++\n"), Ok(None));
+    }
+
+    #[test]
+    fn invalid_front_matter_is_an_error() {
+        let cases = [
+            "+++\nkey = 1\n",
+            "+++\nnot a pair\n+++\n",
+            "+++\nkey = [1, 2]\n+++\n",
+            "+++\n[table]\nkey = 1\n+++\n",
+            "+++\nkey = 1\nkey = 2\n+++\n",
+            "+++\nkey = \"unterminated\n+++\n",
+        ];
+
+        for raw in cases {
+            assert!(
+                parse_front_matter(raw).is_err(),
+                "{:?} should be invalid",
+                raw
+            );
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::util::{current_dir, expand_env, io_context, stable_hash};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::{fs, path::Path};
+use syn::{parse_macro_input, parse_quote, Error, FnArg, ItemFn, LitStr, Result, Type};
+
+struct Entry {
+    fname: String,
+    abs_path: String,
+    bytes: Vec<u8>,
+}
+
+// The length of the hash prefix used to name each case, extended as needed to keep names unique
+const PREFIX_LEN: usize = 8;
+
+// cargo-fuzz names corpus entries using the SHA-1 hash of their contents so the file name is used
+// directly when it is a hash, falling back to hashing the contents for any other entries.
+fn entry_hashes(entries: &[Entry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|Entry { fname, bytes, .. }| {
+            if fname.len() >= PREFIX_LEN && fname.chars().all(|c| c.is_ascii_hexdigit()) {
+                fname.to_ascii_lowercase()
+            } else {
+                stable_hash(bytes)
+            }
+        })
+        .collect()
+}
+
+fn case_names(hashes: &[String]) -> Vec<String> {
+    hashes
+        .iter()
+        .map(|hash| {
+            let mut len = PREFIX_LEN;
+            while len < hash.len()
+                && hashes
+                    .iter()
+                    .any(|other| other != hash && other.get(..len) == hash.get(..len))
+            {
+                len += 1;
+            }
+
+            format!("corpus_{}", &hash[..len])
+        })
+        .collect()
+}
+
+fn load_corpus(span: Span, dir: &str) -> Result<Vec<Entry>> {
+    let err = |msg: String| {
+        Error::new(
+            span,
+            format!("Error loading fuzz corpus from {}: {}", dir, msg),
+        )
+    };
+    let io_err = |e: std::io::Error| err(e.to_string());
+    let expanded = expand_env(dir).map_err(err)?;
+    let joined = current_dir().map_err(io_err)?.join(expanded);
+    let abs_dir = joined
+        .canonicalize()
+        .map_err(io_context("canonicalize", &joined))
+        .map_err(io_err)?;
+    if !abs_dir.is_dir() {
+        return Err(err(format!("{} is not a directory", abs_dir.display())));
+    }
+
+    let mut entries = Vec::new();
+    let read_entry = |path: &Path| fs::read(path).map_err(io_context("read", path));
+    for entry in fs::read_dir(&abs_dir)
+        .map_err(io_context("read directory", &abs_dir))
+        .map_err(io_err)?
+    {
+        let entry = entry
+            .map_err(io_context("read an entry in directory", &abs_dir))
+            .map_err(io_err)?;
+        let path = entry.path();
+        let fname = entry.file_name().to_string_lossy().into_owned();
+        if path.is_file() && !fname.starts_with('.') {
+            let bytes = read_entry(&path).map_err(io_err)?;
+            entries.push(Entry {
+                abs_path: path.to_string_lossy().into_owned(),
+                fname,
+                bytes,
+            });
+        }
+    }
+
+    if entries.is_empty() {
+        return Err(err("the corpus is empty".to_string()));
+    }
+    entries.sort_by(|a, b| a.fname.cmp(&b.fname));
+
+    Ok(entries)
+}
+
+fn has_correct_args(_fn: &ItemFn) -> bool {
+    let bytes_ty: Type = parse_quote!(&[u8]);
+
+    _fn.sig.inputs.len() == 1
+        && matches!(&_fn.sig.inputs[0], FnArg::Typed(pt) if *pt.ty == bytes_ty)
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(args as LitStr);
+    let original = parse_macro_input!(input as ItemFn);
+
+    if !has_correct_args(&original) {
+        return TokenStream::from(
+            Error::new(
+                dir.span(),
+                "fuzz_corpus_cases test functions must accept (data: &[u8]) as their only argument",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let entries = match load_corpus(dir.span(), &dir.value()) {
+        Ok(entries) => entries,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let names = case_names(&entry_hashes(&entries));
+    let case_attrs = entries.iter().zip(names).map(|(entry, name)| {
+        let abs_path = &entry.abs_path;
+        quote! { #[simple_test_case::test_case(include_bytes!(#abs_path); #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_are_named_by_hash_prefix() {
+        let entry = |fname: &str, bytes: &[u8]| Entry {
+            fname: fname.to_string(),
+            abs_path: String::new(),
+            bytes: bytes.to_vec(),
+        };
+        let entries = vec![
+            entry("3f786850e387550fdab836ed7e6dc881de23001b", b""),
+            entry("3f786850f0000000000000000000000000000000", b""),
+            entry("crash-input", b"abc"),
+        ];
+        let names = case_names(&entry_hashes(&entries));
+
+        assert_eq!(
+            names,
+            vec![
+                "corpus_3f786850e".to_string(),
+                "corpus_3f786850f".to_string(),
+                format!("corpus_{}", stable_hash("abc")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    boundary_cases::ExplicitCase,
+    test_cases::parse_explicit_cases,
+    util::{expand_env, io_context},
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::path::PathBuf;
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Error, Expr, ExprLit, ExprMacro, ItemFn, Lit, LitStr, Result, Token,
+};
+
+// generated_cases("cases.rs") relative to OUT_DIR, or generated_cases(dir, "cases.rs") where dir
+// may be given as env!("NAME")
+struct GeneratedCases {
+    span: Span,
+    dir: Option<String>,
+    file: String,
+}
+
+// String literals and env!("NAME") are both resolved at expansion time
+fn resolve_str(expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.value()),
+        Expr::Macro(ExprMacro { mac, .. }) if mac.path.is_ident("env") => {
+            let name: LitStr = mac.parse_body()?;
+            std::env::var(name.value()).map_err(|_| {
+                Error::new_spanned(
+                    expr,
+                    format!("environment variable {} is not set", name.value()),
+                )
+            })
+        }
+        _ => Err(Error::new_spanned(
+            expr,
+            "expected a string literal or env!(\"NAME\")",
+        )),
+    }
+}
+
+impl Parse for GeneratedCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+        let args = args.iter().map(resolve_str).collect::<Result<Vec<_>>>()?;
+
+        match args.as_slice() {
+            [file] => Ok(Self {
+                span,
+                dir: None,
+                file: file.clone(),
+            }),
+            [dir, file] => Ok(Self {
+                span,
+                dir: Some(dir.clone()),
+                file: file.clone(),
+            }),
+            _ => Err(Error::new(
+                span,
+                "expected generated_cases(\"cases.rs\") or generated_cases(dir, \"cases.rs\")",
+            )),
+        }
+    }
+}
+
+// Without an explicit directory the file is read from OUT_DIR, which is where build scripts are
+// expected to write generated files.
+fn case_file_path(span: Span, dir: Option<String>, file: &str) -> Result<PathBuf> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => std::env::var("OUT_DIR").map_err(|_| {
+            Error::new(
+                span,
+                "OUT_DIR is not set: add a build script to the crate or give the directory explicitly",
+            )
+        })?,
+    };
+    let file = expand_env(file).map_err(|e| Error::new(span, e))?;
+
+    Ok(PathBuf::from(dir).join(file))
+}
+
+// Generated files contain a comma separated list of cases using the same syntax as test_case,
+// with each case wrapped in parentheses: `(1, 2; "small"), (3, 4; "large"; ignore),`. Line
+// comments are permitted so build scripts can note where the cases came from.
+fn parse_case_file(raw: &str) -> std::result::Result<Vec<ExplicitCase>, String> {
+    let tokens: proc_macro2::TokenStream = raw.parse().map_err(|e| format!("{}", e))?;
+    parse_explicit_cases
+        .parse2(tokens)
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let GeneratedCases { span, dir, file } = parse_macro_input!(args as GeneratedCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let res = case_file_path(span, dir, &file).and_then(|path| {
+        let raw = std::fs::read_to_string(&path)
+            .map_err(io_context("read", &path))
+            .map_err(|e| Error::new(span, format!("Error loading generated cases: {}", e)))?;
+        let cases = parse_case_file(&raw).map_err(|e| {
+            Error::new(
+                span,
+                format!("invalid generated cases in {}: {}", path.display(), e),
+            )
+        })?;
+        if cases.is_empty() {
+            return Err(Error::new(
+                span,
+                format!("no cases found in {}", path.display()),
+            ));
+        }
+
+        let abs_path = path.to_string_lossy().into_owned();
+        let case_attrs = cases.iter().map(|case| {
+            let args = case.args.iter();
+            let (name, rest) = (&case.name, &case.rest);
+            quote! { #[simple_test_case::test_case(#(#args),*; #name #rest)] }
+        });
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the generated file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_files_are_parsed() {
+        let raw = "
+// generated from schema.json
+(1, 2; \"small\"),
+(vec![1, 2], \"x\".to_string(); \"vec\"; ignore),
+";
+        let cases = parse_case_file(raw).unwrap();
+        let names: Vec<_> = cases.iter().map(|c| c.name.value()).collect();
+
+        assert_eq!(names, vec!["small", "vec"]);
+        assert_eq!(cases[1].args.len(), 2);
+        assert!(parse_case_file("(1, 2 \"missing separator\")").is_err());
+    }
+
+    #[test]
+    fn directories_can_be_given_using_env() {
+        let parsed: GeneratedCases =
+            syn::parse_quote!(env!("CARGO_MANIFEST_DIR"), "tests/generated/cases.rs");
+
+        assert_eq!(
+            parsed.dir,
+            Some(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        );
+        assert!(
+            syn::parse2::<GeneratedCases>(quote!(env!("SIMPLE_TEST_CASE_UNSET"), "a")).is_err()
+        );
+        assert!(syn::parse2::<GeneratedCases>(quote!(concat!("a", "b"))).is_err());
+    }
+}
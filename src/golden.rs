@@ -0,0 +1,179 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, Expr, Ident, ItemFn, LitStr, ReturnType, Token,
+};
+
+// assert_golden!(actual, path)
+struct AssertGolden {
+    actual: Expr,
+    path: Expr,
+}
+
+impl Parse for AssertGolden {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let actual: Expr = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let path: Expr = input.parse()?;
+        if input.peek(Token![,]) {
+            let _: Token![,] = input.parse()?;
+        }
+
+        Ok(Self { actual, path })
+    }
+}
+
+// The comparison is written out in full at the call site given that proc-macro crates are unable to
+// export regular functions for the generated code to call.
+pub(crate) fn golden_assertion(actual: &Expr, path: &Expr) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let __golden_actual = #actual;
+            let __golden_actual: &str = ::std::convert::AsRef::<str>::as_ref(&__golden_actual);
+            let __golden_path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#path);
+            let __golden_update = ::std::env::var("UPDATE_GOLDEN").map_or(false, |v| v == "1");
+
+            // A line based diff using the longest common subsequence of the two outputs
+            fn __golden_diff(expected: &str, actual: &str) -> String {
+                let e: Vec<&str> = expected.lines().collect();
+                let a: Vec<&str> = actual.lines().collect();
+                let mut lcs = vec![vec![0usize; a.len() + 1]; e.len() + 1];
+                for i in (0..e.len()).rev() {
+                    for j in (0..a.len()).rev() {
+                        lcs[i][j] = if e[i] == a[j] {
+                            lcs[i + 1][j + 1] + 1
+                        } else {
+                            lcs[i + 1][j].max(lcs[i][j + 1])
+                        };
+                    }
+                }
+
+                let (mut i, mut j, mut diff) = (0, 0, String::new());
+                while i < e.len() || j < a.len() {
+                    if i < e.len() && j < a.len() && e[i] == a[j] {
+                        diff.push_str(&format!("  {}\n", e[i]));
+                        (i, j) = (i + 1, j + 1);
+                    } else if i < e.len() && (j == a.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                        diff.push_str(&format!("- {}\n", e[i]));
+                        i += 1;
+                    } else {
+                        diff.push_str(&format!("+ {}\n", a[j]));
+                        j += 1;
+                    }
+                }
+                if e == a {
+                    diff.push_str("(the outputs only differ in their line endings)\n");
+                }
+
+                diff
+            }
+
+            if __golden_update {
+                if let Some(parent) = __golden_path.parent() {
+                    ::std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+                        panic!("unable to create directory {}: {}", parent.display(), e)
+                    });
+                }
+                ::std::fs::write(&__golden_path, __golden_actual).unwrap_or_else(|e| {
+                    panic!("unable to update golden file {}: {}", __golden_path.display(), e)
+                });
+            } else {
+                match ::std::fs::read_to_string(&__golden_path) {
+                    Ok(expected) if expected == __golden_actual => (),
+                    Ok(expected) => panic!(
+                        "output does not match golden file {} (set UPDATE_GOLDEN=1 to update it)\n--- expected\n+++ actual\n{}",
+                        __golden_path.display(),
+                        __golden_diff(&expected, __golden_actual),
+                    ),
+                    Err(e) => panic!(
+                        "unable to read golden file {}: {} (set UPDATE_GOLDEN=1 to create it)",
+                        __golden_path.display(),
+                        e,
+                    ),
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn inner(input: TokenStream) -> TokenStream {
+    let AssertGolden { actual, path } = parse_macro_input!(input as AssertGolden);
+
+    TokenStream::from(golden_assertion(&actual, &path))
+}
+
+// golden_cases("path/to/dir") with an optional `extension = "ext"` for the golden files
+struct GoldenCases {
+    dir: String,
+    extension: String,
+}
+
+impl Parse for GoldenCases {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        let dir: LitStr = input.parse()?;
+        let mut extension = "expected".to_string();
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "extension" => extension = input.parse::<LitStr>()?.value(),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown golden_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            dir: dir.value().trim_end_matches('/').to_string(),
+            extension,
+        })
+    }
+}
+
+// golden_cases is placed beneath any test_case attributes so that it is applied to each of the
+// generated test cases, allowing the golden file to be named after the case.
+pub(crate) fn cases_inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let GoldenCases { dir, extension } = parse_macro_input!(args as GoldenCases);
+    let mut _fn = parse_macro_input!(input as ItemFn);
+
+    if matches!(_fn.sig.output, ReturnType::Default) {
+        return TokenStream::from(
+            Error::new_spanned(
+                &_fn.sig,
+                "golden_cases test functions must return the output to be compared",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let ident = _fn.sig.ident.to_string();
+    let name = ident.strip_prefix("r#").unwrap_or(&ident);
+    let path: Expr = parse_quote!(#dir.to_string() + "/" + #name + "." + #extension);
+    // The original body is kept as a nested function so that its return type is preserved
+    let mut output_fn = _fn.clone();
+    output_fn.attrs.clear();
+    output_fn.sig.ident = parse_quote!(__golden_output);
+    let actual: Expr = match _fn.sig.asyncness {
+        Some(_) => parse_quote!(__golden_output().await),
+        None => parse_quote!(__golden_output()),
+    };
+    let actual: Expr = parse_quote!(::std::string::ToString::to_string(&#actual));
+    let assertion = golden_assertion(&actual, &path);
+
+    _fn.sig.output = ReturnType::Default;
+    _fn.block = parse_quote!({
+        #output_fn
+        #assertion
+    });
+
+    TokenStream::from(quote! { #_fn })
+}
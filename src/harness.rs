@@ -0,0 +1,184 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, FnArg, Ident, ItemFn, LitBool, LitStr, Result, Token,
+    Type,
+};
+
+// dir_harness("path/to/dir") with optional `extension = "ext"` and `recursive = bool`
+struct DirHarness {
+    dir: LitStr,
+    extension: Option<String>,
+    recursive: bool,
+}
+
+impl Parse for DirHarness {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let dir: LitStr = input.parse()?;
+        let mut extension = None;
+        let mut recursive = false;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "extension" => extension = Some(input.parse::<LitStr>()?.value()),
+                "recursive" => recursive = input.parse::<LitBool>()?.value,
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown dir_harness option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            dir,
+            extension,
+            recursive,
+        })
+    }
+}
+
+// The contents of each file are passed either as a string or as raw bytes
+fn read_contents(_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    let str_ty: Type = parse_quote!(&str);
+    let bytes_ty: Type = parse_quote!(&[u8]);
+    let arg_ty = |ix: usize| match _fn.sig.inputs.iter().nth(ix) {
+        Some(FnArg::Typed(pt)) => Some(&*pt.ty),
+        _ => None,
+    };
+
+    if _fn.sig.inputs.len() != 2 || arg_ty(0) != Some(&str_ty) {
+        return None;
+    }
+
+    match arg_ty(1) {
+        Some(ty) if *ty == str_ty => Some(quote! { ::std::fs::read_to_string }),
+        Some(ty) if *ty == bytes_ty => Some(quote! { ::std::fs::read }),
+        _ => None,
+    }
+}
+
+// Unlike the other attributes in this crate, the files in the directory are listed when the tests
+// are run rather than when they are compiled. The generated main function builds a libtest-mimic
+// trial for each file so the test binary needs `harness = false` set in Cargo.toml.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let DirHarness {
+        dir,
+        extension,
+        recursive,
+    } = parse_macro_input!(args as DirHarness);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let read = match read_contents(&original) {
+        Some(read) => read,
+        None => {
+            return TokenStream::from(
+                Error::new(
+                    dir.span(),
+                    "dir_harness functions must accept (path: &str, contents: &str) or (path: &str, contents: &[u8]) as arguments",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let ident = &original.sig.ident;
+    let extension = match extension {
+        Some(ext) => quote! { Some(#ext) },
+        None => quote! { None },
+    };
+
+    TokenStream::from(quote! {
+        #original
+
+        fn main() {
+            fn list_files(
+                dir: &::std::path::Path,
+                rel_dir: &str,
+                extension: Option<&str>,
+                recursive: bool,
+                files: &mut Vec<String>,
+            ) {
+                let entries = ::std::fs::read_dir(dir).unwrap_or_else(|e| {
+                    panic!("unable to read directory {}: {}", dir.display(), e)
+                });
+                for entry in entries {
+                    let entry = entry.unwrap_or_else(|e| {
+                        panic!("unable to read an entry in directory {}: {}", dir.display(), e)
+                    });
+                    let fname = entry.file_name().to_string_lossy().into_owned();
+                    let rel_path = format!("{}/{}", rel_dir, fname);
+                    let path = entry.path();
+                    if path.is_dir() {
+                        if recursive {
+                            list_files(&path, &rel_path, extension, recursive, files);
+                        }
+                    } else if extension.map(|ext| fname.ends_with(&format!(".{}", ext))).unwrap_or(true) {
+                        files.push(rel_path);
+                    }
+                }
+            }
+
+            let root = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+            let mut files = Vec::new();
+            list_files(&root.join(#dir), #dir.trim_end_matches('/'), #extension, #recursive, &mut files);
+            files.sort();
+
+            let args = ::libtest_mimic::Arguments::from_args();
+            let trials = files
+                .into_iter()
+                .map(|path| {
+                    ::libtest_mimic::Trial::test(path.clone(), move || {
+                        let contents = #read(root.join(&path))
+                            .map_err(|e| format!("unable to read {}: {}", path, e))?;
+                        ::std::panic::catch_unwind(|| #ident(&path, &contents)).map_err(|e| {
+                            let msg = e
+                                .downcast_ref::<&str>()
+                                .map(|s| s.to_string())
+                                .or_else(|| e.downcast_ref::<String>().cloned())
+                                .unwrap_or_else(|| "test panicked".to_string());
+                            ::libtest_mimic::Failed::from(msg)
+                        })
+                    })
+                })
+                .collect();
+
+            ::libtest_mimic::run(&args, trials).exit();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contents_can_be_read_as_strings_or_bytes() {
+        let cases: Vec<(ItemFn, Option<&str>)> = vec![
+            (
+                parse_quote! { fn f(p: &str, c: &str) {} },
+                Some("read_to_string"),
+            ),
+            (parse_quote! { fn f(p: &str, c: &[u8]) {} }, Some("read")),
+            (parse_quote! { fn f(p: &str) {} }, None),
+            (parse_quote! { fn f(p: String, c: &str) {} }, None),
+        ];
+
+        for (_fn, expected) in cases {
+            let read = read_contents(&_fn).map(|r| r.to_string());
+            let expected = expected.map(|f| {
+                let f = Ident::new(f, proc_macro2::Span::call_site());
+                quote!(::std::fs::#f).to_string()
+            });
+            assert_eq!(read, expected);
+        }
+    }
+}
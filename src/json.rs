@@ -0,0 +1,372 @@
+// A minimal JSON parser for reading test vectors at expansion time. Numbers are kept as the text
+// that was written so that they can be emitted as Rust literals without losing precision.
+//
+// Nesting is limited to MAX_DEPTH arrays and objects so that malformed input can't overflow the
+// stack of the compiler.
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "a boolean",
+            Self::Number(_) => "a number",
+            Self::Str(_) => "a string",
+            Self::Array(_) => "an array",
+            Self::Object(_) => "an object",
+        }
+    }
+}
+
+pub(crate) fn parse_json(raw: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: raw.char_indices().peekable(),
+        raw,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    match parser.chars.peek() {
+        None => Ok(value),
+        Some(&(i, _)) => Err(parser.error_at(i, "trailing characters after JSON value")),
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    raw: &'a str,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error_at(&self, offset: usize, msg: &str) -> String {
+        let line = self.raw[..offset].matches('\n').count() + 1;
+        format!("line {}: {}", line, msg)
+    }
+
+    fn error(&mut self, msg: &str) -> String {
+        let offset = self.chars.peek().map(|&(i, _)| i).unwrap_or(self.raw.len());
+        self.error_at(offset, msg)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some((_, c)) if c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => {
+                Err(self.error_at(i, &format!("expected '{}', found '{}'", expected, c)))
+            }
+            None => Err(self.error(&format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.peek().map(|&(_, c)| c) {
+            Some('n') => self.keyword("null", Value::Null),
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('"') => self.string().map(Value::Str),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(self.error(&format!("unexpected character '{}'", c))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, String>) -> Result<Value, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!(
+                "arrays and objects are nested more than {} deep",
+                MAX_DEPTH
+            )));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        for expected in keyword.chars() {
+            match self.chars.next() {
+                Some((_, c)) if c == expected => (),
+                _ => return Err(self.error(&format!("invalid literal: expected {}", keyword))),
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let mut n = String::new();
+        while let Some(&(_, c)) = self.chars.peek() {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                break;
+            }
+            n.push(c);
+            self.chars.next();
+        }
+
+        match is_json_number(&n) && matches!(n.parse::<f64>(), Ok(f) if f.is_finite()) {
+            true => Ok(Value::Number(n)),
+            false => Err(self.error(&format!("invalid number: {}", n))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(s),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '"')) => s.push('"'),
+                    Some((_, '\\')) => s.push('\\'),
+                    Some((_, '/')) => s.push('/'),
+                    Some((_, 'b')) => s.push('\u{8}'),
+                    Some((_, 'f')) => s.push('\u{c}'),
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 'r')) => s.push('\r'),
+                    Some((_, 't')) => s.push('\t'),
+                    Some((_, 'u')) => s.push(self.unicode_escape()?),
+                    Some((i, c)) => {
+                        return Err(self.error_at(i, &format!("invalid escape sequence \\{}", c)))
+                    }
+                    None => break,
+                },
+                Some((i, c)) if c < ' ' => {
+                    return Err(self.error_at(i, "unescaped control character in string"))
+                }
+                Some((_, c)) => s.push(c),
+                None => break,
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    // \uXXXX escapes, including surrogate pairs for characters outside of the BMP
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !(self.chars.next().is_some_and(|(_, c)| c == '\\')
+                && self.chars.next().is_some_and(|(_, c)| c == 'u'))
+            {
+                return Err(self.error("unpaired unicode surrogate"));
+            }
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("invalid unicode surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.chars.next().and_then(|(_, c)| c.to_digit(16)) {
+                Some(d) => code = code * 16 + d,
+                None => return Err(self.error("invalid unicode escape")),
+            }
+        }
+
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if matches!(self.chars.peek(), Some((_, ']'))) {
+            self.chars.next();
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => (),
+                Some((_, ']')) => return Ok(Value::Array(values)),
+                _ => return Err(self.error("expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace();
+        if matches!(self.chars.peek(), Some((_, '}'))) {
+            self.chars.next();
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            if fields.iter().any(|(k, _)| *k == key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => (),
+                Some((_, '}')) => return Ok(Value::Object(fields)),
+                _ => return Err(self.error("expected ',' or '}' in object")),
+            }
+        }
+    }
+}
+
+// The JSON number grammar: an optional '-', an integer part without leading zeros and optional
+// fraction and exponent parts
+fn is_json_number(n: &str) -> bool {
+    fn digits(s: &str) -> (&str, &str) {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        s.split_at(end)
+    }
+
+    let n = n.strip_prefix('-').unwrap_or(n);
+    let (int, mut rest) = digits(n);
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+    if let Some(frac) = rest.strip_prefix('.') {
+        let (frac, r) = digits(frac);
+        if frac.is_empty() {
+            return false;
+        }
+        rest = r;
+    }
+    if let Some(exp) = rest.strip_prefix(['e', 'E']) {
+        let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+        let (exp, r) = digits(exp);
+        if exp.is_empty() {
+            return false;
+        }
+        rest = r;
+    }
+
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_is_parsed() {
+        let raw =
+            r#"[{"name": "a\n\u00e9\ud83d\ude00", "n": -1.5e3, "ok": true, "v": [1, null]}, {}]"#;
+
+        assert_eq!(
+            parse_json(raw),
+            Ok(Value::Array(vec![
+                Value::Object(vec![
+                    ("name".to_string(), Value::Str("a\né😀".to_string())),
+                    ("n".to_string(), Value::Number("-1.5e3".to_string())),
+                    ("ok".to_string(), Value::Bool(true)),
+                    (
+                        "v".to_string(),
+                        Value::Array(vec![Value::Number("1".to_string()), Value::Null])
+                    ),
+                ]),
+                Value::Object(vec![]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn numbers_are_validated() {
+        let valid = ["0", "-0", "12", "1.5", "-1.5e3", "1E+2", "0.5e-7"];
+        let invalid = ["01", "-", "1.", ".5", "1e", "1.5.5", "1e5e5", "--1", "1-"];
+
+        for n in valid {
+            assert!(parse_json(n).is_ok(), "{} should be valid", n);
+        }
+        for n in invalid {
+            assert!(parse_json(n).is_err(), "{} should be invalid", n);
+        }
+    }
+
+    #[test]
+    fn malformed_strings_are_an_error() {
+        let cases = [
+            ("\"\\x\"", "invalid escape sequence \\x"),
+            ("\"\\u12\"", "invalid unicode escape"),
+            ("\"\\ud83d\"", "unpaired unicode surrogate"),
+            ("\"\\ud83dx\"", "unpaired unicode surrogate"),
+            ("\"\\ud83d\\u0041\"", "invalid unicode surrogate pair"),
+            ("\"\\ude00\"", "invalid unicode escape"),
+            ("\"a\nb\"", "unescaped control character in string"),
+            ("\"\\", "unterminated string"),
+        ];
+
+        for (raw, expected) in cases {
+            let err = parse_json(raw).unwrap_err();
+            assert!(err.ends_with(expected), "{:?}: {}", raw, err);
+        }
+    }
+
+    #[test]
+    fn trailing_commas_are_an_error() {
+        for raw in ["[1,]", "[1, 2, ]", "{\"a\": 1,}", "[,]", "{,}"] {
+            assert!(parse_json(raw).is_err(), "{:?} should be invalid", raw);
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let ok = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(parse_json(&ok).is_ok());
+
+        let err = parse_json(&"[{\"a\": ".repeat(100_000)).unwrap_err();
+        assert!(err.contains("nested more than 128 deep"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        let cases = [
+            "[1, 2",
+            "{\"a\": 1,}",
+            "{\"a\": 1, \"a\": 2}",
+            "\"unterminated",
+            "[1] [2]",
+            "nul",
+            "+1",
+            "\"\\x\"",
+        ];
+
+        for raw in cases {
+            assert!(parse_json(raw).is_err(), "{:?} should be invalid", raw);
+        }
+    }
+}
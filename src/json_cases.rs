@@ -0,0 +1,267 @@
+use crate::{
+    json::{parse_json, Value},
+    util::{case_attr, param_names_and_types, read_fixture_file},
+};
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, GenericArgument, Ident, ItemFn, LitStr, PathArguments,
+    Result, Token, Type,
+};
+
+// json_cases("path/to/vectors.json") with an optional `name = "field"` to name each case
+struct JsonCases {
+    span: Span,
+    path: String,
+    name: String,
+}
+
+impl Parse for JsonCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let path: LitStr = input.parse()?;
+        let mut name = "name".to_string();
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "name" => name = input.parse::<LitStr>()?.value(),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown json_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            span,
+            path: path.value(),
+            name,
+        })
+    }
+}
+
+// The type parameter of a single argument generic type such as Option<T> or Vec<T>
+fn type_param<'a>(ty: &'a Type, outer: &str) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(p) => &p.path,
+        _ => return None,
+    };
+    let last = path.segments.last()?;
+    if last.ident != outer {
+        return None;
+    }
+
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Values are converted into expressions based on the type of the parameter they are being passed
+// to: strings become String or &str as required, arrays become Vec, slice or array expressions,
+// null is only permitted for Option parameters and integers passed as floats are given a
+// fractional part. Anything else is left to rustc to type check.
+pub(crate) fn value_to_expr(
+    value: &Value,
+    ty: &Type,
+) -> std::result::Result<proc_macro2::TokenStream, String> {
+    let string_ty: Type = parse_quote!(String);
+    let float_tys: [Type; 2] = [parse_quote!(f32), parse_quote!(f64)];
+
+    if let Some(inner) = type_param(ty, "Option") {
+        return match value {
+            Value::Null => Ok(quote! { None }),
+            value => value_to_expr(value, inner).map(|v| quote! { Some(#v) }),
+        };
+    }
+
+    match value {
+        Value::Null => Err("null can only be passed to Option arguments".to_string()),
+        Value::Bool(b) => Ok(quote! { #b }),
+        Value::Number(n) => {
+            let n = match float_tys.contains(ty) && !n.contains(['.', 'e', 'E']) {
+                true => format!("{}.0", n),
+                false => n.clone(),
+            };
+            let (neg, digits) = match n.strip_prefix('-') {
+                Some(digits) => (quote! { - }, digits),
+                None => (quote! {}, n.as_str()),
+            };
+            let lit: Literal = digits
+                .parse()
+                .map_err(|_| format!("invalid number: {}", n))?;
+            Ok(quote! { #neg #lit })
+        }
+        Value::Str(s) if *ty == string_ty => Ok(quote! { #s.to_string() }),
+        Value::Str(s) => Ok(quote! { #s }),
+        Value::Array(values) => {
+            let unsupported = || format!("arrays can not be passed as {}", quote!(#ty));
+            let (elem_ty, by_ref, vec) = match ty {
+                Type::Reference(r) => match &*r.elem {
+                    Type::Slice(s) => (&*s.elem, true, false),
+                    Type::Array(a) => (&*a.elem, true, false),
+                    _ => return Err(unsupported()),
+                },
+                Type::Array(a) => (&*a.elem, false, false),
+                ty => (type_param(ty, "Vec").ok_or_else(unsupported)?, false, true),
+            };
+            let elems = values
+                .iter()
+                .map(|v| value_to_expr(v, elem_ty))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(match (by_ref, vec) {
+                (true, _) => quote! { &[#(#elems),*] },
+                (false, true) => quote! { vec![#(#elems),*] },
+                (false, false) => quote! { [#(#elems),*] },
+            })
+        }
+        Value::Object(_) => Err("objects are not supported as argument values".to_string()),
+    }
+}
+
+fn resolve_cases(
+    span: Span,
+    root: Value,
+    params: &[(String, Type)],
+    name_field: &str,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let elements = match root {
+        Value::Array(elements) => elements,
+        other => {
+            return Err(Error::new(
+                span,
+                format!("expected a top level array but found {}", other.kind()),
+            ))
+        }
+    };
+
+    elements
+        .into_iter()
+        .enumerate()
+        .map(|(i, element)| {
+            let n = i + 1;
+            let err = |msg: String| Error::new(span, format!("element {}: {}", n, msg));
+            let fields = match element {
+                Value::Object(fields) => fields,
+                other => {
+                    return Err(err(format!(
+                        "expected an object but found {}",
+                        other.kind()
+                    )))
+                }
+            };
+            let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+
+            let args = params
+                .iter()
+                .map(|(param, ty)| match get(param) {
+                    Some(value) => value_to_expr(value, ty)
+                        .map_err(|e| err(format!("field '{}': {}", param, e))),
+                    None => Err(err(format!("missing field '{}'", param))),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let name = match get(name_field) {
+                Some(Value::Str(name)) => name.clone(),
+                Some(Value::Null) | None => format!("case_{}", n),
+                Some(other) => {
+                    return Err(err(format!(
+                        "the '{}' field must be a string but found {}",
+                        name_field,
+                        other.kind()
+                    )))
+                }
+            };
+
+            Ok(case_attr(&args, &name))
+        })
+        .collect()
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let JsonCases { span, path, name } = parse_macro_input!(args as JsonCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let res = param_names_and_types(&original, "json_cases").and_then(|params| {
+        let (raw, abs_path) = read_fixture_file(span, &path)?;
+        let root = parse_json(&raw)
+            .map_err(|e| Error::new(span, format!("invalid json file {}: {}", path, e)))?;
+        let case_attrs = resolve_cases(span, root, &params, &name)?;
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the json file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expr(value: &str, ty: Type) -> String {
+        let value = parse_json(value).unwrap();
+        value_to_expr(&value, &ty).unwrap().to_string()
+    }
+
+    #[test]
+    fn values_are_converted_based_on_the_parameter_type() {
+        let cases: Vec<(&str, Type, proc_macro2::TokenStream)> = vec![
+            ("\"a\"", parse_quote!(&str), quote!("a")),
+            ("\"a\"", parse_quote!(String), quote!("a".to_string())),
+            ("-3", parse_quote!(i64), quote!(-3)),
+            ("3", parse_quote!(f64), quote!(3.0)),
+            ("null", parse_quote!(Option<u8>), quote!(None)),
+            ("1", parse_quote!(Option<u8>), quote!(Some(1))),
+            ("[1, 2]", parse_quote!(&[u8]), quote!(&[1, 2])),
+            ("[1, 2]", parse_quote!([u8; 2]), quote!([1, 2])),
+            (
+                "[\"a\"]",
+                parse_quote!(Vec<String>),
+                quote!(vec!["a".to_string()]),
+            ),
+        ];
+
+        for (value, ty, expected) in cases {
+            assert_eq!(expr(value, ty), expected.to_string(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn missing_fields_are_an_error() {
+        let root = parse_json(r#"[{"name": "a", "n": 1}, {"name": "b"}]"#).unwrap();
+        let params = vec![("n".to_string(), parse_quote!(u8))];
+        let res = resolve_cases(Span::call_site(), root, &params, "name");
+
+        match res {
+            Err(e) => assert!(
+                e.to_string().contains("element 2: missing field 'n'"),
+                "{}",
+                e
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
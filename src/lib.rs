@@ -11,9 +11,11 @@
 //!
 //! And that's it.
 //!
-//! There is no additional support for custom assertions, fixtures etc. That said, if you want or
-//! need a more complicated testing set up, additional attribute macros should play nice with
-//! `simple_test_case` provided you follow the advice below.
+//! The crate also provides opt-in helpers for common patterns such as shared `#[fixture]`
+//! arguments and generating cases from files on disk (see the documentation of each macro), but
+//! there is no support for custom assertions. If you want or need a more complicated testing set
+//! up, additional attribute macros should play nice with `simple_test_case` provided you follow
+//! the advice below.
 //!
 //!
 //! ## Usage
@@ -37,7 +39,8 @@
 //!
 //! ### Invalid
 //! Here the `#[test]` attribute is provided before all instances of `test_case`. This will cause
-//! the compiler to complain about functions used as tests not being allowed to have any arguments.
+//! the compiler to complain about functions used as tests not being allowed to have any arguments,
+//! alongside an error from `test_case` asking you to move `#[test]` below all of the test cases.
 //! ```ignore
 //! use simple_test_case::test_case;
 //!
@@ -56,8 +59,11 @@
 //! ## Additional attributes
 //!
 //! `test_case` preserves all attributes beneath it, forwarding them on to the individual generated
-//! test functions. As an example, the standard library `should_panic` attribute works just fine as
-//! shown below (just make sure to provide your test cases first as described above):
+//! test functions (lint levels such as `#[allow(...)]` and any `#[cfg(...)]`, including those set
+//! using `#[cfg_attr(...)]`, are also applied to the module generated to hold them). Arguments
+//! that a test function ignores are not reported as unused variables. As an example, the standard
+//! library `should_panic` attribute works just fine as shown below (just make sure to provide your
+//! test cases first as described above):
 //!
 //! ```rust
 //! use simple_test_case::test_case;
@@ -93,8 +99,8 @@
 //!
 //! ## How does it work?
 //!
-//! You are encouraged to read the source of the macro itself (the macro plus associated helper
-//! functions are under 150 lines of code) but the general idea is as follows:
+//! You are encouraged to read the source of the macro itself (see `src/test_case.rs`) but the
+//! general idea is as follows:
 //!
 //! - Collect all `test_case` (or `simple_test_case::test_case`) attributes, each of which maps a
 //!   set of function arguments to a test case name.
@@ -173,36 +179,1543 @@
 //!     test::test_main_static(&[&small_example, &large_example])
 //! }
 //! ```
+#![allow(clippy::test_attr_in_doctest)]
 use proc_macro::TokenStream;
 
+mod archive_cases;
+mod async_test_case;
+mod bench_case;
+mod boundary_cases;
+mod case_sets;
+mod case_table;
+mod compile_fail_cases;
+mod csv_cases;
 mod dir_cases;
+mod dir_pair_cases;
+mod env_cases;
+mod exhaustive_cases;
+mod feature_cases;
+mod fixture;
+mod float_cases;
+mod front_matter;
+mod fuzz_corpus_cases;
+mod generated_cases;
+mod golden;
+#[cfg(feature = "harness")]
+mod harness;
+mod json;
+mod json_cases;
+mod line_cases;
+mod locale_cases;
+mod loom_cases;
+mod manifest;
+mod markdown_cases;
+mod once_setup;
+mod path_cases;
+mod permutation_cases;
+mod proptest_regressions;
+mod random_cases;
+mod range_cases;
+mod regression_cases;
+mod subset_cases;
+mod suite;
+mod target_cases;
 mod test_case;
+mod test_cases;
+mod test_matrix;
+mod toml;
+mod type_cases;
+mod unicode_cases;
 mod util;
+mod vector_cases;
 
 /// A simple parameterised test helper
 ///
-/// See the main module documentation for usage details.
+/// See the main module documentation for usage details. A test function without a test attribute
+/// beneath its cases generates a warning rather than silently never being run: anything named
+/// `test` (such as `#[tokio::test]`) or ending in `test` (such as `#[wasm_bindgen_test]`) is
+/// recognised, along with `#[bench]` and `#[kani::proof]`. The warning is reported as a use of a
+/// deprecated item, so it can be silenced for custom harnesses with `#[allow(deprecated)]` on the
+/// test function.
+///
+/// The cases are generated inside of a module named after the test function, so the cases of
+/// `fn TestHTTP2Frames` are found in `TestHTTP2Frames::` (with `non_snake_case` allowed on the
+/// module).
+///
+/// Test functions can not be generic (or take `impl Trait` arguments) as each case is a plain
+/// function binding its arguments with the types of the original parameters. Use `type_cases`
+/// to run a generic test for a list of types.
+///
+/// Additional attributes that should only be applied to an individual case can be given after the
+/// case name, along with the `ignore` shorthand for `#[ignore]`. The `miri_only` and `skip_miri`
+/// shorthands expand to `#[cfg(miri)]` and `#[cfg_attr(miri, ignore)]` respectively for cases that
+/// should only (or never) be run under miri. When the `serial` feature is enabled `serial` and
+/// `serial(key, ...)` attach `#[serial_test::serial]` to the generated case so that it is not run
+/// in parallel with other serial tests (sharing any of the given keys). Cases can also be placed
+/// in a nested module (inside of the module named after the test function) using `module = a::b`.
+///
+/// Tags can be attached to a case using `tags = [slow, network]`, which places the case in a
+/// nested `tag_<name>` module for each tag (sorted by name and following any `module`). This gives
+/// tagged cases a stable path such as `example::tag_network::tag_slow::large` so that they can be
+/// selected with `cargo test tag_slow` or a nextest filter expression such as
+/// `test(/::tag_slow::/)`:
+///
+/// ```ignore
+/// #[test_case(1, 2; "small")]
+/// #[test_case(1_000_000, 2_000_000; "large"; ignore, skip_miri)]
+/// #[test_case(0, 1; "wrong"; #[should_panic])]
+/// #[test_case(0, 0; "zero"; module = edge_cases)]
+/// #[test_case(1 << 20, 1 << 21; "huge"; tags = [slow])]
+/// #[test]
+/// fn example(n: usize, double: usize) {
+///   assert_eq!(n * 2, double);
+/// }
+/// ```
+///
+/// When the `insta` feature is enabled the body of each case is run with the insta snapshot suffix
+/// set to the name of the case, so that snapshots asserted within a parameterised test are stored
+/// separately for each case rather than overwriting one another.
+///
+/// When the `tracing` feature is enabled each case installs its own `tracing_subscriber` that
+/// writes to the libtest output capture, and runs inside of a span named after the case. Logs
+/// emitted by a case are then only printed (tagged with the case name) if that case fails.
+///
+/// When the `kani` feature is enabled a `#[kani::proof]` harness named `<case>_proof` is generated
+/// alongside each case (gated on `cfg(kani)`) so that case tables can be reused as concrete inputs
+/// for verification. Harnesses are not generated for async tests or tests that return a value.
+///
+/// When the `shard` feature is enabled and the `TEST_SHARD` environment variable is set to `i/n`
+/// at compile time, only the cases in the `i`th of `n` shards are generated. Cases are assigned to
+/// shards by a stable hash of their path so that large generated suites can be split across CI
+/// machines by building each with a different `TEST_SHARD`.
+///
+/// When the `manifest` feature is enabled a JSON manifest listing the name, path, arguments,
+/// attributes and fixture files of each generated case is written to
+/// `$OUT_DIR/simple_test_case_manifest/<function>.json` for use by external tooling. `OUT_DIR` is
+/// only set for crates with a build script, so no manifests are written for crates without one.
+/// Test functions sharing a name in different modules share a manifest file, with the last one
+/// to be compiled taking precedence.
+///
+/// When the `temp_dir` feature is enabled, arguments marked with `#[temp_dir]` are not given a
+/// value by the test cases and are instead given the path of a new `tempfile::TempDir` for each
+/// case (as either a `&Path` or a `PathBuf`), which is removed once the test completes:
+///
+/// ```ignore
+/// #[test_case("a.txt"; "text file")]
+/// #[test_case("nested/b.bin"; "nested file")]
+/// #[test]
+/// fn write_creates_parents(name: &str, #[temp_dir] dir: &Path) {
+///   write_file(&dir.join(name), b"data").unwrap();
+///   assert!(dir.join(name).exists());
+/// }
+/// ```
+///
+/// When the `case_names` feature is enabled the generated module also contains a
+/// `pub const CASE_NAMES: &[&str]` listing the path of each case relative to the module (such as
+/// `"small"` or `"edge_cases::zero"`), which can be used to assert on coverage from other tests.
 #[proc_macro_attribute]
 pub fn test_case(args: TokenStream, input: TokenStream) -> TokenStream {
     test_case::inner(args, input)
 }
 
-/// Generate a set of parameterised tests based on the contents of a directory
+/// Define an async test case and apply the test attribute for its runtime in a single attribute
 ///
-/// NOTE: The path given will be resolved relative to the root of your cargo workspace and the test
-/// function that you provide must accept to `&str` arguments: the path to the file loaded for the
-/// test case and the contents of that file. The files are read at compile time so
-/// adding/removing/modifying files in the given directory should trigger a recompile of your
-/// tests.
+/// Each `async_test_case` is rewritten as a `test_case` with the same arguments, name and per-case
+/// options, followed by the test attribute for the runtime given by `runtime = <runtime>` (which
+/// can be `tokio`, `tokio_multi_thread` or `async_std` and defaults to `tokio`). This removes the
+/// need to place `#[tokio::test]` beneath the cases, so the test function must not have a test
+/// attribute of its own. The runtime may be given on any of the cases but must be the same for all
+/// of them.
 ///
 /// ```ignore
-/// #[dir_cases("resources/test_data")]
+/// #[async_test_case(1, 2; "small"; runtime = tokio)]
+/// #[async_test_case(100, 200; "large")]
+/// async fn double(n: usize, expected: usize) {
+///   assert_eq!(double_async(n).await, expected);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn async_test_case(args: TokenStream, input: TokenStream) -> TokenStream {
+    async_test_case::inner(args, input)
+}
+
+/// A function-like alternative to `test_case` with the test function and its cases written together
+///
+/// The test function (along with any attributes such as `#[test]`) is given first, followed by a
+/// table of cases mapping the arguments for each case to its name. Because the cases are not
+/// attributes, the order in which they are written relative to the other attributes of the test
+/// function doesn't matter, which is useful when tests are generated by other macros:
+///
+/// ```ignore
+/// test_cases! {
+///   #[test]
+///   fn double(n: usize, expected: usize) {
+///     assert_eq!(n * 2, expected);
+///   }
+///
+///   cases: [
+///     (1, 2) => "small",
+///     (100, 200) => "large",
+///   ]
+/// }
+/// ```
+#[proc_macro]
+pub fn test_cases(input: TokenStream) -> TokenStream {
+    test_cases::inner(input)
+}
+
+/// A function-like wrapper around `test_case` for when the order of attributes can't be controlled
+///
+/// The test function is given first (along with any attributes it already has), followed by a
+/// `cases` block containing each case in the same `args; "name"; options` syntax accepted by
+/// `test_case`. This is mainly useful for tests emitted by other `macro_rules` macros that have
+/// already attached `#[test]` to the function:
+///
+/// ```ignore
+/// param_test! {
+///   #[test]
+///   fn double(n: usize, expected: usize) {
+///     assert_eq!(n * 2, expected);
+///   }
+///
+///   cases {
+///     (1, 2; "small"),
+///     (100, 200; "large"; ignore),
+///   }
+/// }
+/// ```
+#[proc_macro]
+pub fn param_test(input: TokenStream) -> TokenStream {
+    test_cases::param_test_inner(input)
+}
+
+/// Generate a test case for each value in an integer range
+///
+/// The range must be given using integer literals and may be either exclusive (`0..16`) or
+/// inclusive (`0..=16`). Every `n`th value can be used by setting `step = n`. Each case is named
+/// after its value, with negative values being given a `neg_` prefix:
+///
+/// ```ignore
+/// #[range_cases(0..=16, step = 4)]
 /// #[test]
-/// fn example(path: &str, contents: &str) {
-///   // ..
+/// fn header_sizes(len: usize) {
+///   assert_eq!(Header::parse(&Header::new(len).encode()).len(), len);
 /// }
 /// ```
+///
+/// Ranges resulting in more than 10,000 cases are rejected.
 #[proc_macro_attribute]
-pub fn dir_cases(args: TokenStream, input: TokenStream) -> TokenStream {
-    dir_cases::inner(args, input)
+pub fn range_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    range_cases::inner(args, input)
+}
+
+/// Generate a fixed number of deterministic, pseudo-random test cases from a seed
+///
+/// A seed is derived for each case from the `seed` given to the attribute and each case is named
+/// after its seed, so the cases are identical between runs and can be re-run individually. If
+/// the test function accepts a single `u64` argument it is given the seed of the case directly.
+/// Otherwise a generator function can be given using `gen`: it is called with the seed of the
+/// case and should return the value of the argument for a function with a single argument or a
+/// tuple of the arguments for a function with multiple arguments.
+///
+/// ```ignore
+/// fn points(seed: u64) -> (i32, i32) {
+///   let mut rng = StdRng::seed_from_u64(seed);
+///   (rng.gen(), rng.gen())
+/// }
+///
+/// #[random_cases(count = 32, seed = 0xDEADBEEF, gen = points)]
+/// #[test]
+/// fn distance_is_symmetric(x: i32, y: i32) {
+///   assert_eq!(distance(x, y), distance(y, x));
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn random_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    random_cases::inner(args, input)
+}
+
+/// Generate test cases using the standard boundary values of integer and float arguments
+///
+/// Integer arguments are given `0`, `1`, `MAX - 1` and `MAX` (along with `MIN`, `MIN + 1` and
+/// `-1` for signed integers) while float arguments are given zero and one of each sign, `MIN`,
+/// `MAX`, `MIN_POSITIVE`, `EPSILON`, both infinities and `NAN`. Specific values can be left out
+/// using `skip = [nan, infinity]`.
+///
+/// With no arguments every combination of the boundary values of every argument is tested. If
+/// the names of some of the arguments are given then only those arguments are given boundary
+/// values and each `test_case` beneath `boundary_cases` (which should omit those arguments)
+/// is run with every combination of them instead.
+///
+/// ```ignore
+/// #[boundary_cases]
+/// #[test]
+/// fn checked_add_never_panics(a: u32, b: u32) {
+///   let _ = a.checked_add(b);
+/// }
+///
+/// #[boundary_cases(len, skip = [max])]
+/// #[test_case("hello"; "ascii")]
+/// #[test_case("héllo"; "unicode")]
+/// #[test]
+/// fn truncate(s: &str, len: usize) {
+///   assert!(truncate(s, len).len() <= len);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn boundary_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    boundary_cases::inner(args, input)
+}
+
+/// Generate test cases covering the special values of a float argument
+///
+/// Cases are generated for `NAN` (of both signs), both infinities, zero and negative zero, the
+/// smallest and largest subnormal values, `MIN_POSITIVE`, `EPSILON`, the neighbouring values of
+/// one, `MAX` and `MIN`. Each case is named after its value (`nan`, `neg_zero`, `min_subnormal`
+/// and so on) and individual values can be left out using `skip = [name, ...]`. The test
+/// function must accept a single `f32` or `f64` argument.
+///
+/// ```ignore
+/// #[float_cases(skip = [nan, neg_nan])]
+/// #[test]
+/// fn round_trips_through_text(x: f64) {
+///   assert_eq!(x.to_string().parse::<f64>().unwrap().to_bits(), x.to_bits());
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn float_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    float_cases::inner(args, input)
+}
+
+/// Generate a test case for each ordering of a list of values
+///
+/// The single argument of the test function is given each permutation of the list as a `Vec`,
+/// slice or array depending on its type, with cases being named after the indices of the
+/// elements in the order they are given (`perm_0_2_1` and so on). Lists with more than 10,000
+/// permutations must specify `max = n`, in which case `n` permutations are sampled evenly from
+/// the full set.
+///
+/// ```ignore
+/// #[permutation_cases([3, 1, 2])]
+/// #[test]
+/// fn sorting_is_order_independent(v: Vec<u8>) {
+///   let mut v = v;
+///   v.sort();
+///   assert_eq!(v, vec![1, 2, 3]);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn permutation_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    permutation_cases::inner(args, input)
+}
+
+/// Generate a test case for each subset of a list of values
+///
+/// The single argument of the test function is given each subset of the list as a `Vec`, slice
+/// or array depending on its type, with the elements kept in the order they were given. Cases
+/// are named after the indices of the elements they contain (`subset_0_2` and so on) and the
+/// empty subset is named `empty`. The size of the subsets can be bounded using `min_size = n` and
+/// `max_size = n`, and is required to be when there would otherwise be more than 10,000 cases.
+///
+/// ```ignore
+/// #[subset_cases(["read", "write", "exec"], min_size = 1)]
+/// #[test]
+/// fn permissions_round_trip(perms: &[&str]) {
+///   assert_eq!(Permissions::from_names(perms).names(), perms);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn subset_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    subset_cases::inner(args, input)
+}
+
+/// Generate test cases from a built in corpus of strings that commonly break text handling code
+///
+/// The corpus covers empty and whitespace only strings, control characters, byte order marks,
+/// zero width and bidirectional override characters, right to left and mixed direction text,
+/// combining characters, characters outside of the basic multilingual plane, emoji sequences,
+/// characters with unusual case mappings, noncharacters, escaped lone surrogates and very long
+/// strings. The test function must accept a single `&str` or `String` argument and individual
+/// cases can be left out using `skip = [name, ...]`.
+///
+/// ```ignore
+/// #[unicode_cases(skip = [nul])]
+/// #[test]
+/// fn round_trips(s: &str) {
+///   assert_eq!(decode(&encode(s)).unwrap(), s);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn unicode_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    unicode_cases::inner(args, input)
+}
+
+/// Generate a test case for every combination of the `bool` arguments of a test function
+///
+/// All `2^n` combinations of values are generated, with each case being named after its values
+/// (`false_true_false` and so on), guaranteeing coverage of every combination of flags without
+/// needing to list them by hand. Test functions must only take `bool` arguments and may take at
+/// most 13 of them.
+///
+/// ```ignore
+/// #[exhaustive_cases]
+/// #[test]
+/// fn options_are_independent(verbose: bool, color: bool, unicode: bool) {
+///   let opts = Options { verbose, color, unicode };
+///   assert_eq!(Options::parse(&opts.to_args()), opts);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn exhaustive_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    exhaustive_cases::inner(args, input)
+}
+
+/// Generate test cases from a built in corpus of locale formatted numbers, dates and times
+///
+/// The corpus covers different grouping and decimal separators (including non-breaking and narrow
+/// non-breaking spaces), non-ASCII digits, negative and currency formats, ambiguous values such as
+/// `1,234` and `03/04/2026`, and dates written using different orderings, month names and
+/// calendars. Entries that are only produced by the locale data of one platform are marked with
+/// the corresponding `cfg` so that they only run there. Each case is named after its entry and
+/// `kind = number` or `kind = date` restricts the corpus to numbers or to dates and times, with
+/// individual entries being left out using `skip = [name, ...]`. The test function must accept a
+/// single `&str` argument.
+///
+/// ```ignore
+/// #[locale_cases(kind = number, skip = [exponent])]
+/// #[test]
+/// fn amounts_never_panic(s: &str) {
+///   let _ = parse_amount(s);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn locale_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    locale_cases::inner(args, input)
+}
+
+/// Generate test cases from a built in corpus of pathological filesystem paths
+///
+/// The corpus covers empty paths, `..` traversal, trailing and repeated separators, hidden files,
+/// unicode and control characters, very deep nesting and over long components. Entries that only
+/// have special meaning on one platform (UNC and verbatim paths, drive letters and reserved names
+/// on Windows; the root directory and backslashes on Unix) are marked with the corresponding
+/// `cfg` so that they only run there. Each case is named after its entry (`unc`, `traversal` and
+/// so on) and individual entries can be left out using `skip = [name, ...]`. The test function
+/// must accept a single `&str`, `String`, `&Path` or `PathBuf` argument.
+///
+/// ```ignore
+/// #[path_cases(skip = [nul_byte])]
+/// #[test]
+/// fn sanitised_paths_stay_inside_the_root(p: &Path) {
+///   assert!(sanitise(p).starts_with("/srv/root"));
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn path_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    path_cases::inner(args, input)
+}
+
+/// Generate a cfg gated variant of each test case for each of the given targets
+///
+/// Targets may be `unix`, `windows`, `wasm` or any of the common values of `target_os` (`linux`,
+/// `macos`, `android` and so on). The test function must take the name of the target as its final
+/// `&str` argument, with the remaining arguments being provided by `test_case` attributes given
+/// beneath `target_cases`. Each case is named `<case>_<target>` and is only compiled when building
+/// for that target, which keeps deliberately platform specific behaviour in a single test. Test
+/// functions that only take the target name do not need any `test_case` attributes.
+///
+/// ```ignore
+/// #[target_cases(unix, windows)]
+/// #[test_case("a/b"; "nested")]
+/// #[test_case("a"; "flat")]
+/// #[test]
+/// fn native_paths(path: &str, target: &str) {
+///   let expected = match target {
+///     "windows" => path.replace('/', "\\"),
+///     _ => path.to_string(),
+///   };
+///   assert_eq!(to_native(path), expected);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn target_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    target_cases::inner(args, input)
+}
+
+/// Generate a test case for each of a list of types from a generic test function
+///
+/// The test function must have a single generic type parameter and no arguments. A case named
+/// after each of the given types is generated that calls the test function with that type, so
+/// the example below generates the cases `case_u8`, `case_u16`, `case_u32` and `case_u64`:
+///
+/// ```ignore
+/// #[type_cases(u8, u16, u32, u64)]
+/// #[test]
+/// fn round_trip<T: Encode + Decode + PartialEq + Debug + From<u8>>() {
+///   let n = T::from(42);
+///   assert_eq!(T::decode(&n.encode()), n);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn type_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    type_cases::inner(args, input)
+}
+
+/// Generate a test running each row of a table of cases held in a struct
+///
+/// The struct must be given a `case_table` attribute naming the table of rows (a const or static
+/// array or slice of the struct) and the function to run for each row, which is called with a
+/// reference to the row. Every row is run even if an earlier row fails, with the generated test
+/// failing at the end if any of them panicked. A field of the struct implementing `Display` can
+/// be given using `name` to identify failing rows, otherwise they are identified by their index.
+///
+/// The generated test is named after the struct: the example below generates a test named
+/// `parse_case_cases`.
+///
+/// ```ignore
+/// #[derive(TestCases)]
+/// #[case_table(rows = PARSE_CASES, run = check_parse, name = input)]
+/// struct ParseCase {
+///   input: &'static str,
+///   expected: Option<i64>,
+/// }
+///
+/// const PARSE_CASES: &[ParseCase] = &[
+///   ParseCase { input: "42", expected: Some(42) },
+///   ParseCase { input: "nope", expected: None },
+/// ];
+///
+/// fn check_parse(case: &ParseCase) {
+///   assert_eq!(case.input.parse().ok(), case.expected);
+/// }
+/// ```
+#[proc_macro_derive(TestCases, attributes(case_table))]
+pub fn derive_test_cases(input: TokenStream) -> TokenStream {
+    case_table::inner(input)
+}
+
+/// Define a named set of test cases that can be applied to multiple test functions
+///
+/// The cases are given using the same table syntax as `test_cases!` and the set can then be
+/// applied to any number of test functions using `#[use_cases(name)]`. Case sets are defined as
+/// `macro_rules` macros so a set must be defined before it is used, either earlier in the same
+/// module or in another module of the crate in which case it can be referred to by its path.
+///
+/// ```ignore
+/// define_cases!(documents, [
+///   ("{}") => "empty object",
+///   ("[1, 2, 3]") => "array",
+/// ]);
+///
+/// #[use_cases(documents)]
+/// #[test]
+/// fn round_trip(doc: &str) {
+///   assert_eq!(parse(doc).unwrap().to_string(), doc);
+/// }
+///
+/// #[use_cases(documents)]
+/// #[test]
+/// fn validates(doc: &str) {
+///   assert!(validate(doc).is_ok());
+/// }
+/// ```
+#[proc_macro]
+pub fn define_cases(input: TokenStream) -> TokenStream {
+    case_sets::define_inner(input)
+}
+
+/// Apply one or more case sets defined using `define_cases!` to a test function
+///
+/// See `define_cases!` for details. Multiple sets can be applied at once using
+/// `#[use_cases(set_a, set_b)]` and they may be combined with `test_case` attributes given
+/// beneath `use_cases`.
+#[proc_macro_attribute]
+pub fn use_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    case_sets::use_inner(args, input)
+}
+
+/// Apply one or more case sets defined using `define_cases!` to every test function in a module
+///
+/// Any function in the module (or in a nested inline module) that has a test attribute such as
+/// `#[test]` or `#[tokio::test]` is given a `use_cases` attribute for the listed sets. Paths to
+/// the sets are resolved from inside of the module so sets defined earlier in the same file can
+/// be referred to by name.
+///
+/// ```ignore
+/// define_cases!(backends, [
+///   (Backend::Memory) => "memory",
+///   (Backend::Disk) => "disk",
+/// ]);
+///
+/// #[cases_for_all(backends)]
+/// mod storage {
+///   use super::*;
+///
+///   #[test]
+///   fn round_trip(backend: Backend) {
+///     // ...
+///   }
+///
+///   #[test]
+///   fn delete(backend: Backend) {
+///     // ...
+///   }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cases_for_all(args: TokenStream, input: TokenStream) -> TokenStream {
+    case_sets::for_all_inner(args, input)
+}
+
+/// Generate a criterion benchmark for each case using the same syntax as `test_case`
+///
+/// The first argument of the function must be the `&mut criterion::Bencher` for the case and the
+/// remaining arguments are given values by each case. The cases are written out as a
+/// `criterion_group!` named `benches` inside of a module named after the function, with each case
+/// using its name as its benchmark ID. Your crate will need to depend on `criterion` directly.
+///
+/// ```ignore
+/// use criterion::{criterion_main, Bencher};
+///
+/// #[bench_case(100; "small")]
+/// #[bench_case(100_000; "large")]
+/// fn sort(b: &mut Bencher, n: usize) {
+///   let data: Vec<usize> = (0..n).rev().collect();
+///   b.iter(|| data.clone().sort());
+/// }
+///
+/// criterion_main!(sort::benches);
+/// ```
+#[proc_macro_attribute]
+pub fn bench_case(args: TokenStream, input: TokenStream) -> TokenStream {
+    bench_case::inner(args, input)
+}
+
+/// Run each test case as a loom model, optionally across several preemption bounds
+///
+/// The body of the test function is run inside of `loom::model::Builder::check` with the arguments
+/// for each case being provided by `test_case` attributes given beneath `loom_cases`. Specifying
+/// `preemption_bounds = [n, ...]` runs every case once for each bound, with the cases being named
+/// `<case>_preemption_bound_<n>`. Test functions that take no arguments do not need any
+/// `test_case` attributes.
+///
+/// As loom runs the model many times the arguments are captured by the model closure, so any
+/// arguments that are not `Copy` need to be cloned before being consumed. `loom` needs to be a
+/// dependency of the crate using `loom_cases`.
+///
+/// ```ignore
+/// #[loom_cases(preemption_bounds = [2, 3])]
+/// #[test_case(2; "two threads")]
+/// #[test_case(3; "three threads")]
+/// #[test]
+/// fn counter_is_consistent(n_threads: usize) {
+///   let counter = Arc::new(AtomicUsize::new(0));
+///   let handles: Vec<_> = (0..n_threads)
+///     .map(|_| {
+///       let counter = counter.clone();
+///       loom::thread::spawn(move || counter.fetch_add(1, Ordering::SeqCst))
+///     })
+///     .collect();
+///   handles.into_iter().for_each(|h| { h.join().unwrap(); });
+///   assert_eq!(counter.load(Ordering::SeqCst), n_threads);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn loom_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    loom_cases::inner(args, input)
+}
+
+/// Generate a trybuild compile failure test for each `.rs` file in a directory
+///
+/// Each file is checked using `trybuild::TestCases::compile_fail` once the body of the test
+/// function has run, asserting that the file fails to compile with the stderr output stored in
+/// the corresponding `.stderr` file. Test functions must not take any arguments and your crate
+/// will need to depend on `trybuild` directly.
+///
+/// ```ignore
+/// #[compile_fail_cases("tests/ui")]
+/// #[test]
+/// fn ui() {}
+/// ```
+#[proc_macro_attribute]
+pub fn compile_fail_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    compile_fail_cases::inner(args, input)
+}
+
+/// Mark a function as a fixture that can be used to provide arguments to test cases
+///
+/// Arguments of a test function marked with `#[fixture]` are not given a value by the test cases
+/// and are instead bound to the value returned by the fixture with the same name as the argument.
+/// Fixtures must take no arguments and are called once for each test case, allowing the values
+/// being tested and the environment they are tested in to be declared separately:
+///
+/// ```ignore
+/// #[fixture]
+/// fn db() -> Database {
+///   Database::in_memory().with_schema(SCHEMA)
+/// }
+///
+/// #[test_case("alice", true; "existing user")]
+/// #[test_case("bob", false; "unknown user")]
+/// #[test]
+/// fn lookup(name: &str, exists: bool, #[fixture] db: Database) {
+///   assert_eq!(db.find_user(name).is_some(), exists);
+/// }
+/// ```
+///
+/// Fixtures need to be in scope where the test function is defined and can be imported from
+/// other modules using their name as with any other function.
+///
+/// Fixtures that need to clean up after themselves can be marked with `#[fixture(guard)]` and
+/// return a `(value, guard)` pair. The value is passed to the test and the guard is held until the
+/// end of the test case before being dropped, after the value itself:
+///
+/// ```ignore
+/// struct RemoveOnDrop(PathBuf);
+///
+/// impl Drop for RemoveOnDrop {
+///   fn drop(&mut self) {
+///     let _ = std::fs::remove_dir_all(&self.0);
+///   }
+/// }
+///
+/// #[fixture(guard)]
+/// fn scratch_dir() -> (PathBuf, RemoveOnDrop) {
+///   let dir = make_unique_temp_dir();
+///   (dir.clone(), RemoveOnDrop(dir))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fixture(args: TokenStream, input: TokenStream) -> TokenStream {
+    fixture::inner(args, input)
+}
+
+/// Share expensive setup state between the parameterised tests in a module
+///
+/// Functions in the module marked with `#[shared]` are turned into fixtures (see `fixture`) whose
+/// value is created once, the first time a test case asks for it, and is then passed by reference
+/// to every test case that takes a `#[fixture]` argument with the same name. The value is stored
+/// in a static so it must be `Send + Sync` and is never dropped.
+///
+/// Functions marked with `#[setup]` are called at the start of every test function in the module
+/// and functions marked with `#[teardown]` are called at the end, even if the test panics. Hooks
+/// must not take any arguments or return a value.
+///
+/// ```ignore
+/// #[suite]
+/// mod engine_tests {
+///   use super::*;
+///
+///   #[shared]
+///   fn engine() -> Engine {
+///     Engine::start()
+///   }
+///
+///   #[teardown]
+///   fn clear_cache() {
+///     std::fs::remove_dir_all("target/engine-cache").ok();
+///   }
+///
+///   #[test_case("SELECT 1"; "select")]
+///   #[test_case("SELECT 1 + 1"; "arithmetic")]
+///   #[test]
+///   fn query_succeeds(query: &str, #[fixture] engine: &Engine) {
+///     assert!(engine.run(query).is_ok());
+///   }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn suite(args: TokenStream, input: TokenStream) -> TokenStream {
+    suite::inner(args, input)
+}
+
+/// Run an expression once per process before a test runs
+///
+/// When given beneath `test_case` attributes, the expression is run (using a `std::sync::Once`
+/// shared by every case of the test function) at the start of each case before any of its
+/// arguments are bound, so per-process initialisation such as installing a logger is guaranteed to
+/// have happened regardless of which case runs first. `once_setup` can also be used on plain
+/// tests. Each test function has its own `Once`, so an expression shared between several test
+/// functions may be run more than once and should be safe to repeat.
+///
+/// ```ignore
+/// #[test_case("a"; "letter")]
+/// #[test_case("1"; "digit")]
+/// #[once_setup(env_logger::init())]
+/// #[test]
+/// fn logs_input(s: &str) {
+///   log::info!("got {s}");
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn once_setup(args: TokenStream, input: TokenStream) -> TokenStream {
+    once_setup::inner(args, input)
+}
+
+/// Generate a test case for every combination of a set of values for each argument
+///
+/// A list of values must be given for each argument of the test function, with a case being
+/// generated for each element of the cartesian product of the lists. Cases are named by joining
+/// the values used for that case, so the example below generates the cases `_1_true`, `_1_false`,
+/// `_2_true` and so on.
+///
+/// ```ignore
+/// #[test_matrix([1, 2, 3], [true, false])]
+/// #[test]
+/// fn example(n: usize, flag: bool) {
+///   assert_eq!(parse(&format!("{n}:{flag}")), (n, flag));
+/// }
+/// ```
+///
+/// The values for each argument must result in distinct case names: a compile error is reported
+/// if two values (for example `"a b"` and `"a-b"`) would produce the same name.
+///
+/// Values that don't make for readable case names can be given an explicit label using
+/// `("label", value)`. Each list of values can also be named after the argument it is for, in
+/// which case a compile error is reported if the name doesn't match the argument in that position.
+/// The example below generates the cases `fast_true`, `fast_false`, `safe_true` and `safe_false`:
+///
+/// ```ignore
+/// #[test_matrix(config = [("fast", Config::fast()), ("safe", Config::safe())], [true, false])]
+/// #[test]
+/// fn example(config: Config, verbose: bool) {
+///   assert!(run(config, verbose).is_ok());
+/// }
+/// ```
+///
+/// To pass a tuple whose first element is a string literal as a value it must be given a label.
+#[proc_macro_attribute]
+pub fn test_matrix(args: TokenStream, input: TokenStream) -> TokenStream {
+    test_matrix::inner(args, input)
+}
+
+/// Generate a set of parameterised tests based on the contents of a directory
+///
+/// NOTE: The path given will be resolved relative to the root of your cargo workspace and the test
+/// function that you provide must accept to `&str` arguments: the path to the file loaded for the
+/// test case and the contents of that file. The files are read at compile time so
+/// adding/removing/modifying files in the given directory should trigger a recompile of your
+/// tests. Owned `String` arguments may be used in place of any of the `&str` arguments if your
+/// test needs to modify or take ownership of them.
+///
+/// Environment variables can be referenced in the path using `$NAME` or `${NAME}` and are expanded
+/// at compile time, allowing fixtures generated by a build script to be used via `$OUT_DIR`.
+///
+/// Fixtures shared between the members of a workspace can be referenced using
+/// `${WORKSPACE_ROOT}`, or by setting `workspace_root = true` to resolve all of the given paths
+/// relative to the root of the workspace. The workspace root is found by searching upwards from
+/// the directory of the crate being tested for a `Cargo.toml` with a `[workspace]` section, so
+/// paths continue to work if the crate is built on its own or moved within the workspace.
+///
+/// Cargo is not always able to tell that adding or removing a fixture file should cause your
+/// tests to be recompiled. Setting `check_drift = true` generates an additional test named
+/// `<test function>_fixture_drift` which fails if the files found in the given directories no
+/// longer match those that were present when the test cases were generated. All files in the
+/// directories are checked, including those that are not used as fixtures.
+///
+/// ```ignore
+/// #[dir_cases("resources/test_data")]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   // ..
+/// }
+/// ```
+///
+/// By default the path passed to the test is the directory as written in the attribute followed
+/// by the file name (`resources/test_data/case.txt`). This can be changed using `path_arg`:
+/// `"name"` passes the path of the file relative to its directory (`case.txt`), which is useful for
+/// naming snapshots, and `"absolute"` passes the canonical absolute path of the file, which can be
+/// re-opened regardless of the current directory:
+///
+/// ```ignore
+/// #[dir_cases("resources/test_data", path_arg = "name")]
+/// #[test]
+/// fn example(name: &str, contents: &str) {
+///   assert_snapshot!(name, render(contents));
+/// }
+/// ```
+///
+/// ### Additional arguments
+///
+/// Directories can be given a tag using `tagged(tag = "path/to/dir", ...)`, in which case the tag
+/// of the directory that each fixture was loaded from is passed as an additional `&str` argument
+/// before the path. If any directory is tagged then all of them must be:
+///
+/// ```ignore
+/// #[dir_cases(tagged(valid = "resources/valid", invalid = "resources/invalid"))]
+/// #[test]
+/// fn example(tag: &str, path: &str, contents: &str) {
+///   assert_eq!(parse(contents).is_ok(), tag == "valid");
+/// }
+/// ```
+///
+/// Setting `stem = true` passes the file stem of each fixture (the file name without its
+/// extension) as an additional `&str` argument between the path and the contents, which is useful
+/// when the expected behaviour of a test depends on the name of the fixture:
+///
+/// ```ignore
+/// #[dir_cases("resources/test_data", stem = true)]
+/// #[test]
+/// fn example(path: &str, stem: &str, contents: &str) {
+///   assert_eq!(parse(contents).is_ok(), stem.starts_with("valid_"));
+/// }
+/// ```
+///
+/// The test function may accept additional arguments after `(path, contents)` if their values are
+/// provided using the `args` option. This allows the same fixtures to drive a test with some extra
+/// static configuration:
+///
+/// ```ignore
+/// #[dir_cases("resources/test_data", args = (Mode::Strict, 3))]
+/// #[test]
+/// fn example(path: &str, contents: &str, mode: Mode, retries: usize) {
+///   // ..
+/// }
+/// ```
+///
+/// Setting `common = "_common.txt"` treats any file with the given name as shared data rather than
+/// as a test case: its contents are passed as an additional `&str` argument directly after the
+/// contents of each fixture. When searching recursively the closest common file to each fixture
+/// is used, allowing subdirectories to override the one found in the top level directory:
+///
+/// ```ignore
+/// #[dir_cases("resources/queries", common = "_schema.sql")]
+/// #[test]
+/// fn example(path: &str, contents: &str, schema: &str) {
+///   // ..
+/// }
+/// ```
+///
+/// Each fixture can also be run once for each of a set of labelled values using the `each` option.
+/// The value is passed as the final argument to the test function and the label is appended to the
+/// case name, so the following generates `example::resources_test_data_a_txt__strict`,
+/// `example::resources_test_data_a_txt__lenient` and so on:
+///
+/// ```ignore
+/// #[dir_cases("resources/test_data", each = [strict = Mode::Strict, lenient = Mode::Lenient])]
+/// #[test]
+/// fn example(path: &str, contents: &str, mode: Mode) {
+///   // ..
+/// }
+/// ```
+///
+/// A single fixture file containing many cases can be split into multiple test cases using the
+/// `split` option. Each non-empty segment of the file between the given delimiter becomes its own
+/// case named using the position of the segment in the file, so the following generates
+/// `example::resources_suites_conformance_txt__segment_1`,
+/// `example::resources_suites_conformance_txt__segment_2` and so on for each file in the directory:
+///
+/// ```ignore
+/// #[dir_cases("resources/suites", split = "\n---\n")]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   // ..
+/// }
+/// ```
+///
+/// ### Selecting fixtures
+///
+/// The `matching` option restricts the fixtures used to those whose file name matches the given
+/// regular expression, e.g. `matching = r"^vec_\d+\.bin$"`. Patterns use the syntax of the
+/// [regex](https://docs.rs/regex) crate and are evaluated at compile time. As with
+/// `Regex::is_match`, a pattern only needs to match part of the file name unless it is anchored
+/// with `^` and `$`.
+///
+/// Fixtures whose file name matches the regular expression given by the `ignore_matching` option
+/// still generate a test case but it is marked as `#[ignore]`, so skipped fixtures continue to
+/// show up when running `cargo test -- --ignored` rather than being forgotten about. For example,
+/// `ignore_matching = r"^_|\.skip$"` ignores any fixture prefixed with `_` or ending in `.skip`.
+///
+/// The `max_size` option (e.g. `max_size = "1MB"`) guards against accidentally embedding very
+/// large files in your test binary: by default any fixture over the given size is a compile error.
+/// Setting `oversized = "ignore"` instead generates an `#[ignore]`d test for each oversized
+/// fixture which reads the file at runtime, allowing it to be run on demand with `--ignored`.
+/// `ignore_over = "10MB"` can be used as a shorthand for `max_size = "10MB", oversized = "ignore"`.
+///
+/// For large collections of fixtures, `sample = N` runs only the first `N` fixtures (in order of
+/// their path) as normal tests and marks the rest as `#[ignore]`. A full run is then available
+/// using `cargo test -- --include-ignored`. Alternatively, `sample_unless` can be given a cfg
+/// predicate and all fixtures are run as normal tests whenever it holds:
+///
+/// ```ignore
+/// #[dir_cases("resources/corpus", sample = 50, sample_unless = cfg(feature = "full-corpus"))]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   // ..
+/// }
+/// ```
+///
+/// Rather than using everything found in a directory, the fixtures to use can be listed
+/// explicitly in a manifest file using `manifest = "resources/cases.list"` in place of any
+/// directories. Each line of the manifest gives the path of a fixture relative to the directory
+/// containing the manifest, optionally followed by ` = name` to set the name of the case. Blank
+/// lines and lines starting with `#` are skipped:
+///
+/// ```text
+/// # resources/cases.list
+/// parsing/valid_utf8.txt
+/// parsing/utf8_bom.txt = byte order mark
+/// ```
+///
+/// ### Case names
+///
+/// By default each case is named using the full path of its fixture file, so
+/// `resources/test_data/utf8_bom.txt` becomes `resources_test_data_utf8_bom_txt`. Setting
+/// `strip_extension = true` drops the file extension and `strip_prefix = true` drops the
+/// directory path, giving `utf8_bom`. `strip_prefix` may also be given a string which is removed
+/// from the start of each file name in addition to the directory path.
+///
+/// Setting `recursive = true` will also search subdirectories for fixture files. The path of the
+/// subdirectory is always included in the case name so files with the same name in different
+/// subdirectories will not collide. File names that only differ in characters that are not valid
+/// in an identifier (such as `report-2024.txt` and `report_2024.txt`) are given a suffix derived
+/// from a hash of the original name so that each case name is unique and stable. If two fixtures
+/// still end up with the same name (for example when stripping the prefix from multiple
+/// directories) a compile error naming both files is emitted.
+///
+/// When searching recursively, setting `nested = true` will place the cases for each
+/// subdirectory in a nested module of the same name rather than including the path of the
+/// subdirectory in the case name. For example `parsing/numbers/case_42.txt` would become the test
+/// `example::parsing::numbers::case_42_txt`, allowing you to filter tests in the same way that the
+/// fixtures are organised on disk.
+///
+/// When a directory contains fixtures in multiple formats, setting `group_by_extension = true`
+/// places the cases for each file extension in a module of the same name (e.g. `example::json::`
+/// and `example::txt::`) so that the cases for each format can be run on their own.
+///
+/// Multiple `dir_cases` attributes can be applied to the same test function in order to use
+/// fixtures with different options. Setting `prefix` on each attribute adds the given prefix to
+/// the names of its cases so that they do not collide and it is clear which set of fixtures a
+/// failing case came from:
+///
+/// ```ignore
+/// #[dir_cases("resources/valid", strip_prefix = true, prefix = "valid")]
+/// #[dir_cases("resources/regressions", strip_prefix = true, prefix = "regression")]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   // ..
+/// }
+/// ```
+///
+/// Symlinks are followed by default (with dangling symlinks reported as a compile error naming
+/// the offending entry) but can be skipped entirely by setting `follow_symlinks = false`.
+///
+/// ### Typed fixtures
+///
+/// If the contents argument of the test function is not a `&str` then each fixture is parsed
+/// into the given type using its `FromStr` implementation, with the generated test panicking with
+/// a message naming the fixture file if parsing fails:
+///
+/// ```ignore
+/// #[dir_cases("resources/programs")]
+/// #[test]
+/// fn example(path: &str, program: Program) {
+///   // ..
+/// }
+/// ```
+///
+/// Setting `format = "json"` will deserialize each fixture file into the type of the second
+/// argument of the test function using `serde_json` (which must be a dependency of your crate).
+/// The generated test panics with a message naming the fixture file if deserialization fails.
+/// With the `yaml` or `toml` features enabled, `format = "yaml"` and `format = "toml"` do the same
+/// using `serde_yaml` and `toml` respectively, which likewise must be dependencies of your crate.
+///
+/// The `extensions` option restricts the fixtures used to those with one of the given file
+/// extensions, e.g. `extensions = ["yaml", "yml"]`.
+///
+/// ```ignore
+/// #[dir_cases("resources/vectors", format = "json")]
+/// #[test]
+/// fn example(path: &str, case: TestVector) {
+///   // ..
+/// }
+/// ```
+///
+/// Alternatively, `parse_with` can be used to name a function `fn(&str) -> T` that is called at
+/// the top of each generated test to produce the contents argument. If the parser panics, the
+/// test fails with a message naming the fixture file along with the original panic message.
+///
+/// ```ignore
+/// #[dir_cases("resources/programs", parse_with = parse_program)]
+/// #[test]
+/// fn example(path: &str, program: Program) {
+///   // ..
+/// }
+/// ```
+///
+/// ### Front matter
+///
+/// Setting `front_matter = [key, ..]` allows per-case configuration to be kept alongside each
+/// fixture in a block of `key = value` pairs at the top of the file between `+++` lines. The
+/// values of the listed keys are passed as additional arguments after the contents (which no
+/// longer include the front matter) and the special `ignore` and `should_panic` keys can be used
+/// to set the corresponding attributes on the generated test. Use `front_matter = true` if you
+/// only need the attributes. The block is parsed as TOML, with strings, integers, floats and
+/// booleans supported as values:
+///
+/// ```text
+/// +++
+/// expected = "Hello, world!"
+/// should_panic = "unterminated string"
+/// +++
+/// print("Hello, world!)
+/// ```
+///
+/// ```ignore
+/// #[dir_cases("resources/programs", front_matter = [expected])]
+/// #[test]
+/// fn example(path: &str, program: &str, expected: &str) {
+///   assert_eq!(run(program), expected);
+/// }
+/// ```
+///
+/// ### Compressed fixtures
+///
+/// With the `gzip` or `zstd` features enabled, fixtures ending in `.gz` or `.zst` are embedded in
+/// their compressed form and decompressed at the start of each test (using `flate2` or `zstd`
+/// respectively, which must be dependencies of your crate) before being passed to the test
+/// function or on to `format` / `parse_with`. This allows large corpora to be stored compressed
+/// in your repository. Decompressed contents must be valid UTF-8 unless the contents argument of
+/// the test function is a `&[u8]`, in which case the raw bytes are passed through as they are.
+///
+/// ### Sharing fixture contents
+///
+/// By default each test function embeds its own copy of every fixture file. If several test
+/// functions make use of the same directory you can declare a shared module of fixture contents
+/// once using [dir_fixtures!] and point `dir_cases` at it using the `fixtures` option:
+///
+/// ```ignore
+/// simple_test_case::dir_fixtures!(mod fixtures = "resources/test_data");
+///
+/// #[dir_cases("resources/test_data", fixtures = crate::fixtures)]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   // ..
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn dir_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    dir_cases::inner(args, input)
+}
+
+/// Generate a module of `&str` constants holding the contents of each file in a set of directories
+///
+/// Each constant is named using the upper case slug of the file path (`tests/data/one.txt` becomes
+/// `TESTS_DATA_ONE_TXT`) so that `dir_cases` can refer to it using the `fixtures` option. Paths
+/// are resolved in the same way as for [dir_cases].
+///
+/// ```ignore
+/// simple_test_case::dir_fixtures!(
+///   pub(crate) mod fixtures = "resources/test_data", "resources/other"
+/// );
+/// ```
+#[proc_macro]
+pub fn dir_fixtures(input: TokenStream) -> TokenStream {
+    dir_cases::fixtures_inner(input)
+}
+
+/// Generate a set of parameterised tests from pairs of input and expected output files
+///
+/// The given directory is searched for files named `X.in` along with a matching `X.out`, with a
+/// test case being generated for each pair named after the directory and the shared file stem.
+/// Any input or output file without a counterpart is a compile error. Files with other extensions
+/// are skipped. The extensions used can be changed with the `input` and `output` options.
+///
+/// The test function must accept three `&str` arguments: the path to the input file, the
+/// contents of the input file and the contents of the expected output file.
+///
+/// ```ignore
+/// #[dir_pair_cases("resources/formatting", input = "src", output = "expected")]
+/// #[test]
+/// fn example(path: &str, input: &str, expected: &str) {
+///   assert_eq!(format_source(input), expected, "{}", path);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn dir_pair_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    dir_pair_cases::inner(args, input)
+}
+
+/// Generate a set of parameterised tests from the rows of a CSV file
+///
+/// The file is parsed at compile time and must begin with a header row. If every argument of the
+/// test function has a column with a matching header name then columns are mapped to arguments
+/// by name, while if none of them do they are mapped by position. Headers that only match some of
+/// the arguments are a compile error listing the unmatched arguments and columns. Values for
+/// `&str` and `String` arguments are passed through as strings while all other values are parsed
+/// as Rust expressions.
+///
+/// Cases are named using the column given by the `name` option, falling back to `row_1`,
+/// `row_2`, etc. if no name column is specified.
+///
+/// ```ignore
+/// // tests/vectors.csv
+/// // description,input,expected
+/// // small,1,2
+/// // large,100,200
+///
+/// #[csv_cases("tests/vectors.csv", name = "description")]
+/// #[test]
+/// fn example(input: usize, expected: usize) {
+///   assert_eq!(input * 2, expected);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn csv_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    csv_cases::inner(args, input)
+}
+
+/// Generate a set of parameterised tests from the elements of a JSON array
+///
+/// The file is parsed at compile time and must contain a top level array of objects, each of
+/// which generates a test case. Arguments of the test function are given the value of the field
+/// with the same name, with any additional fields being ignored. Cases are named using the `name`
+/// field of each object (a different field can be used by setting the `name` option), falling
+/// back to `case_1`, `case_2`, etc. if it is missing or null.
+///
+/// Values are converted based on the type of the argument: strings can be passed as `&str` or
+/// `String`, arrays as slices, arrays or `Vec`s and `null` is only accepted for `Option`
+/// arguments. Nested objects are not supported.
+///
+/// ```ignore
+/// // tests/vectors.json
+/// // [
+/// //   { "name": "empty", "input": "", "digest": [227, 176, 196, 66] },
+/// //   { "name": "abc", "input": "abc", "digest": [186, 120, 22, 191] }
+/// // ]
+///
+/// #[json_cases("tests/vectors.json")]
+/// #[test]
+/// fn example(input: &str, digest: &[u8]) {
+///   assert_eq!(&sha256(input.as_bytes())[..4], digest);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn json_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    json_cases::inner(args, input)
+}
+
+/// Generate test cases from a registry of regressions stored in a TOML file
+///
+/// Each `[[regression]]` table in the file gives a `name` for the case, a link to the `issue` it
+/// was found in and the `args` to pass to the test function in order, converted into the types of
+/// the arguments in the same way as for `json_cases`. Cases are named after the number found at
+/// the end of the issue link along with their name (`issue_123_empty_input`) and the link is
+/// printed if the test panics.
+///
+/// Only the subset of TOML needed for tables of test data is supported: dotted keys, dates and
+/// multi-line strings are rejected.
+///
+/// ```toml
+/// [[regression]]
+/// name = "empty input"
+/// issue = "https://github.com/me/my-crate/issues/123"
+/// args = ["", 0]
+/// ```
+///
+/// ```ignore
+/// #[regression_cases("tests/regressions.toml")]
+/// #[test]
+/// fn parse_does_not_panic(input: &str, expected_len: usize) {
+///   assert_eq!(parse(input).len(), expected_len);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn regression_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    regression_cases::inner(args, input)
+}
+
+/// Generate a test case for each file in a tar or zip archive
+///
+/// The archive is read at compile time and may be a `.tar`, `.tar.gz` (or `.tgz`) or `.zip` file,
+/// allowing large corpora of fixtures to be checked in as a single compressed file. Each regular
+/// file in the archive becomes a test case named after its path within the archive. Files that are
+/// stored uncompressed are read directly from the archive (which is embedded using
+/// `include_bytes!`) while compressed files are decompressed at compile time, which requires the
+/// `gzip` feature to be enabled. The test function must accept either `(contents)` or
+/// `(path: &str, contents)` as arguments, where `contents` is a `&str` or `&[u8]`.
+///
+/// ```ignore
+/// #[archive_cases("tests/fixtures.tar.gz")]
+/// #[test]
+/// fn fixtures_parse(path: &str, contents: &[u8]) {
+///   assert!(Document::parse(contents).is_ok(), "{} failed to parse", path);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn archive_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    archive_cases::inner(args, input)
+}
+
+/// Generate test cases from a case list written by a build script
+///
+/// The file contains a comma separated list of cases, each wrapped in parentheses and using the
+/// same `args; "name"; options` syntax as `test_case`, allowing cases to be generated from schemas
+/// or protocol specifications in `build.rs`. By default the file is read from `OUT_DIR`, with
+/// `generated_cases(dir, "cases.rs")` reading it from another directory (which may be given as
+/// `env!("NAME")`). The test crate is rebuilt whenever the file changes.
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///   let out_dir = std::env::var("OUT_DIR").unwrap();
+///   std::fs::write(
+///     format!("{}/cases.rs", out_dir),
+///     "(1, 2; \"small\"),\n(100, 200; \"large\"),\n",
+///   ).unwrap();
+/// }
+///
+/// // tests/double.rs
+/// #[generated_cases("cases.rs")]
+/// #[test]
+/// fn double(n: usize, expected: usize) {
+///   assert_eq!(n * 2, expected);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn generated_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    generated_cases::inner(args, input)
+}
+
+/// Generate a set of parameterised tests from the lines of a single file
+///
+/// Each non-empty line of the file becomes its own test case named after its (1-indexed) line
+/// number. Lines beginning with `#` are treated as comments and skipped: a different comment
+/// prefix can be specified using the `comment` option. The test function must accept either
+/// `(line: &str)` or `(line_number: usize, line: &str)` as arguments.
+///
+/// ```ignore
+/// #[line_cases("tests/malformed_urls.txt", comment = "//")]
+/// #[test]
+/// fn example(line_number: usize, url: &str) {
+///   assert!(parse_url(url).is_err(), "line {} parsed successfully", line_number);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn line_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    line_cases::inner(args, input)
+}
+
+/// Generate a test case for each block of a NIST style test vector file
+///
+/// Test vector files (such as the `.rsp` files published alongside many cryptographic standards)
+/// are made up of blocks of `Key = value` lines separated by blank lines. Blocks may be grouped
+/// into sections using `[name]` or `[Key = value]` headers, with the values given in a section
+/// header being available to every block in that section. Lines beginning with `#` are comments.
+///
+/// Each block becomes a test case with values passed to the argument of the same name (ignoring
+/// case). Values for `&[u8]`, `Vec<u8>` and `[u8; N]` arguments are decoded from hex, `&str` and
+/// `String` arguments are given the value as written and all other types are parsed using their
+/// `FromStr` implementation. Cases are named after their section and their position in the file.
+///
+/// ```ignore
+/// // tests/SHA256ShortMsg.rsp
+/// //
+/// // [L = 32]
+/// //
+/// // Len = 8
+/// // Msg = d3
+/// // MD = 28969cdfa74a12c82f3bad960b0b000aca2ac329deea5c2328ebc6f2ba9802c1
+///
+/// #[vector_cases("tests/SHA256ShortMsg.rsp")]
+/// #[test]
+/// fn sha256(len: usize, msg: &[u8], md: [u8; 32]) {
+///   assert_eq!(sha256(&msg[..len / 8]), md);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn vector_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    vector_cases::inner(args, input)
+}
+
+/// Generate a test case for each fenced code block in a markdown file
+///
+/// Code blocks fenced with either backticks or tildes are extracted, with `lang = "json"` selecting
+/// only the blocks whose info string starts with the given language. Each block becomes its own
+/// test case named after the (1-indexed) line number of its opening fence, so that examples in
+/// documentation are checked whenever the tests are run. The test function must accept either
+/// `(block: &str)` or `(line_number: usize, block: &str)` as arguments.
+///
+/// ```ignore
+/// #[markdown_cases("docs/examples.md", lang = "json")]
+/// #[test]
+/// fn documented_configs_are_valid(line_number: usize, block: &str) {
+///   assert!(Config::from_json(block).is_ok(), "example on line {} is invalid", line_number);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn markdown_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    markdown_cases::inner(args, input)
+}
+
+/// Generate a test case for each environment variable with a given prefix
+///
+/// The variables are read when the test function is compiled, allowing CI to inject additional
+/// cases without editing the source of the tests. Each case is named using the remainder of the
+/// variable name after the prefix and the test function must accept a single argument: `&str`
+/// arguments are given the value of the variable directly while any other type is parsed from the
+/// value using its `FromStr` implementation. If no variables match then the test function is
+/// still type checked but no tests are generated.
+///
+/// Changing the value of a variable that was already set will trigger recompilation of your tests
+/// but setting a new variable will not.
+///
+/// ```ignore
+/// // MATRIX_POSTGRES=postgres://localhost MATRIX_SQLITE=sqlite::memory: cargo test
+/// #[env_cases("MATRIX_")]
+/// #[test]
+/// fn connects(url: &str) {
+///   assert!(connect(url).is_ok());
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn env_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    env_cases::inner(args, input)
+}
+
+/// Generate a test case for each row of the `Examples` tables in a directory of Gherkin feature
+/// files
+///
+/// Only the example tables of each `Scenario` (or `Scenario Outline`) are used: the steps of the
+/// scenario are ignored, leaving the test function to perform them. The cells of each row are
+/// passed to the argument with the same name as their column, with `&str` and `String` arguments
+/// being given the cell as written and other types being parsed from the cell using their
+/// `FromStr` implementation. Cases are named using the name of the feature file, the scenario and
+/// the number of the example within that scenario.
+///
+/// ```ignore
+/// // tests/features/addition.feature
+/// //
+/// // Scenario Outline: adding two numbers
+/// //   Given I have entered <a> and <b>
+/// //   Then the result should be <sum>
+/// //
+/// //   Examples:
+/// //     | a | b  | sum |
+/// //     | 1 | 2  | 3   |
+/// //     | 0 | -1 | -1  |
+///
+/// #[feature_cases("tests/features")]
+/// #[test]
+/// fn addition(a: i64, b: i64, sum: i64) {
+///   assert_eq!(add(a, b), sum);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn feature_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    feature_cases::inner(args, input)
+}
+
+/// Generate a test case for each entry in a fuzzing corpus so that it can be replayed using
+/// `cargo test`
+///
+/// Each file in the corpus directory is passed to the test function as a byte slice. Cases are
+/// named using a prefix of the hash of the entry: cargo-fuzz names corpus entries using the hash
+/// of their contents so the file name is used directly where possible. The test function must
+/// accept `(data: &[u8])` as its only argument.
+///
+/// ```ignore
+/// #[fuzz_corpus_cases("fuzz/corpus/parse_target")]
+/// #[test]
+/// fn parse_does_not_panic(data: &[u8]) {
+///   let _ = parse(data);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fuzz_corpus_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    fuzz_corpus_cases::inner(args, input)
+}
+
+/// Run a function against every file in a directory, discovering the files when the tests are run
+///
+/// Unlike `dir_cases`, which lists the files in a directory when your tests are compiled, the
+/// files are only listed once the test binary runs so that adding or removing a fixture never
+/// requires recompilation. This is intended for very large or frequently changing corpora.
+///
+/// A `main` function is generated that runs each file as a separate test using `libtest-mimic`,
+/// so the test target needs `harness = false` set in your Cargo.toml and your crate will need to
+/// depend on `libtest-mimic` directly. The function must accept either `(path: &str, contents:
+/// &str)` or `(path: &str, contents: &[u8])` as arguments and files can be filtered using the
+/// `extension` option. Subdirectories are included when `recursive = true` is given.
+///
+/// Requires the `harness` feature.
+///
+/// ```ignore
+/// // Cargo.toml
+/// // [[test]]
+/// // name = "corpus"
+/// // harness = false
+///
+/// #[dir_harness("tests/corpus", extension = "json", recursive = true)]
+/// fn parses(path: &str, contents: &str) {
+///   assert!(parse(contents).is_ok(), "{} failed to parse", path);
+/// }
+/// ```
+#[cfg(feature = "harness")]
+#[proc_macro_attribute]
+pub fn dir_harness(args: TokenStream, input: TokenStream) -> TokenStream {
+    harness::inner(args, input)
+}
+
+/// Replay the failures recorded in a proptest regression file as individual named test cases
+///
+/// Each `cc` entry in the file becomes a test case named after the start of its seed, using the
+/// values recorded in the "shrinks to" comment for the entry as the arguments for the case. The
+/// names of the recorded values must match the names of the test function arguments.
+///
+/// ```ignore
+/// #[proptest_regressions("proptest-regressions/parse.txt")]
+/// #[test]
+/// fn parse_round_trips(s: String) {
+///   assert_eq!(parse(&s).unwrap().to_string(), s);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn proptest_regressions(args: TokenStream, input: TokenStream) -> TokenStream {
+    proptest_regressions::inner(args, input)
+}
+
+/// Compare some output against the contents of a golden file, updating the file instead if the
+/// `UPDATE_GOLDEN` environment variable is set to `1`
+///
+/// The output may be anything implementing `AsRef<str>` and the path is resolved relative to the
+/// root of your crate (`CARGO_MANIFEST_DIR`). If the output does not match, or the golden file does
+/// not exist, the test panics with a message showing a line diff of the expected and actual
+/// output. Running your tests with `UPDATE_GOLDEN=1 cargo test` writes the current output to each
+/// golden file (creating any missing directories) so that the changes can be reviewed using your
+/// version control system.
+///
+/// ```ignore
+/// #[dir_cases("resources/inputs")]
+/// #[test]
+/// fn example(path: &str, contents: &str) {
+///   let output = render(contents);
+///   assert_golden!(output, format!("{}.expected", path));
+/// }
+/// ```
+#[proc_macro]
+pub fn assert_golden(input: TokenStream) -> TokenStream {
+    golden::inner(input)
+}
+
+/// Compare the output returned by each test case against its own golden file
+///
+/// The test function returns the output to be checked (anything implementing `Display`) which is
+/// compared against `{dir}/{case_name}.expected` in the same way as `assert_golden!`, including
+/// updating the golden files when `UPDATE_GOLDEN=1` is set. `golden_cases` must be placed after
+/// any `test_case` (or other case generating) attributes so that it is applied to each case. The
+/// extension of the golden files can be set using the `extension` option.
+///
+/// ```ignore
+/// #[test_case("1 + 2"; "addition")]
+/// #[test_case("3 * (4 - 1)"; "nested")]
+/// #[golden_cases("tests/golden/parse", extension = "ast")]
+/// #[test]
+/// fn parse(input: &str) -> String {
+///   format!("{:#?}", parse_expr(input).unwrap())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn golden_cases(args: TokenStream, input: TokenStream) -> TokenStream {
+    golden::cases_inner(args, input)
 }
@@ -0,0 +1,138 @@
+use crate::util::read_fixture_file;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Error, FnArg, Ident, ItemFn, LitStr, Result, Token, Type,
+};
+
+// line_cases("path/to/file.txt") with an optional `comment = "//"` prefix for lines to skip
+struct LineCases {
+    span: Span,
+    path: String,
+    comment: String,
+}
+
+impl Parse for LineCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let path: LitStr = input.parse()?;
+        let mut comment = "#".to_string();
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "comment" => comment = input.parse::<LitStr>()?.value(),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown line_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            span,
+            path: path.value(),
+            comment,
+        })
+    }
+}
+
+// Line numbers are 1-indexed to match what you see in your editor
+fn get_lines(raw: &str, comment: &str) -> Vec<(usize, String)> {
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && (comment.is_empty() || !trimmed.starts_with(comment))
+        })
+        .map(|(i, line)| (i + 1, line.to_string()))
+        .collect()
+}
+
+// Test functions take either (line: &str) or (line_number: usize, line: &str)
+pub(crate) fn with_line_number(_fn: &ItemFn) -> Option<bool> {
+    let str_ty: Type = parse_quote!(&str);
+    let usize_ty: Type = parse_quote!(usize);
+    let is = |fnarg: &FnArg, ty: &Type| matches!(fnarg, FnArg::Typed(pt) if *pt.ty == *ty);
+    let inputs: Vec<_> = _fn.sig.inputs.iter().collect();
+
+    match inputs.as_slice() {
+        [line] if is(line, &str_ty) => Some(false),
+        [n, line] if is(n, &usize_ty) && is(line, &str_ty) => Some(true),
+        _ => None,
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let LineCases {
+        span,
+        path,
+        comment,
+    } = parse_macro_input!(args as LineCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let line_number = match with_line_number(&original) {
+        Some(line_number) => line_number,
+        None => {
+            return TokenStream::from(
+                Error::new(
+                    span,
+                    "line_cases test functions must accept (line: &str) or (line_number: usize, line: &str) as arguments",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let (raw, abs_path) = match read_fixture_file(span, &path) {
+        Ok(res) => res,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs: Vec<_> = get_lines(&raw, &comment)
+        .into_iter()
+        .map(|(n, line)| {
+            let case = format!("line {}", n);
+            if line_number {
+                quote! { #[simple_test_case::test_case(#n, #line; #case)] }
+            } else {
+                quote! { #[simple_test_case::test_case(#line; #case)] }
+            }
+        })
+        .collect();
+
+    TokenStream::from(quote! {
+        // Ensure that we are recompiled if the file changes
+        const _: &str = include_str!(#abs_path);
+
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_and_comment_lines_are_skipped() {
+        let lines = get_lines(
+            "# header\nfirst\n\n   \n  # indented comment\nsecond\n",
+            "#",
+        );
+
+        assert_eq!(
+            lines,
+            vec![(2, "first".to_string()), (6, "second".to_string())]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Attribute, Error, FnArg, Ident, ItemFn, Result, Token, Type,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Date,
+}
+
+// The platform whose locale data produces an entry, if it is specific to one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Platform {
+    Any,
+    MacOs,
+    Windows,
+}
+
+use Kind::*;
+use Platform::*;
+
+// Numbers, dates and times as they are written (or formatted by locale aware APIs) in different
+// locales. Entries that are only produced by the locale data of a particular platform are only
+// run on that platform.
+const CORPUS: &[(&str, &str, Kind, Platform)] = &[
+    ("en_us_grouped", "1,234,567.89", Number, Any),
+    ("de_de_grouped", "1.234.567,89", Number, Any),
+    ("fr_fr_grouped", "1\u{202f}234\u{202f}567,89", Number, Any),
+    ("fr_fr_nbsp_grouped", "1\u{a0}234\u{a0}567,89", Number, Any),
+    ("space_grouped", "1 234 567.89", Number, Any),
+    ("de_ch_apostrophe", "1'234'567.89", Number, Any),
+    (
+        "de_ch_right_quote",
+        "1\u{2019}234\u{2019}567.89",
+        Number,
+        Any,
+    ),
+    ("en_in_lakh", "12,34,567.89", Number, Any),
+    ("arabic_indic_digits", "١٬٢٣٤٬٥٦٧٫٨٩", Number, Any),
+    ("persian_digits", "۱٬۲۳۴٬۵۶۷٫۸۹", Number, Any),
+    ("devanagari_digits", "१,२३४,५६७.८९", Number, Any),
+    ("fullwidth_digits", "１２３４５", Number, Any),
+    ("decimal_comma", "0,5", Number, Any),
+    ("decimal_point", "0.5", Number, Any),
+    ("leading_decimal", ".5", Number, Any),
+    ("trailing_decimal", "5.", Number, Any),
+    ("ambiguous_grouping", "1,234", Number, Any),
+    ("ambiguous_decimal", "1.234", Number, Any),
+    ("unicode_minus", "\u{2212}42", Number, Any),
+    ("accounting_negative", "(1,234.56)", Number, Any),
+    ("trailing_minus", "1234-", Number, Any),
+    ("explicit_plus", "+42", Number, Any),
+    ("exponent", "1.5e-3", Number, Any),
+    ("currency_prefix", "$1,234.56", Number, Any),
+    ("currency_suffix", "1.234,56\u{a0}€", Number, Any),
+    ("percent_nbsp", "45\u{a0}%", Number, Any),
+    ("iso_8601_date", "2026-10-16", Date, Any),
+    ("iso_8601_datetime", "2026-10-16T15:45:00Z", Date, Any),
+    ("iso_8601_offset", "2026-10-16T15:45:00+05:30", Date, Any),
+    ("rfc_2822", "Fri, 16 Oct 2026 15:45:00 +0000", Date, Any),
+    ("en_us_date", "10/16/2026", Date, Any),
+    ("en_gb_date", "16/10/2026", Date, Any),
+    ("ambiguous_date", "03/04/2026", Date, Any),
+    ("de_de_date", "16.10.2026", Date, Any),
+    ("ja_jp_date", "2026年10月16日", Date, Any),
+    ("ko_kr_date", "2026. 10. 16.", Date, Any),
+    ("zh_cn_date", "2026/10/16", Date, Any),
+    ("two_digit_year", "16/10/26", Date, Any),
+    ("en_month_name", "16 October 2026", Date, Any),
+    ("fr_month_name", "16 octobre 2026", Date, Any),
+    ("de_month_name", "16. Oktober 2026", Date, Any),
+    ("ru_genitive_month", "16 октября 2026 г.", Date, Any),
+    ("th_buddhist_year", "16/10/2569", Date, Any),
+    ("leap_day", "2024-02-29", Date, Any),
+    ("invalid_leap_day", "2026-02-29", Date, Any),
+    ("time_24h", "15:45", Date, Any),
+    ("time_12h", "3:45 PM", Date, Any),
+    ("time_12h_dotted", "3:45 p.m.", Date, Any),
+    ("time_12h_narrow_nbsp", "3:45\u{202f}PM", Date, MacOs),
+    (
+        "date_with_ltr_marks",
+        "\u{200e}16\u{200e}/\u{200e}10\u{200e}/\u{200e}2026",
+        Date,
+        Windows,
+    ),
+];
+
+// locale_cases with optional `kind = number|date` and `skip = [name, ...]` options
+struct LocaleCases {
+    kind: Option<Kind>,
+    skip: Vec<Ident>,
+}
+
+impl Parse for LocaleCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut kind = None;
+        let mut skip = Vec::new();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "kind" => {
+                    let k: Ident = input.parse()?;
+                    kind = match k.to_string().as_str() {
+                        "number" => Some(Number),
+                        "date" => Some(Date),
+                        other => {
+                            return Err(Error::new(
+                                k.span(),
+                                format!(
+                                    "unknown locale case kind: {} (expected number or date)",
+                                    other
+                                ),
+                            ))
+                        }
+                    };
+                }
+                "skip" => {
+                    let content;
+                    bracketed!(content in input);
+                    skip.extend(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown locale_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { kind, skip })
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let LocaleCases { kind, skip } = parse_macro_input!(args as LocaleCases);
+    let original = parse_macro_input!(input as ItemFn);
+    let str_ty: Type = parse_quote!(&str);
+
+    if !matches!(original.sig.inputs.iter().collect::<Vec<_>>().as_slice(), [FnArg::Typed(pt)] if *pt.ty == str_ty)
+    {
+        return TokenStream::from(
+            Error::new_spanned(
+                &original.sig,
+                "locale_cases test functions must accept a single &str argument",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    if let Some(s) = skip.iter().find(|s| !CORPUS.iter().any(|(n, ..)| *s == n)) {
+        let names: Vec<_> = CORPUS.iter().map(|(n, ..)| *n).collect();
+        return TokenStream::from(
+            Error::new(
+                s.span(),
+                format!(
+                    "unknown locale case: {} (expected one of {})",
+                    s,
+                    names.join(", ")
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = CORPUS
+        .iter()
+        .filter(|(name, _, k, _)| {
+            (kind.is_none() || kind == Some(*k)) && !skip.iter().any(|s| s == name)
+        })
+        .map(|(name, s, _, platform)| {
+            let cfg: Option<Attribute> = match platform {
+                Any => None,
+                MacOs => Some(parse_quote!(#[cfg(target_os = "macos")])),
+                Windows => Some(parse_quote!(#[cfg(windows)])),
+            };
+            match cfg {
+                Some(cfg) => quote! { #[simple_test_case::test_case(#s; #name; #cfg)] },
+                None => quote! { #[simple_test_case::test_case(#s; #name)] },
+            }
+        });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_names_are_unique() {
+        let mut names: Vec<_> = CORPUS.iter().map(|(n, ..)| *n).collect();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(names.len(), CORPUS.len());
+    }
+
+    #[test]
+    fn kinds_are_parsed() {
+        let parsed: LocaleCases = parse_quote!(kind = date, skip = [leap_day]);
+
+        assert_eq!(parsed.kind, Some(Date));
+        assert_eq!(parsed.skip.len(), 1);
+        assert!(syn::parse2::<LocaleCases>(quote!(kind = currency)).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    boundary_cases::{extract_explicit_cases, ExplicitCase},
+    util::wrong_arg_count,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Error, Ident, ItemFn, LitInt, Result, ReturnType, Token,
+};
+
+// loom_cases with an optional `preemption_bounds = [n, ...]` to run each case under
+struct LoomCases {
+    bounds: Vec<usize>,
+}
+
+impl Parse for LoomCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut bounds = Vec::new();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "preemption_bounds" => {
+                    let content;
+                    bracketed!(content in input);
+                    for lit in Punctuated::<LitInt, Token![,]>::parse_terminated(&content)? {
+                        let n: usize = lit.base10_parse()?;
+                        if bounds.contains(&n) {
+                            return Err(Error::new(
+                                lit.span(),
+                                format!("duplicate preemption bound: {}", n),
+                            ));
+                        }
+                        bounds.push(n);
+                    }
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown loom_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { bounds })
+    }
+}
+
+// Each explicit case is crossed with each of the preemption bounds, with the bound being passed
+// to the test function as an additional trailing argument. Without any bounds the cases are run
+// using the default loom configuration.
+fn render_cases(
+    span: Span,
+    bounds: &[usize],
+    n_params: usize,
+    explicit: Vec<ExplicitCase>,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let bounds: Vec<(String, proc_macro2::TokenStream)> = match bounds {
+        [] => vec![(String::new(), quote! { None })],
+        bounds => bounds
+            .iter()
+            .map(|n| (format!("preemption_bound_{}", n), quote! { Some(#n) }))
+            .collect(),
+    };
+
+    if explicit.is_empty() {
+        if n_params != 0 {
+            return Err(Error::new(
+                span,
+                "test_case attributes providing the arguments must be given beneath loom_cases",
+            ));
+        }
+        return Ok(bounds
+            .into_iter()
+            .map(|(name, bound)| {
+                let name = if name.is_empty() {
+                    "model".to_string()
+                } else {
+                    name
+                };
+                quote! { #[simple_test_case::test_case(#bound; #name)] }
+            })
+            .collect());
+    }
+
+    let mut rendered = Vec::new();
+    for case in explicit.iter() {
+        if case.args.len() != n_params {
+            return Err(wrong_arg_count(case.name.span(), &case.args, n_params));
+        }
+        for (bound_name, bound) in bounds.iter() {
+            let args = case.args.iter();
+            let name = match bound_name.as_str() {
+                "" => case.name.value(),
+                bound_name => format!("{} {}", case.name.value(), bound_name),
+            };
+            let rest = &case.rest;
+            rendered
+                .push(quote! { #[simple_test_case::test_case(#(#args,)* #bound; #name #rest)] });
+        }
+    }
+
+    Ok(rendered)
+}
+
+// The body of the test function is run as a loom model, with the arguments of each case being
+// captured by the model closure. As loom runs the closure many times, arguments that are not Copy
+// need to be cloned within the body before being consumed.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let span = Span::call_site();
+    let LoomCases { bounds } = parse_macro_input!(args as LoomCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    if original.sig.asyncness.is_some() || original.sig.output != ReturnType::Default {
+        return TokenStream::from(
+            Error::new_spanned(
+                &original.sig,
+                "loom_cases test functions must be synchronous and must not return a value",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let n_params = original.sig.inputs.len();
+    let res = extract_explicit_cases(&mut original.attrs)
+        .and_then(|explicit| render_cases(span, &bounds, n_params, explicit));
+    let case_attrs = match res {
+        Ok(case_attrs) => case_attrs,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let block = &original.block;
+    original.block = parse_quote! {{
+        let mut __loom_builder = ::loom::model::Builder::new();
+        __loom_builder.preemption_bound = __loom_preemption_bound;
+        __loom_builder.check(move || #block);
+    }};
+    original
+        .sig
+        .inputs
+        .push(parse_quote!(__loom_preemption_bound: Option<usize>));
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(bounds: &[usize], n_params: usize, explicit: Vec<ExplicitCase>) -> Vec<String> {
+        render_cases(Span::call_site(), bounds, n_params, explicit)
+            .unwrap()
+            .into_iter()
+            .map(|ts| ts.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn cases_are_crossed_with_preemption_bounds() {
+        let cases = rendered(
+            &[2, 3],
+            2,
+            vec![
+                parse_quote!(1, 2; "small"),
+                parse_quote!(10, 20; "large"; ignore),
+            ],
+        );
+
+        assert_eq!(cases.len(), 4);
+        assert_eq!(
+            cases[3],
+            quote!(#[simple_test_case::test_case(10, 20, Some(3usize); "large preemption_bound_3"; ignore)])
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn functions_without_arguments_only_need_bounds() {
+        let cases = rendered(&[], 0, vec![]);
+
+        assert_eq!(
+            cases,
+            vec![quote!(#[simple_test_case::test_case(None; "model")]).to_string()]
+        );
+        assert!(render_cases(Span::call_site(), &[1], 1, vec![]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// A JSON manifest of the cases generated for a test function, written to OUT_DIR when the
+// `manifest` feature is enabled so that external tooling can enumerate generated cases without
+// needing to parse Rust.
+use proc_macro2::{TokenStream, TokenTree};
+use std::{fs, path::Path};
+use syn::LitStr;
+
+// The directory (inside of OUT_DIR) that manifests are written to
+const MANIFEST_DIR: &str = "simple_test_case_manifest";
+
+pub(crate) struct ManifestCase {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) args: Vec<String>,
+    pub(crate) attrs: Vec<String>,
+    pub(crate) fixtures: Vec<String>,
+}
+
+// Any files included into the arguments of a case using include_str! or include_bytes!, which is
+// how the file based macros pass fixtures to the cases they generate
+pub(crate) fn fixture_paths(tokens: TokenStream) -> Vec<String> {
+    let mut paths = Vec::new();
+    let tts: Vec<TokenTree> = tokens.into_iter().collect();
+
+    for (i, tt) in tts.iter().enumerate() {
+        match tt {
+            TokenTree::Ident(ident) if ident == "include_str" || ident == "include_bytes" => {
+                let path = match (tts.get(i + 1), tts.get(i + 2)) {
+                    (Some(TokenTree::Punct(p)), Some(TokenTree::Group(g)))
+                        if p.as_char() == '!' =>
+                    {
+                        syn::parse2::<LitStr>(g.stream()).ok()
+                    }
+                    _ => None,
+                };
+                paths.extend(path.map(|lit| lit.value()));
+            }
+            TokenTree::Group(g) => paths.extend(fixture_paths(g.stream())),
+            _ => (),
+        }
+    }
+
+    paths
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+fn json_strings(strs: &[String]) -> String {
+    let strs: Vec<_> = strs.iter().map(|s| json_string(s)).collect();
+    format!("[{}]", strs.join(", "))
+}
+
+pub(crate) fn render_manifest(crate_name: &str, function: &str, cases: &[ManifestCase]) -> String {
+    let cases: Vec<_> = cases
+        .iter()
+        .map(|c| {
+            format!(
+                "    {{\"name\": {}, \"path\": {}, \"args\": {}, \"attrs\": {}, \"fixtures\": {}}}",
+                json_string(&c.name),
+                json_string(&c.path),
+                json_strings(&c.args),
+                json_strings(&c.attrs),
+                json_strings(&c.fixtures),
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"crate\": {},\n  \"function\": {},\n  \"cases\": [\n{}\n  ]\n}}\n",
+        json_string(crate_name),
+        json_string(function),
+        cases.join(",\n")
+    )
+}
+
+// OUT_DIR is only set for crates with a build script, so there is nowhere to write manifests for
+// any other crate and they are silently skipped.
+pub(crate) fn write_manifest(function: &str, cases: &[ManifestCase]) -> Result<(), String> {
+    let out_dir = match std::env::var("OUT_DIR") {
+        Ok(out_dir) => out_dir,
+        Err(_) => return Ok(()),
+    };
+    let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+
+    write_manifest_to(Path::new(&out_dir), &crate_name, function, cases)
+}
+
+// Each test function gets its own manifest file named after the function, which is overwritten
+// each time the function is expanded so that stale manifests are not left behind.
+fn write_manifest_to(
+    out_dir: &Path,
+    crate_name: &str,
+    function: &str,
+    cases: &[ManifestCase],
+) -> Result<(), String> {
+    let manifest = render_manifest(crate_name, function, cases);
+    let dir = out_dir.join(MANIFEST_DIR);
+    let path = dir.join(format!("{}.json", function));
+
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, manifest))
+        .map_err(|e| format!("unable to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn fixture_paths_are_found_in_arguments() {
+        let args = quote! {
+            "a.txt", include_str!("/data/a.txt"), decompress(include_bytes!("/data/b.gz"))
+        };
+
+        assert_eq!(fixture_paths(args), vec!["/data/a.txt", "/data/b.gz"]);
+    }
+
+    #[test]
+    fn manifests_are_rendered_as_json() {
+        let cases = vec![ManifestCase {
+            name: "a \"quoted\" case".to_string(),
+            path: "example::a_quoted_case".to_string(),
+            args: vec!["1".to_string(), "\"x\\n\"".to_string()],
+            attrs: vec![],
+            fixtures: vec![],
+        }];
+        let rendered = render_manifest("my_crate", "example", &cases);
+
+        assert_eq!(
+            rendered,
+            r#"{
+  "crate": "my_crate",
+  "function": "example",
+  "cases": [
+    {"name": "a \"quoted\" case", "path": "example::a_quoted_case", "args": ["1", "\"x\\n\""], "attrs": [], "fixtures": []}
+  ]
+}
+"#
+        );
+        assert!(crate::json::parse_json(&rendered).is_ok());
+    }
+
+    #[test]
+    fn manifests_are_written_once_per_function() {
+        let out_dir = std::env::temp_dir().join(format!("manifest_test_{}", std::process::id()));
+        let case = |name: &str| ManifestCase {
+            name: name.to_string(),
+            path: format!("example::{}", name),
+            args: vec![],
+            attrs: vec![],
+            fixtures: vec![],
+        };
+
+        write_manifest_to(&out_dir, "my_crate", "example", &[case("old")]).unwrap();
+        write_manifest_to(&out_dir, "my_crate", "example", &[case("new")]).unwrap();
+
+        let dir = out_dir.join(MANIFEST_DIR);
+        let files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        let manifest = fs::read_to_string(dir.join("example.json")).unwrap();
+        fs::remove_dir_all(&out_dir).unwrap();
+
+        assert_eq!(files, vec!["example.json"]);
+        assert!(manifest.contains("\"name\": \"new\""), "{}", manifest);
+        assert!(!manifest.contains("\"name\": \"old\""), "{}", manifest);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{line_cases::with_line_number, util::read_fixture_file};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Ident, ItemFn, LitStr, Result, Token,
+};
+
+// markdown_cases("path/to/file.md") with an optional `lang = "json"` to select code blocks by
+struct MarkdownCases {
+    span: Span,
+    path: String,
+    lang: Option<String>,
+}
+
+impl Parse for MarkdownCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let path: LitStr = input.parse()?;
+        let mut lang = None;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "lang" => lang = Some(input.parse::<LitStr>()?.value()),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown markdown_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            span,
+            path: path.value(),
+            lang,
+        })
+    }
+}
+
+// A fenced code block along with the (1-indexed) line number of its opening fence
+#[derive(Debug, PartialEq, Eq)]
+struct CodeBlock {
+    line: usize,
+    lang: String,
+    content: String,
+}
+
+// The indent, fence character and fence length of a line opening a code block along with its info
+// string, if the line is one
+fn opening_fence(line: &str) -> Option<(usize, char, usize, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    let c = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|ch| *ch == c).count();
+    let info = trimmed[len..].trim();
+
+    if indent > 3 || len < 3 || (c == '`' && info.contains('`')) {
+        return None;
+    }
+
+    Some((indent, c, len, info))
+}
+
+fn is_closing_fence(line: &str, c: char, len: usize) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    let n = trimmed.chars().take_while(|ch| *ch == c).count();
+
+    line.len() - trimmed.len() <= 3 && n >= len && trimmed[n..].trim().is_empty()
+}
+
+// Only fenced code blocks are extracted: the language of a block is the first word of its info
+// string (so "rust,ignore" and "json title=example" are "rust" and "json") and the indentation of
+// the opening fence is removed from each line of the block.
+fn parse_code_blocks(raw: &str) -> std::result::Result<Vec<CodeBlock>, String> {
+    let mut blocks = Vec::new();
+    let mut lines = raw.lines().enumerate();
+
+    while let Some((i, line)) = lines.next() {
+        let (indent, c, len, info) = match opening_fence(line) {
+            Some(fence) => fence,
+            None => continue,
+        };
+        let lang = info
+            .split(|ch: char| ch.is_whitespace() || ch == ',')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut content = String::new();
+        let mut closed = false;
+        for (_, line) in lines.by_ref() {
+            if is_closing_fence(line, c, len) {
+                closed = true;
+                break;
+            }
+            let n = line.len() - line.trim_start_matches(' ').len();
+            content.push_str(&line[n.min(indent)..]);
+            content.push('\n');
+        }
+
+        if !closed {
+            return Err(format!("unclosed code block starting on line {}", i + 1));
+        }
+
+        blocks.push(CodeBlock {
+            line: i + 1,
+            lang,
+            content,
+        });
+    }
+
+    Ok(blocks)
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let MarkdownCases { span, path, lang } = parse_macro_input!(args as MarkdownCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let line_number = match with_line_number(&original) {
+        Some(line_number) => line_number,
+        None => {
+            return TokenStream::from(
+                Error::new(
+                    span,
+                    "markdown_cases test functions must accept (block: &str) or (line_number: usize, block: &str) as arguments",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let res = read_fixture_file(span, &path).and_then(|(raw, abs_path)| {
+        let blocks = parse_code_blocks(&raw)
+            .map_err(|e| Error::new(span, format!("invalid markdown file {}: {}", path, e)))?;
+
+        let case_attrs: Vec<_> = blocks
+            .into_iter()
+            .filter(|b| lang.is_none() || lang.as_ref() == Some(&b.lang))
+            .map(|CodeBlock { line, content, .. }| {
+                let case = format!("line {}", line);
+                if line_number {
+                    quote! { #[simple_test_case::test_case(#line, #content; #case)] }
+                } else {
+                    quote! { #[simple_test_case::test_case(#content; #case)] }
+                }
+            })
+            .collect();
+
+        if case_attrs.is_empty() {
+            let msg = match &lang {
+                Some(lang) => format!("no {} code blocks found in {}", lang, path),
+                None => format!("no code blocks found in {}", path),
+            };
+            return Err(Error::new(span, msg));
+        }
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &str = "\
+# Examples
+
+```json
+{\"a\": 1}
+```
+
+Some text with `inline code`.
+
+  ~~~~rust,ignore
+  fn main() {
+      ```
+  }
+  ~~~~
+
+```
+no language
+```
+";
+
+    #[test]
+    fn fenced_code_blocks_are_extracted() {
+        let blocks = parse_code_blocks(RAW).unwrap();
+
+        assert_eq!(
+            blocks,
+            vec![
+                CodeBlock {
+                    line: 3,
+                    lang: "json".to_string(),
+                    content: "{\"a\": 1}\n".to_string(),
+                },
+                CodeBlock {
+                    line: 9,
+                    lang: "rust".to_string(),
+                    content: "fn main() {\n    ```\n}\n".to_string(),
+                },
+                CodeBlock {
+                    line: 15,
+                    lang: "".to_string(),
+                    content: "no language\n".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_code_blocks_are_an_error() {
+        assert!(parse_code_blocks("text\n```json\n{}\n").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Error, Expr, Ident, ItemFn, Path, Result, Stmt,
+};
+
+// The static Once guarding a once_setup expression along with the statement that runs it
+pub(crate) fn once_setup_parts(once: &Ident, expr: &Expr) -> (proc_macro2::TokenStream, Stmt) {
+    let item = quote! {
+        static #once: ::std::sync::Once = ::std::sync::Once::new();
+    };
+    let stmt = parse_quote! {
+        #once.call_once(|| {
+            #expr;
+        });
+    };
+
+    (item, stmt)
+}
+
+// Glob up any once_setup attributes beneath the test_case attributes so that test_case can run
+// them before binding the arguments of each case
+pub(crate) fn extract_once_setups(attrs: &mut Vec<Attribute>) -> Result<Vec<Expr>> {
+    let once_setup_attr: Path = parse_quote!(once_setup);
+    let qualified_once_setup_attr: Path = parse_quote!(simple_test_case::once_setup);
+    let mut exprs = Vec::new();
+    let mut res = Ok(());
+
+    attrs.retain(|a| {
+        if a.path() != &once_setup_attr && a.path() != &qualified_once_setup_attr {
+            return true;
+        }
+        match a.parse_args::<Expr>() {
+            Ok(expr) => exprs.push(expr),
+            Err(err) => {
+                res = Err(Error::new_spanned(
+                    a,
+                    format!("invalid once_setup: {}", err),
+                ))
+            }
+        }
+        false
+    });
+
+    res.map(|_| exprs)
+}
+
+// Used directly (rather than being picked up by test_case) the Once is written out alongside the
+// test function and the expression is run at the start of its body
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let expr = parse_macro_input!(args as Expr);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    let ident = original.sig.ident.to_string();
+    let once = format_ident!(
+        "__ONCE_SETUP_{}",
+        ident.strip_prefix("r#").unwrap_or(&ident).to_uppercase()
+    );
+    let (item, stmt) = once_setup_parts(&once, &expr);
+    original.block.stmts.insert(0, stmt);
+
+    TokenStream::from(quote! {
+        #item
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn once_setup_attributes_are_extracted() {
+        let mut attrs: Vec<Attribute> = vec![
+            parse_quote!(#[once_setup(init_logging())]),
+            parse_quote!(#[test]),
+            parse_quote!(#[simple_test_case::once_setup(install_provider())]),
+        ];
+        let exprs = extract_once_setups(&mut attrs).unwrap();
+        let expected: Vec<Expr> = vec![
+            parse_quote!(init_logging()),
+            parse_quote!(install_provider()),
+        ];
+
+        assert_eq!(exprs, expected);
+        assert_eq!(attrs, vec![parse_quote!(#[test])]);
+    }
+}
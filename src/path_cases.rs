@@ -0,0 +1,243 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Error, FnArg, Ident, ItemFn, Result, Token, Type,
+};
+
+// The platform a corpus entry is specific to, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Platform {
+    Any,
+    Unix,
+    Windows,
+}
+
+// Paths that commonly break path handling code, along with the platform they are meaningful on.
+// Entries that only have special meaning on one platform are only run there.
+const CORPUS: &[(&str, &str, Platform)] = &[
+    ("empty", "", Platform::Any),
+    ("dot", ".", Platform::Any),
+    ("dot_dot", "..", Platform::Any),
+    ("trailing_slash", "dir/", Platform::Any),
+    ("double_slash", "a//b", Platform::Any),
+    ("dot_segments", "a/./b/../c", Platform::Any),
+    ("traversal", "../../../etc/passwd", Platform::Any),
+    ("hidden", ".hidden", Platform::Any),
+    ("leading_dash", "-rf", Platform::Any),
+    ("spaces", " spaced  name ", Platform::Any),
+    ("trailing_dot", "name.", Platform::Any),
+    ("multiple_extensions", "archive.tar.gz", Platform::Any),
+    ("unicode", "répertoire/日本語/файл", Platform::Any),
+    ("combining", "cafe\u{301}", Platform::Any),
+    ("emoji", "📁/📄", Platform::Any),
+    ("newline", "line\nbreak", Platform::Any),
+    ("nul_byte", "a\0b", Platform::Any),
+    ("root", "/", Platform::Unix),
+    ("absolute_traversal", "/../../etc", Platform::Unix),
+    ("backslash", "a\\b", Platform::Unix),
+    ("tilde", "~/file", Platform::Unix),
+    ("drive_absolute", "C:\\Windows\\System32", Platform::Windows),
+    ("drive_relative", "C:file", Platform::Windows),
+    ("unc", "\\\\server\\share\\file", Platform::Windows),
+    ("verbatim", "\\\\?\\C:\\file", Platform::Windows),
+    ("device", "\\\\.\\COM1", Platform::Windows),
+    ("mixed_separators", "a/b\\c", Platform::Windows),
+    ("reserved_con", "CON", Platform::Windows),
+    ("reserved_nul_with_extension", "nul.txt", Platform::Windows),
+    ("reserved_com1", "dir\\COM1", Platform::Windows),
+    (
+        "alternate_data_stream",
+        "file.txt:stream",
+        Platform::Windows,
+    ),
+];
+
+// Long paths are generated rather than written out in the corpus
+const DEEP_NESTING: usize = 256;
+const MAX_COMPONENT_LEN: usize = 255;
+
+fn corpus() -> Vec<(&'static str, String, Platform)> {
+    let mut corpus: Vec<_> = CORPUS
+        .iter()
+        .map(|(n, p, platform)| (*n, p.to_string(), *platform))
+        .collect();
+    corpus.push(("deep_nesting", "d/".repeat(DEEP_NESTING), Platform::Any));
+    corpus.push((
+        "max_component",
+        "a".repeat(MAX_COMPONENT_LEN),
+        Platform::Any,
+    ));
+    corpus.push((
+        "oversized_component",
+        "a".repeat(MAX_COMPONENT_LEN + 1),
+        Platform::Any,
+    ));
+
+    corpus
+}
+
+// path_cases with an optional `skip = [name, ...]` for entries that should not be used
+struct PathCases {
+    skip: Vec<Ident>,
+}
+
+impl Parse for PathCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut skip = Vec::new();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "skip" => {
+                    let content;
+                    bracketed!(content in input);
+                    skip.extend(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown path_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { skip })
+    }
+}
+
+// The kinds of argument that a path can be passed as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgKind {
+    Str,
+    String,
+    Path,
+    PathBuf,
+}
+
+fn arg_kind(ty: &Type) -> Option<ArgKind> {
+    let last_ident = |ty: &Type| match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    };
+
+    match ty {
+        Type::Reference(r) => match last_ident(&r.elem)?.as_str() {
+            "str" => Some(ArgKind::Str),
+            "Path" => Some(ArgKind::Path),
+            _ => None,
+        },
+        ty => match last_ident(ty)?.as_str() {
+            "String" => Some(ArgKind::String),
+            "PathBuf" => Some(ArgKind::PathBuf),
+            _ => None,
+        },
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let PathCases { skip } = parse_macro_input!(args as PathCases);
+    let original = parse_macro_input!(input as ItemFn);
+    let corpus = corpus();
+
+    let kind = match original.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] => arg_kind(&pt.ty),
+        _ => None,
+    };
+    let kind = match kind {
+        Some(kind) => kind,
+        None => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "path_cases test functions must accept a single &str, String, &Path or PathBuf argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    if let Some(s) = skip
+        .iter()
+        .find(|s| !corpus.iter().any(|(n, _, _)| *s == n))
+    {
+        let names: Vec<_> = corpus.iter().map(|(n, _, _)| *n).collect();
+        return TokenStream::from(
+            Error::new(
+                s.span(),
+                format!(
+                    "unknown path case: {} (expected one of {})",
+                    s,
+                    names.join(", ")
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = corpus
+        .iter()
+        .filter(|(name, _, _)| !skip.iter().any(|s| s == name))
+        .map(|(name, p, platform)| {
+            let arg = match kind {
+                ArgKind::Str => quote! { #p },
+                ArgKind::String => quote! { #p.to_string() },
+                ArgKind::Path => quote! { ::std::path::Path::new(#p) },
+                ArgKind::PathBuf => quote! { ::std::path::PathBuf::from(#p) },
+            };
+            let cfg: Option<syn::Attribute> = match platform {
+                Platform::Any => None,
+                Platform::Unix => Some(parse_quote!(#[cfg(unix)])),
+                Platform::Windows => Some(parse_quote!(#[cfg(windows)])),
+            };
+            match cfg {
+                Some(cfg) => quote! { #[simple_test_case::test_case(#arg; #name; #cfg)] },
+                None => quote! { #[simple_test_case::test_case(#arg; #name)] },
+            }
+        });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_names_are_unique() {
+        let mut names: Vec<_> = corpus().into_iter().map(|(n, _, _)| n).collect();
+        let n = names.len();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(names.len(), n);
+    }
+
+    #[test]
+    fn argument_kinds_are_detected() {
+        let cases: Vec<(Type, Option<ArgKind>)> = vec![
+            (parse_quote!(&str), Some(ArgKind::Str)),
+            (parse_quote!(String), Some(ArgKind::String)),
+            (parse_quote!(&Path), Some(ArgKind::Path)),
+            (parse_quote!(&std::path::Path), Some(ArgKind::Path)),
+            (parse_quote!(PathBuf), Some(ArgKind::PathBuf)),
+            (parse_quote!(&[u8]), None),
+        ];
+
+        for (ty, expected) in cases {
+            assert_eq!(arg_kind(&ty), expected);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::util::sequence_arg;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Expr, ExprArray, FnArg, Ident, ItemFn, LitInt, Result, Token,
+};
+
+// Matches the limit used by range_cases
+const MAX_CASES: usize = 10_000;
+
+// 20! is the largest factorial that fits in a u64
+const MAX_ELEMENTS: usize = 20;
+
+// permutation_cases([a, b, ...]) with an optional `max = n` to cap the number of cases
+struct PermutationCases {
+    span: Span,
+    elems: Vec<Expr>,
+    max: Option<usize>,
+}
+
+impl Parse for PermutationCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let list: ExprArray = input.parse()?;
+        let mut max = None;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "max" => {
+                    let lit: LitInt = input.parse()?;
+                    let n: usize = lit.base10_parse()?;
+                    if n == 0 || n > MAX_CASES {
+                        return Err(Error::new(
+                            lit.span(),
+                            format!("max must be between 1 and {}", MAX_CASES),
+                        ));
+                    }
+                    max = Some(n);
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown permutation_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        let elems: Vec<Expr> = list.elems.into_iter().collect();
+        if elems.is_empty() || elems.len() > MAX_ELEMENTS {
+            return Err(Error::new(
+                span,
+                format!(
+                    "permutation_cases requires between 1 and {} elements",
+                    MAX_ELEMENTS
+                ),
+            ));
+        }
+
+        Ok(Self { span, elems, max })
+    }
+}
+
+// The nth permutation of 0..len in lexicographic order, found by decoding n in the factorial
+// number system so that permutations can be sampled without generating all of them.
+fn nth_permutation(len: usize, mut n: u64) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..len).collect();
+    let mut perm = Vec::with_capacity(len);
+
+    for i in (0..len).rev() {
+        let f = factorial(i);
+        perm.push(remaining.remove((n / f) as usize));
+        n %= f;
+    }
+
+    perm
+}
+
+fn factorial(n: usize) -> u64 {
+    (1..=n as u64).product()
+}
+
+// Every permutation if there are few enough of them, otherwise `max` permutations spread evenly
+// across the lexicographic order so that the sample is not dominated by a common prefix.
+fn selected_permutations(span: Span, len: usize, max: Option<usize>) -> Result<Vec<Vec<usize>>> {
+    let total = factorial(len);
+    let limit = max.unwrap_or(MAX_CASES) as u64;
+
+    if total > limit && max.is_none() {
+        return Err(Error::new(
+            span,
+            format!(
+                "{} elements have {} permutations: use `max = n` to sample at most {} of them",
+                len, total, MAX_CASES
+            ),
+        ));
+    }
+
+    let indices: Vec<u64> = if total <= limit {
+        (0..total).collect()
+    } else {
+        (0..limit)
+            .map(|i| (i as u128 * total as u128 / limit as u128) as u64)
+            .collect()
+    };
+
+    Ok(indices
+        .into_iter()
+        .map(|n| nth_permutation(len, n))
+        .collect())
+}
+
+// Each case is named after the indices of the elements in the order they are given to the test
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let PermutationCases { span, elems, max } = parse_macro_input!(args as PermutationCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let ty = match original.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] => (*pt.ty).clone(),
+        _ => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "permutation_cases test functions must accept a single argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let perms = match selected_permutations(span, elems.len(), max) {
+        Ok(perms) => perms,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs = perms.iter().map(|perm| {
+        let arg = sequence_arg(perm.iter().map(|&i| &elems[i]), &ty);
+        let name = perm
+            .iter()
+            .fold("perm".to_string(), |acc, i| format!("{}_{}", acc, i));
+        quote! { #[simple_test_case::test_case(#arg; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutations_are_in_lexicographic_order() {
+        let perms = selected_permutations(Span::call_site(), 3, None).unwrap();
+
+        assert_eq!(
+            perms,
+            vec![
+                vec![0, 1, 2],
+                vec![0, 2, 1],
+                vec![1, 0, 2],
+                vec![1, 2, 0],
+                vec![2, 0, 1],
+                vec![2, 1, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn large_lists_require_a_max() {
+        assert!(selected_permutations(Span::call_site(), 8, None).is_err());
+
+        let perms = selected_permutations(Span::call_site(), 20, Some(4)).unwrap();
+        assert_eq!(perms.len(), 4);
+        assert_eq!(perms[0], (0..20).collect::<Vec<_>>());
+        assert_eq!(perms[2][0], 10);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::util::{case_attr, param_names_and_types, read_fixture_file};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Error, Expr, ExprLit, Ident, ItemFn, Lit, LitStr, Result, Token, Type,
+};
+
+// A single `name = value` binding from the "shrinks to" comment of a regression
+struct Binding {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Binding {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let value = input.parse()?;
+
+        Ok(Self { name, value })
+    }
+}
+
+// A recorded failure of the form: cc <seed hash> # shrinks to a = 1, b = "two"
+struct Regression {
+    line: usize,
+    seed: String,
+    bindings: Vec<Binding>,
+}
+
+// Proptest records the debug representation of the minimal failing input alongside the seed for
+// each failure, which is parsed back into Rust expressions for use as the arguments of each case.
+fn parse_regressions(raw: &str) -> std::result::Result<Vec<Regression>, String> {
+    let mut regressions = Vec::new();
+
+    for (i, line) in raw.lines().enumerate() {
+        let n = i + 1;
+        let line = line.trim();
+        let rest = match line.strip_prefix("cc ") {
+            Some(rest) => rest,
+            None if line.is_empty() || line.starts_with('#') => continue,
+            None => return Err(format!("line {}: expected a 'cc' entry", n)),
+        };
+
+        let (seed, comment) = rest.split_once('#').unwrap_or((rest, ""));
+        let shrunk = comment
+            .trim()
+            .strip_prefix("shrinks to")
+            .ok_or_else(|| format!("line {}: no 'shrinks to' values were recorded", n))?;
+        let bindings = syn::parse::Parser::parse_str(
+            Punctuated::<Binding, Token![,]>::parse_terminated,
+            shrunk,
+        )
+        .map_err(|e| format!("line {}: unable to parse recorded values: {}", n, e))?;
+
+        regressions.push(Regression {
+            line: n,
+            seed: seed.trim().to_string(),
+            bindings: bindings.into_iter().collect(),
+        });
+    }
+
+    Ok(regressions)
+}
+
+// Debug output uses string literals for String and array syntax for Vec, so these are converted
+// to match the type of the parameter. Anything else is left to rustc to type check.
+fn convert(value: Expr, ty: &Type) -> Expr {
+    let string_ty: Type = parse_quote!(String);
+    let is_vec =
+        matches!(ty, Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Vec"));
+
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) if *ty == string_ty => parse_quote!(#s.to_string()),
+        Expr::Array(arr) if is_vec => {
+            let elems = arr.elems.iter();
+            parse_quote!(vec![#(#elems),*])
+        }
+        value => value,
+    }
+}
+
+fn regression_attr(
+    span: Span,
+    params: &[(String, Type)],
+    reg: Regression,
+) -> Result<proc_macro2::TokenStream> {
+    let Regression {
+        line,
+        seed,
+        mut bindings,
+    } = reg;
+    let err = |msg: String| Error::new(span, format!("line {}: {}", line, msg));
+
+    if let Some(b) = bindings
+        .iter()
+        .find(|b| !params.iter().any(|(p, _)| b.name == p))
+    {
+        return Err(err(format!(
+            "'{}' is not an argument of the test function",
+            b.name
+        )));
+    }
+
+    let mut arg = |param: &String, ty: &Type| match bindings.iter().position(|b| b.name == *param) {
+        Some(ix) => Ok(convert(bindings.remove(ix).value, ty)),
+        None => Err(err(format!("no value was recorded for '{}'", param))),
+    };
+    let args = params
+        .iter()
+        .map(|(param, ty)| arg(param, ty))
+        .collect::<Result<Vec<_>>>()?;
+
+    let short_seed: String = seed.chars().take(8).collect();
+    let name = format!("cc_{}", short_seed);
+
+    Ok(case_attr(&args, &name))
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(args as LitStr);
+    let original = parse_macro_input!(input as ItemFn);
+    let span = path.span();
+
+    let res = param_names_and_types(&original, "proptest_regressions").and_then(|params| {
+        let (raw, abs_path) = read_fixture_file(span, &path.value())?;
+        let regressions = parse_regressions(&raw).map_err(|e| {
+            Error::new(
+                span,
+                format!("invalid proptest regression file {}: {}", path.value(), e),
+            )
+        })?;
+        if regressions.is_empty() {
+            return Err(Error::new(span, "no regressions found"));
+        }
+
+        let case_attrs = regressions
+            .into_iter()
+            .map(|reg| regression_attr(span, &params, reg))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the regression file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &str = r#"
+# Seeds for failure cases proptest has generated in the past. It is
+# automatically read and these particular cases re-run before any
+# novel cases are generated.
+cc 8a3f0c2d9b1e4f5a6b7c8d9e0f1a2b3c # shrinks to s = "a,b", n = -3
+cc 17b2e4d6f8a0c1e3 # shrinks to n = 0, s = ""
+"#;
+
+    #[test]
+    fn regressions_are_parsed() {
+        let regressions = parse_regressions(RAW).unwrap();
+        let names: Vec<Vec<String>> = regressions
+            .iter()
+            .map(|r| r.bindings.iter().map(|b| b.name.to_string()).collect())
+            .collect();
+
+        assert_eq!(regressions.len(), 2);
+        assert_eq!(regressions[0].line, 5);
+        assert_eq!(regressions[1].seed, "17b2e4d6f8a0c1e3");
+        assert_eq!(names, vec![vec!["s", "n"], vec!["n", "s"]]);
+    }
+
+    #[test]
+    fn values_are_passed_in_parameter_order() {
+        let reg = parse_regressions(RAW).unwrap().remove(0);
+        let params = vec![
+            ("n".to_string(), parse_quote!(i32)),
+            ("s".to_string(), parse_quote!(String)),
+        ];
+        let attr = regression_attr(Span::call_site(), &params, reg).unwrap();
+        let expected = quote! {
+            #[simple_test_case::test_case(-3, "a,b".to_string(); "cc_8a3f0c2d")]
+        };
+
+        assert_eq!(attr.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn entries_without_values_are_an_error() {
+        assert!(parse_regressions("cc 8a3f0c2d").is_err());
+        assert!(parse_regressions("xx 8a3f0c2d # shrinks to n = 1").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Ident, ItemFn, LitInt, Path, Result, Token,
+};
+
+// Matches the limit used by range_cases
+const MAX_CASES: usize = 10_000;
+
+// random_cases(count = n, seed = n) with an optional `gen = path::to::generator`
+struct RandomCases {
+    span: Span,
+    count: usize,
+    seed: u64,
+    gen: Option<Path>,
+}
+
+impl Parse for RandomCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let mut count = None;
+        let mut seed = None;
+        let mut gen = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "count" => {
+                    let lit: LitInt = input.parse()?;
+                    let n: usize = lit.base10_parse()?;
+                    if n == 0 || n > MAX_CASES {
+                        return Err(Error::new(
+                            lit.span(),
+                            format!("count must be between 1 and {}", MAX_CASES),
+                        ));
+                    }
+                    count = Some(n);
+                }
+                "seed" => seed = Some(input.parse::<LitInt>()?.base10_parse()?),
+                "gen" => gen = Some(input.parse()?),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown random_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self {
+            span,
+            count: count.ok_or_else(|| Error::new(span, "random_cases requires a count"))?,
+            seed: seed.ok_or_else(|| Error::new(span, "random_cases requires a seed"))?,
+            gen,
+        })
+    }
+}
+
+// SplitMix64: a small, well distributed generator that is trivial to reproduce outside of the
+// macro if a case ever needs to be investigated by hand.
+fn case_seeds(seed: u64, count: usize) -> Vec<u64> {
+    let mut state = seed;
+    let mut seeds = Vec::with_capacity(count);
+
+    while seeds.len() < count {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        if !seeds.contains(&z) {
+            seeds.push(z);
+        }
+    }
+
+    seeds
+}
+
+// Each case is given a seed derived from the seed of the attribute and is named after it. The
+// generator is called with the seed of the case when the test runs: a single argument is given the
+// value it returns and multiple arguments are given the fields of the tuple it returns.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let RandomCases {
+        span,
+        count,
+        seed,
+        gen,
+    } = parse_macro_input!(args as RandomCases);
+    let original = parse_macro_input!(input as ItemFn);
+    let n_args = original.sig.inputs.len();
+
+    if n_args == 0 || (gen.is_none() && n_args != 1) {
+        return TokenStream::from(
+            Error::new(
+                span,
+                "random_cases test functions must accept a single u64 seed or the values returned by `gen`",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = case_seeds(seed, count).into_iter().map(|case_seed| {
+        let lit = Literal::u64_suffixed(case_seed);
+        let name = format!("seed_{:016x}", case_seed);
+        let args: Vec<_> = match &gen {
+            None => vec![quote! { #lit }],
+            Some(gen) if n_args == 1 => vec![quote! { #gen(#lit) }],
+            Some(gen) => (0..n_args)
+                .map(|i| {
+                    let field = syn::Index::from(i);
+                    quote! { #gen(#lit).#field }
+                })
+                .collect(),
+        };
+
+        quote! { #[simple_test_case::test_case(#(#args),*; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn seeds_are_deterministic_and_distinct() {
+        let seeds = case_seeds(0xdeadbeef, 100);
+        let mut deduped = seeds.clone();
+        deduped.sort();
+        deduped.dedup();
+
+        assert_eq!(seeds, case_seeds(0xdeadbeef, 100));
+        assert_ne!(seeds, case_seeds(0xdeadbef0, 100));
+        assert_eq!(deduped.len(), 100);
+        // The first output of SplitMix64 seeded with 0
+        assert_eq!(case_seeds(0, 1), vec![0xe220a8397b1dcdaf]);
+    }
+
+    #[test]
+    fn count_and_seed_are_required() {
+        let parsed: RandomCases = parse_quote!(count = 32, seed = 0xDEADBEEF, gen = my_gen);
+
+        assert_eq!(parsed.count, 32);
+        assert_eq!(parsed.seed, 0xdeadbeef);
+        assert!(syn::parse2::<RandomCases>(quote!(count = 32)).is_err());
+        assert!(syn::parse2::<RandomCases>(quote!(seed = 1)).is_err());
+        assert!(syn::parse2::<RandomCases>(quote!(count = 0, seed = 1)).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    Error, Expr, ExprLit, ExprRange, ExprUnary, Ident, ItemFn, Lit, LitInt, RangeLimits, Result,
+    Token, UnOp,
+};
+
+// Expanding a range that is larger than this is almost certainly a mistake that would otherwise
+// result in the compiler appearing to hang.
+const MAX_CASES: usize = 10_000;
+
+// range_cases(start..end) or range_cases(start..=end) with an optional `step = n`
+struct RangeCases {
+    span: Span,
+    start: i128,
+    end: i128,
+    inclusive: bool,
+    step: u64,
+}
+
+impl Parse for RangeCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let range: ExprRange = input.parse()?;
+        let mut step = 1;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "step" => {
+                    let lit: LitInt = input.parse()?;
+                    step = lit.base10_parse()?;
+                    if step == 0 {
+                        return Err(Error::new(lit.span(), "step must be greater than zero"));
+                    }
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown range_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        let bound = |expr: &Option<Box<Expr>>| match expr {
+            Some(expr) => int_value(expr),
+            None => Err(Error::new(
+                range.span(),
+                "range_cases requires a range with both a start and an end",
+            )),
+        };
+        let start = bound(&range.start)?;
+        let end = bound(&range.end)?;
+
+        Ok(Self {
+            span,
+            start,
+            end,
+            inclusive: matches!(range.limits, RangeLimits::Closed(_)),
+            step,
+        })
+    }
+}
+
+fn int_value(expr: &Expr) -> Result<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_value(expr).map(|n| -n),
+        expr => Err(Error::new(
+            expr.span(),
+            "range_cases bounds must be integer literals",
+        )),
+    }
+}
+
+impl RangeCases {
+    fn values(&self) -> Result<Vec<i128>> {
+        let end = if self.inclusive {
+            self.end
+        } else {
+            self.end - 1
+        };
+        let mut values = Vec::new();
+        let mut n = self.start;
+
+        while n <= end {
+            if values.len() == MAX_CASES {
+                return Err(Error::new(
+                    self.span,
+                    format!("range_cases would generate more than {} cases", MAX_CASES),
+                ));
+            }
+            values.push(n);
+            n += self.step as i128;
+        }
+
+        if values.is_empty() {
+            return Err(Error::new(self.span, "range_cases range is empty"));
+        }
+
+        Ok(values)
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let range = parse_macro_input!(args as RangeCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let values = match range.values() {
+        Ok(values) => values,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs = values.into_iter().map(|n| {
+        let lit = Literal::i128_unsuffixed(n.abs());
+        let (value, name) = match n < 0 {
+            true => (quote! { -#lit }, format!("neg_{}", n.abs())),
+            false => (quote! { #lit }, n.to_string()),
+        };
+
+        quote! { #[simple_test_case::test_case(#value; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn range_values() {
+        let cases: Vec<(RangeCases, Vec<i128>)> = vec![
+            (parse_quote!(0..3), vec![0, 1, 2]),
+            (parse_quote!(0..=3), vec![0, 1, 2, 3]),
+            (parse_quote!(-2..=2, step = 2), vec![-2, 0, 2]),
+            (parse_quote!(1..10, step = 4), vec![1, 5, 9]),
+        ];
+
+        for (range, expected) in cases {
+            assert_eq!(range.values().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_ranges_are_an_error() {
+        let empty: RangeCases = parse_quote!(3..3);
+        let huge: RangeCases = parse_quote!(0..1_000_000);
+
+        assert!(empty.values().is_err());
+        assert!(huge.values().is_err());
+        assert!(syn::parse2::<RangeCases>(quote!(0..)).is_err());
+        assert!(syn::parse2::<RangeCases>(quote!(0..N)).is_err());
+        assert!(syn::parse2::<RangeCases>(quote!(0..4, step = 0)).is_err());
+    }
+}
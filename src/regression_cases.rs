@@ -0,0 +1,204 @@
+use crate::{json::Value, json_cases::value_to_expr, toml::parse_toml, util::read_fixture_file};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Error, FnArg, ItemFn, LitStr, Result, Type};
+
+// The name of the array of tables that regressions are listed under
+const TABLE: &str = "regression";
+
+// The issue number is taken to be the last run of digits in the issue link, which covers the
+// URL schemes of the common issue trackers (…/issues/123, …/browse/PROJ-123, …?id=123, #123).
+fn issue_number(issue: &str) -> Option<&str> {
+    let end = issue.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = issue[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+
+    Some(&issue[start..end])
+}
+
+fn param_types(_fn: &ItemFn) -> Result<Vec<Type>> {
+    _fn.sig
+        .inputs
+        .iter()
+        .map(|fnarg| match fnarg {
+            FnArg::Typed(pt) => Ok((*pt.ty).clone()),
+            FnArg::Receiver(_) => Err(Error::new_spanned(
+                fnarg,
+                "self is not permitted for test cases",
+            )),
+        })
+        .collect()
+}
+
+// Each [[regression]] table requires a name, a link to the issue it was found in and the
+// arguments to pass to the test function in order
+fn resolve_cases(
+    span: Span,
+    root: Value,
+    params: &[Type],
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let tables = match root {
+        Value::Object(mut fields) => match fields.iter().position(|(k, _)| k == TABLE) {
+            Some(i) => fields.swap_remove(i).1,
+            None => return Err(Error::new(span, format!("no [[{}]] tables found", TABLE))),
+        },
+        _ => unreachable!("the root of a toml document is always a table"),
+    };
+    let tables = match tables {
+        Value::Array(tables) => tables,
+        _ => {
+            return Err(Error::new(
+                span,
+                format!("{} must be an array of tables ([[{}]])", TABLE, TABLE),
+            ))
+        }
+    };
+
+    tables
+        .into_iter()
+        .enumerate()
+        .map(|(i, table)| {
+            let n = i + 1;
+            let err = |msg: String| Error::new(span, format!("regression {}: {}", n, msg));
+            let fields = match table {
+                Value::Object(fields) => fields,
+                _ => unreachable!("arrays of tables only contain tables"),
+            };
+            let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+            let get_str = |key: &str| match get(key) {
+                Some(Value::Str(s)) => Ok(s.clone()),
+                Some(other) => Err(err(format!(
+                    "'{}' must be a string but found {}",
+                    key,
+                    other.kind()
+                ))),
+                None => Err(err(format!("missing '{}'", key))),
+            };
+
+            let name = get_str("name")?;
+            let issue = get_str("issue")?;
+            let number = issue_number(&issue)
+                .ok_or_else(|| err(format!("no issue number found in '{}'", issue)))?;
+
+            let values = match get("args") {
+                Some(Value::Array(values)) => values,
+                Some(other) => {
+                    return Err(err(format!(
+                        "'args' must be an array but found {}",
+                        other.kind()
+                    )))
+                }
+                None => return Err(err("missing 'args'".to_string())),
+            };
+            if values.len() != params.len() {
+                return Err(err(format!(
+                    "expected {} args but found {}",
+                    params.len(),
+                    values.len()
+                )));
+            }
+            let args = values
+                .iter()
+                .zip(params)
+                .enumerate()
+                .map(|(j, (value, ty))| {
+                    value_to_expr(value, ty).map_err(|e| err(format!("arg {}: {}", j + 1, e)))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let case = format!("issue_{} {}", number, name);
+
+            Ok(quote! {
+                #[simple_test_case::test_case(#(#args,)* #issue; #case)]
+            })
+        })
+        .collect()
+}
+
+// The link to the issue is passed to the test function as an additional argument and printed by a
+// guard if the test panics, so that failures point straight at the original report.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(args as LitStr);
+    let mut original = parse_macro_input!(input as ItemFn);
+    let span = path.span();
+
+    let res = param_types(&original).and_then(|params| {
+        let (raw, abs_path) = read_fixture_file(span, &path.value())?;
+        let root = parse_toml(&raw)
+            .map_err(|e| Error::new(span, format!("invalid toml file {}: {}", path.value(), e)))?;
+        let case_attrs = resolve_cases(span, root, &params)?;
+
+        let block = &original.block;
+        original.block = parse_quote! {{
+            struct __RegressionIssue<'a>(&'a str);
+            impl Drop for __RegressionIssue<'_> {
+                fn drop(&mut self) {
+                    if ::std::thread::panicking() {
+                        eprintln!("regression test for {} failed", self.0);
+                    }
+                }
+            }
+            let __regression_issue_guard = __RegressionIssue(__regression_issue);
+            #block
+        }};
+        original
+            .sig
+            .inputs
+            .push(parse_quote!(__regression_issue: &str));
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the registry changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_numbers_are_found_in_links() {
+        let cases = [
+            ("https://github.com/a/b/issues/123", Some("123")),
+            ("https://tracker.example.com/browse/PROJ-42", Some("42")),
+            ("https://bugs.example.com/show_bug.cgi?id=7", Some("7")),
+            ("#9", Some("9")),
+            ("https://example.com/no-number", None),
+        ];
+
+        for (issue, expected) in cases {
+            assert_eq!(issue_number(issue), expected, "{}", issue);
+        }
+    }
+
+    #[test]
+    fn args_are_checked_against_the_test_function() {
+        let root = parse_toml(
+            "[[regression]]\nname = 'a'\nissue = '#1'\nargs = [1]\n\n\
+             [[regression]]\nname = 'b'\nissue = '#2'\nargs = [1, 2]\n",
+        )
+        .unwrap();
+        let res = resolve_cases(Span::call_site(), root, &[parse_quote!(u8)]);
+
+        match res {
+            Err(e) => assert!(
+                e.to_string()
+                    .contains("regression 2: expected 1 args but found 2"),
+                "{}",
+                e
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::util::sequence_arg;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Expr, ExprArray, FnArg, Ident, ItemFn, LitInt, Result, Token,
+};
+
+// Matches the limit used by range_cases
+const MAX_CASES: u128 = 10_000;
+
+// Keeps the number of subsets of each size well within a u128
+const MAX_ELEMENTS: usize = 64;
+
+// subset_cases([a, b, ...]) with optional `min_size = n` and `max_size = n` bounds on the size of
+// the generated subsets
+struct SubsetCases {
+    span: Span,
+    elems: Vec<Expr>,
+    min_size: usize,
+    max_size: Option<usize>,
+}
+
+impl Parse for SubsetCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let list: ExprArray = input.parse()?;
+        let mut min_size = 0;
+        let mut max_size = None;
+
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "min_size" => min_size = input.parse::<LitInt>()?.base10_parse()?,
+                "max_size" => max_size = Some(input.parse::<LitInt>()?.base10_parse()?),
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown subset_cases option: {}", other),
+                    ))
+                }
+            }
+        }
+
+        let elems: Vec<Expr> = list.elems.into_iter().collect();
+        if elems.is_empty() || elems.len() > MAX_ELEMENTS {
+            return Err(Error::new(
+                span,
+                format!(
+                    "subset_cases requires between 1 and {} elements",
+                    MAX_ELEMENTS
+                ),
+            ));
+        }
+        if min_size > max_size.unwrap_or(elems.len()) {
+            return Err(Error::new(
+                span,
+                "min_size must not be greater than max_size or the number of elements",
+            ));
+        }
+
+        Ok(Self {
+            span,
+            elems,
+            min_size,
+            max_size,
+        })
+    }
+}
+
+fn n_choose_k(n: usize, k: usize) -> u128 {
+    (0..k as u128).fold(1, |acc, i| acc * (n as u128 - i) / (i + 1))
+}
+
+// Every subset of 0..len with a size in the given range, ordered by size and then
+// lexicographically. The total is checked before anything is generated so that an oversized set
+// is reported rather than hanging the compiler.
+fn subsets(span: Span, len: usize, min_size: usize, max_size: usize) -> Result<Vec<Vec<usize>>> {
+    let max_size = max_size.min(len);
+    let total: u128 = (min_size..=max_size).map(|k| n_choose_k(len, k)).sum();
+
+    if total > MAX_CASES {
+        return Err(Error::new(
+            span,
+            format!(
+                "{} elements have {} subsets between size {} and {}: use `max_size = n` to \
+                 generate at most {} cases",
+                len, total, min_size, max_size, MAX_CASES
+            ),
+        ));
+    }
+
+    let mut subsets = Vec::with_capacity(total as usize);
+    for k in min_size..=max_size {
+        let mut subset: Vec<usize> = (0..k).collect();
+        loop {
+            subsets.push(subset.clone());
+            // Advance the rightmost index that still has room to move
+            match (0..k).rev().find(|&i| subset[i] < len - k + i) {
+                Some(i) => {
+                    subset[i] += 1;
+                    for j in i + 1..k {
+                        subset[j] = subset[j - 1] + 1;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    Ok(subsets)
+}
+
+// Each case is named after the indices of the elements in the subset, with the empty subset
+// being named "empty"
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let SubsetCases {
+        span,
+        elems,
+        min_size,
+        max_size,
+    } = parse_macro_input!(args as SubsetCases);
+    let original = parse_macro_input!(input as ItemFn);
+
+    let ty = match original.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] => (*pt.ty).clone(),
+        _ => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "subset_cases test functions must accept a single argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    let subsets = match subsets(span, elems.len(), min_size, max_size.unwrap_or(elems.len())) {
+        Ok(subsets) => subsets,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let case_attrs = subsets.iter().map(|subset| {
+        let arg = sequence_arg(subset.iter().map(|&i| &elems[i]), &ty);
+        let name = if subset.is_empty() {
+            "empty".to_string()
+        } else {
+            subset
+                .iter()
+                .fold("subset".to_string(), |acc, i| format!("{}_{}", acc, i))
+        };
+        quote! { #[simple_test_case::test_case(#arg; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsets_are_ordered_by_size() {
+        let subsets = subsets(Span::call_site(), 3, 0, 3).unwrap();
+
+        assert_eq!(
+            subsets,
+            vec![
+                vec![],
+                vec![0],
+                vec![1],
+                vec![2],
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 2],
+                vec![0, 1, 2],
+            ]
+        );
+    }
+
+    #[test]
+    fn large_sets_require_a_size_bound() {
+        assert!(subsets(Span::call_site(), 20, 0, 20).is_err());
+
+        let bounded = subsets(Span::call_site(), 20, 2, 2).unwrap();
+        assert_eq!(bounded.len(), 190);
+        assert_eq!(bounded[189], vec![18, 19]);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{case_sets::is_test_fn, fixture::shared_setup_module};
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Error, Ident, Item, ItemFn, ItemMod, Result,
+    ReturnType, Stmt,
+};
+
+// The inert attributes used to mark functions within a suite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Shared,
+    Setup,
+    Teardown,
+}
+
+// Remove the marker attribute from a function (if it has one)
+fn take_marker(attrs: &mut Vec<Attribute>) -> Result<Option<Marker>> {
+    let mut markers = Vec::new();
+    attrs.retain(|a| {
+        let marker = match a.path().get_ident().map(|i| i.to_string()).as_deref() {
+            Some("shared") => Marker::Shared,
+            Some("setup") => Marker::Setup,
+            Some("teardown") => Marker::Teardown,
+            _ => return true,
+        };
+        markers.push((marker, a.clone()));
+        false
+    });
+
+    match markers.as_slice() {
+        [] => Ok(None),
+        [(marker, _)] => Ok(Some(*marker)),
+        [_, (_, attr), ..] => Err(Error::new_spanned(
+            attr,
+            "functions can only have one of #[shared], #[setup] or #[teardown]",
+        )),
+    }
+}
+
+// Setup and teardown hooks are called without any arguments and their return value is ignored
+fn check_hook(_fn: &ItemFn) -> Result<()> {
+    let sig = &_fn.sig;
+    if sig.asyncness.is_some() || !sig.inputs.is_empty() || sig.output != ReturnType::Default {
+        return Err(Error::new_spanned(
+            sig,
+            "setup and teardown functions must be synchronous functions without arguments or a return value",
+        ));
+    }
+
+    Ok(())
+}
+
+// Setup hooks are run (in the order they are written) at the start of each test and teardown
+// hooks are run by drop guards at the end, so that they still run if the test panics. Guards are
+// dropped in reverse order so teardown hooks run in the reverse of the order they are written.
+fn hook_stmts(setup: &[Ident], teardown: &[Ident]) -> Vec<Stmt> {
+    let mut stmts: Vec<Stmt> = setup.iter().map(|f| parse_quote!(#f();)).collect();
+
+    for f in teardown {
+        let guard_ty = format_ident!("__SuiteTeardown_{}", f);
+        let guard = format_ident!("__suite_teardown_{}", f);
+        stmts.push(parse_quote! {
+            #[allow(non_camel_case_types)]
+            struct #guard_ty;
+        });
+        stmts.push(parse_quote! {
+            impl Drop for #guard_ty {
+                fn drop(&mut self) {
+                    #f();
+                }
+            }
+        });
+        stmts.push(parse_quote!(let #guard = #guard_ty;));
+    }
+
+    stmts
+}
+
+// Functions in the suite marked as #[shared] are turned into fixtures whose value is created the
+// first time that a test case asks for it and is then passed by reference to every other case,
+// so test functions in the suite receive the shared state as a `#[fixture] name: &T` argument.
+// Functions marked as #[setup] or #[teardown] are called at the start and end of every test.
+fn expand_suite(module: &mut ItemMod) -> Result<()> {
+    let items = match &mut module.content {
+        Some((_, items)) => items,
+        None => {
+            return Err(Error::new_spanned(
+                &*module,
+                "suite can only be applied to inline modules",
+            ))
+        }
+    };
+
+    let mut setup_modules = Vec::new();
+    let mut setup = Vec::new();
+    let mut teardown = Vec::new();
+    for item in items.iter_mut() {
+        if let Item::Fn(_fn) = item {
+            match take_marker(&mut _fn.attrs)? {
+                Some(Marker::Shared) => {
+                    _fn.attrs.push(parse_quote!(#[allow(dead_code)]));
+                    setup_modules.push(shared_setup_module(_fn)?);
+                }
+                Some(Marker::Setup) => {
+                    check_hook(_fn)?;
+                    setup.push(_fn.sig.ident.clone());
+                }
+                Some(Marker::Teardown) => {
+                    check_hook(_fn)?;
+                    teardown.push(_fn.sig.ident.clone());
+                }
+                None => (),
+            }
+        }
+    }
+
+    if setup_modules.is_empty() && setup.is_empty() && teardown.is_empty() {
+        return Err(Error::new(
+            module.ident.span(),
+            "suite modules must contain at least one #[shared], #[setup] or #[teardown] function",
+        ));
+    }
+
+    let hooks = hook_stmts(&setup, &teardown);
+    if !hooks.is_empty() {
+        for item in items.iter_mut() {
+            match item {
+                Item::Fn(_fn) if is_test_fn(_fn) => {
+                    let body = std::mem::take(&mut _fn.block.stmts);
+                    _fn.block.stmts = hooks.iter().cloned().chain(body).collect();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    for setup in setup_modules {
+        items.push(syn::parse2(setup)?);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut module = parse_macro_input!(input as ItemMod);
+
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return TokenStream::from(
+            Error::new_spanned(args, "suite does not accept any arguments").into_compile_error(),
+        );
+    }
+
+    match expand_suite(&mut module) {
+        Ok(()) => TokenStream::from(quote! { #module }),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_functions_become_fixtures() {
+        let mut module: ItemMod = parse_quote! {
+            mod engine_tests {
+                #[shared]
+                fn engine() -> Engine {
+                    Engine::new()
+                }
+
+                #[test]
+                fn not_shared() {}
+            }
+        };
+        expand_suite(&mut module).unwrap();
+        let items = &module.content.unwrap().1;
+
+        assert_eq!(items.len(), 3);
+        match (&items[0], &items[2]) {
+            (Item::Fn(f), Item::Mod(m)) => {
+                assert_eq!(f.attrs, vec![parse_quote!(#[allow(dead_code)])]);
+                assert_eq!(m.ident, "engine");
+            }
+            _ => panic!("expected the shared function and its setup module"),
+        }
+    }
+
+    #[test]
+    fn hooks_are_added_to_test_functions() {
+        let mut module: ItemMod = parse_quote! {
+            mod engine_tests {
+                #[setup]
+                fn start() {}
+
+                #[teardown]
+                fn stop() {}
+
+                #[test]
+                fn t() {
+                    assert!(true);
+                }
+
+                fn helper() {}
+            }
+        };
+        expand_suite(&mut module).unwrap();
+        let items = &module.content.unwrap().1;
+
+        match (&items[2], &items[3]) {
+            (Item::Fn(t), Item::Fn(helper)) => {
+                let first: Stmt = parse_quote!(start(););
+                assert_eq!(t.block.stmts.len(), 5);
+                assert_eq!(t.block.stmts[0], first);
+                assert!(helper.block.stmts.is_empty());
+            }
+            _ => panic!("expected functions"),
+        }
+    }
+
+    #[test]
+    fn suites_require_shared_state() {
+        let mut module: ItemMod = parse_quote! {
+            mod engine_tests {
+                #[test]
+                fn t() {}
+            }
+        };
+
+        assert!(expand_suite(&mut module).is_err());
+    }
+}
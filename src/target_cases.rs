@@ -0,0 +1,204 @@
+use crate::{
+    boundary_cases::{extract_explicit_cases, ExplicitCase},
+    util::wrong_arg_count,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Attribute, Error, Ident, ItemFn, Result, Token,
+};
+
+// Values of target_os that can be given directly as a target
+const TARGET_OS: &[&str] = &[
+    "android",
+    "dragonfly",
+    "freebsd",
+    "fuchsia",
+    "haiku",
+    "illumos",
+    "ios",
+    "linux",
+    "macos",
+    "netbsd",
+    "openbsd",
+    "redox",
+    "solaris",
+    "wasi",
+];
+
+// target_cases(unix, windows, ...)
+struct TargetCases {
+    targets: Vec<(String, Attribute)>,
+}
+
+impl Parse for TargetCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut targets: Vec<(String, Attribute)> = Vec::new();
+
+        for ident in Punctuated::<Ident, Token![,]>::parse_terminated(input)? {
+            let target = ident.to_string();
+            let cfg: Attribute = match target.as_str() {
+                "unix" => parse_quote!(#[cfg(unix)]),
+                "windows" => parse_quote!(#[cfg(windows)]),
+                "wasm" => parse_quote!(#[cfg(target_family = "wasm")]),
+                os if TARGET_OS.contains(&os) => parse_quote!(#[cfg(target_os = #os)]),
+                other => {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!("unknown target: {}", other),
+                    ))
+                }
+            };
+            if targets.iter().any(|(t, _)| *t == target) {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("duplicate target: {}", target),
+                ));
+            }
+            targets.push((target, cfg));
+        }
+
+        if targets.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "at least one target must be specified",
+            ));
+        }
+
+        Ok(Self { targets })
+    }
+}
+
+// The cfg attribute for the target is given as the first per-case option, followed by any options
+// that were given for the explicit case.
+fn with_cfg(cfg: &Attribute, rest: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let opts: proc_macro2::TokenStream = rest.clone().into_iter().skip(1).collect();
+    if opts.is_empty() {
+        quote! { ; #cfg }
+    } else {
+        quote! { ; #cfg, #opts }
+    }
+}
+
+// Each explicit case is crossed with each of the targets, with the name of the target being passed
+// to the test function as an additional trailing argument. Without any explicit cases a single
+// case is generated for each target.
+fn render_cases(
+    span: Span,
+    targets: &[(String, Attribute)],
+    n_params: usize,
+    explicit: Vec<ExplicitCase>,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    if explicit.is_empty() {
+        if n_params != 0 {
+            return Err(Error::new(
+                span,
+                "test_case attributes providing the arguments must be given beneath target_cases",
+            ));
+        }
+        return Ok(targets
+            .iter()
+            .map(|(target, cfg)| quote! { #[simple_test_case::test_case(#target; #target; #cfg)] })
+            .collect());
+    }
+
+    let mut rendered = Vec::new();
+    for case in explicit.iter() {
+        if case.args.len() != n_params {
+            return Err(wrong_arg_count(case.name.span(), &case.args, n_params));
+        }
+        for (target, cfg) in targets.iter() {
+            let args = case.args.iter();
+            let name = format!("{} {}", case.name.value(), target);
+            let rest = with_cfg(cfg, &case.rest);
+            rendered
+                .push(quote! { #[simple_test_case::test_case(#(#args,)* #target; #name #rest)] });
+        }
+    }
+
+    Ok(rendered)
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let span = Span::call_site();
+    let TargetCases { targets } = parse_macro_input!(args as TargetCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    let n_params = match original.sig.inputs.len() {
+        0 => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "target_cases test functions must take the name of the target as their final argument",
+                )
+                .into_compile_error(),
+            )
+        }
+        n => n - 1,
+    };
+
+    let res = extract_explicit_cases(&mut original.attrs)
+        .and_then(|explicit| render_cases(span, &targets, n_params, explicit));
+
+    match res {
+        Ok(case_attrs) => TokenStream::from(quote! {
+            #(#case_attrs)*
+            #original
+        }),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(tokens: proc_macro2::TokenStream) -> Vec<(String, Attribute)> {
+        syn::parse2::<TargetCases>(tokens).unwrap().targets
+    }
+
+    #[test]
+    fn targets_are_mapped_to_cfg_attributes() {
+        let targets = targets(quote!(unix, linux, wasm));
+        let cfgs: Vec<_> = targets
+            .iter()
+            .map(|(_, cfg)| quote!(#cfg).to_string())
+            .collect();
+
+        assert_eq!(
+            cfgs,
+            vec![
+                quote!(#[cfg(unix)]).to_string(),
+                quote!(#[cfg(target_os = "linux")]).to_string(),
+                quote!(#[cfg(target_family = "wasm")]).to_string(),
+            ]
+        );
+        assert!(syn::parse2::<TargetCases>(quote!(plan9)).is_err());
+        assert!(syn::parse2::<TargetCases>(quote!(unix, unix)).is_err());
+    }
+
+    #[test]
+    fn cases_are_crossed_with_targets() {
+        let cases: Vec<_> = render_cases(
+            Span::call_site(),
+            &targets(quote!(unix, windows)),
+            1,
+            vec![parse_quote!(1; "one"), parse_quote!(2; "two"; ignore)],
+        )
+        .unwrap()
+        .into_iter()
+        .map(|ts| ts.to_string())
+        .collect();
+
+        assert_eq!(cases.len(), 4);
+        assert_eq!(
+            cases[3],
+            quote!(#[simple_test_case::test_case(2, "windows"; "two windows"; #[cfg(windows)], ignore)])
+                .to_string()
+        );
+    }
+}
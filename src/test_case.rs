@@ -1,31 +1,135 @@
-use crate::util::slugify_path;
+use crate::{
+    fixture::fixture_binding,
+    manifest::{fixture_paths, write_manifest, ManifestCase},
+    once_setup::{extract_once_setups, once_setup_parts},
+    util::{
+        case_binding, fnv1a, slug_ident, slugify_label, slugify_path, snake_case, wrong_arg_count,
+    },
+};
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input, parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Error, Expr, FnArg, ItemFn, LitStr, PatType, Path, Result, Stmt, Token,
+    Attribute, Error, Expr, FnArg, ItemFn, LitStr, Meta, PatType, Path, Result, ReturnType, Stmt,
+    Token, Type,
 };
 
 // A really simple test case specification of the form: test_case(exprs, ...; "name for test case")
-// There is no defaulting of the case name or additional properties that can be set.
+// There is no defaulting of the case name. Additional attributes for an individual case and the
+// module it should be placed in can be provided after the name:
+//   test_case(exprs, ...; "name"; #[attr], ignore, module = nested::module, tags = [slow])
 struct TestCase {
     args: Punctuated<Expr, Token![,]>,
     name: LitStr,
+    attrs: Vec<Attribute>,
+    module: Vec<Ident>,
     span: Span,
 }
 
+enum CaseOption {
+    Attr(Box<Attribute>),
+    Module(Vec<Ident>),
+    Tags(Vec<Ident>),
+}
+
 impl Parse for TestCase {
     fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
         let span = input.span();
         let args = Punctuated::parse_separated_nonempty_with(input, Expr::parse)?;
         let _: Token![;] = input.parse()?;
         let name: LitStr = input.parse()?;
+        check_name(&args, &name)?;
+        let mut attrs = Vec::new();
+        let mut module = Vec::new();
+        let mut tags = Vec::new();
+
+        if input.peek(Token![;]) {
+            let _: Token![;] = input.parse()?;
+            while !input.is_empty() {
+                if input.peek(Token![#]) {
+                    attrs.extend(input.call(Attribute::parse_outer)?);
+                } else {
+                    match parse_case_option(input)? {
+                        CaseOption::Attr(attr) => attrs.push(*attr),
+                        CaseOption::Module(path) => module = path,
+                        CaseOption::Tags(t) => tags.extend(t),
+                    }
+                }
+
+                if !input.is_empty() {
+                    let _: Token![,] = input.parse()?;
+                }
+            }
+        }
+
+        // Tagged cases are placed in a nested `tag_<name>` module for each of their tags (after
+        // any explicit module) so that they can be selected using test name filters. Tags are
+        // sorted so that the path of a case does not depend on the order its tags were given in.
+        tags.sort_by_key(|t: &Ident| t.to_string());
+        tags.dedup_by_key(|t| t.to_string());
+        module.extend(tags.iter().map(|t| format_ident!("tag_{}", t)));
 
-        Ok(Self { args, name, span })
+        Ok(Self {
+            args,
+            name,
+            attrs,
+            module,
+            span,
+        })
+    }
+}
+
+// Shorthand options for common per-case attributes and the module to place the case in
+fn parse_case_option(input: ParseStream<'_>) -> Result<CaseOption> {
+    let opt: Ident = input.parse()?;
+
+    match opt.to_string().as_str() {
+        "ignore" => Ok(CaseOption::Attr(Box::new(parse_quote!(#[ignore])))),
+        "miri_only" => Ok(CaseOption::Attr(Box::new(parse_quote!(#[cfg(miri)])))),
+        "skip_miri" => Ok(CaseOption::Attr(Box::new(
+            parse_quote!(#[cfg_attr(miri, ignore)]),
+        ))),
+        "tags" => {
+            let _: Token![=] = input.parse()?;
+            let content;
+            syn::bracketed!(content in input);
+            let tags = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+            Ok(CaseOption::Tags(tags.into_iter().collect()))
+        }
+        #[cfg(feature = "serial")]
+        "serial" => {
+            if !input.peek(syn::token::Paren) {
+                return Ok(CaseOption::Attr(Box::new(
+                    parse_quote!(#[::serial_test::serial]),
+                )));
+            }
+            let content;
+            syn::parenthesized!(content in input);
+            let keys = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+            Ok(CaseOption::Attr(Box::new(
+                parse_quote!(#[::serial_test::serial(#keys)]),
+            )))
+        }
+        #[cfg(not(feature = "serial"))]
+        "serial" => Err(Error::new(
+            opt.span(),
+            "serial test cases require the `serial` feature of simple_test_case to be enabled",
+        )),
+        "module" => {
+            let _: Token![=] = input.parse()?;
+            let path: Path = input.parse()?;
+            Ok(CaseOption::Module(
+                path.segments.into_iter().map(|s| s.ident).collect(),
+            ))
+        }
+        other => Err(Error::new(
+            opt.span(),
+            format!("unknown test case option: {}", other),
+        )),
     }
 }
 
@@ -47,19 +151,336 @@ pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
         original.attrs.swap_remove(i);
     }
 
+    if let Err(e) = check_not_generic(&original) {
+        return TokenStream::from(e.into_compile_error());
+    }
+
+    if let Err(e) = check_unique_names(&cases) {
+        return TokenStream::from(e.into_compile_error());
+    }
+
+    // Additional items for the top of the generated module
+    let mut header = Vec::new();
+
+    // once_setup expressions are guarded by a Once shared by every case and are run before any of
+    // the arguments of the case are bound
+    let once_setups = match extract_once_setups(&mut original.attrs) {
+        Ok(exprs) => exprs,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+    header.extend(missing_test_attr_warning(&original, &cases));
+
+    let mut prelude = Vec::new();
+    for (i, expr) in once_setups.iter().enumerate() {
+        let (item, stmt) = once_setup_parts(&format_ident!("__ONCE_SETUP_{}", i), expr);
+        header.push(item);
+        prelude.push(stmt);
+    }
+
+    if cfg!(feature = "shard") {
+        let shard = match std::env::var(SHARD_VAR).ok().map(|s| parse_shard(&s)) {
+            Some(Ok(shard)) => Some(shard),
+            Some(Err(e)) => {
+                let msg = format!("invalid {}: {}", SHARD_VAR, e);
+                return TokenStream::from(Error::new(Span::call_site(), msg).into_compile_error());
+            }
+            None => None,
+        };
+        if let Some(shard) = shard {
+            cases.retain(|c| in_shard(&case_path(&module, c), shard));
+        }
+        // Ensure that we are recompiled if the shard changes
+        header.push(quote! { const _: Option<&str> = option_env!(#SHARD_VAR); });
+    }
+
+    if cfg!(feature = "manifest") {
+        let manifest_cases: Vec<_> = cases
+            .iter()
+            .map(|c| ManifestCase {
+                name: c.name.value(),
+                path: case_path(&module, c),
+                args: c
+                    .args
+                    .iter()
+                    .map(|a| a.to_token_stream().to_string())
+                    .collect(),
+                attrs: c
+                    .attrs
+                    .iter()
+                    .map(|a| a.to_token_stream().to_string())
+                    .collect(),
+                fixtures: fixture_paths(c.args.to_token_stream()),
+            })
+            .collect();
+        if let Err(e) = write_manifest(&module.to_string(), &manifest_cases) {
+            return TokenStream::from(Error::new(Span::call_site(), e).into_compile_error());
+        }
+    }
+
+    if cfg!(feature = "case_names") {
+        let names = cases.iter().map(relative_case_path);
+        header.push(quote! {
+            /// The paths of the generated test cases relative to this module
+            #[allow(dead_code)]
+            pub const CASE_NAMES: &[&str] = &[#(#names),*];
+        });
+    }
+
+    let module_allow = module_allow(&module);
+    let module_attrs = module_attrs(&original.attrs);
     let resolved_cases: Vec<_> = cases
         .into_iter()
-        .map(|c| resolve_test_case(original.clone(), c))
+        .map(|mut c| {
+            let path = std::mem::take(&mut c.module);
+            (path, resolve_test_case(original.clone(), &prelude, c))
+        })
         .collect();
+    let rendered = render_module(&module, header, resolved_cases);
 
     TokenStream::from(quote! {
+        #module_allow
+        #(#module_attrs)*
+        #rendered
+    })
+}
+
+// The module holding the cases keeps the name of the test function so that the paths of the cases
+// are predictable, which means that a function such as `TestHTTP2Frames` gives a module that would
+// trigger non_snake_case. Any lint levels set on the test function come after this so they still
+// take precedence.
+fn module_allow(module: &Ident) -> Option<proc_macro2::TokenStream> {
+    let name = module.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+
+    match snake_case(name) == name {
+        true => None,
+        false => Some(quote! { #[allow(non_snake_case)] }),
+    }
+}
+
+// Lint levels set on the test function also apply to the items we generate alongside the cases
+// (such as once_setup statics) by repeating them on the generated module. The cases themselves
+// keep the original attributes, which covers the argument bindings added to each of them. `expect`
+// is left on the cases only as each case fulfils it separately: on the module it would never be.
+// The same goes for `cfg` so that the module (and its glob import) is removed along with the cases.
+// Lint levels and cfgs inside of a `cfg_attr` are repeated under the same predicate, dropping any
+// other attributes that it applies.
+fn module_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    const MODULE_ATTRS: [&str; 5] = ["allow", "warn", "deny", "forbid", "cfg"];
+    let is_module_attr = |path: &Path| MODULE_ATTRS.iter().any(|name| path.is_ident(name));
+
+    attrs
+        .iter()
+        .filter_map(|a| {
+            if is_module_attr(a.path()) {
+                return Some(a.clone());
+            }
+            if !a.path().is_ident("cfg_attr") {
+                return None;
+            }
+
+            let args = a
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()?;
+            let mut args = args.into_iter();
+            let pred = args.next()?;
+            let forwarded: Vec<_> = args.filter(|m| is_module_attr(m.path())).collect();
+
+            match forwarded.is_empty() {
+                true => None,
+                false => Some(parse_quote! { #[cfg_attr(#pred, #(#forwarded),*)] }),
+            }
+        })
+        .collect()
+}
+
+// The environment variable used to select the shard of cases to generate, in the form "i/n"
+const SHARD_VAR: &str = "TEST_SHARD";
+
+// Shards are numbered from 1 so that "2/8" reads as the second of eight shards
+fn parse_shard(s: &str) -> std::result::Result<(u32, u32), String> {
+    let (i, n) = s
+        .trim()
+        .split_once('/')
+        .ok_or_else(|| format!("expected a shard of the form i/n but found '{}'", s))?;
+    let parse = |x: &str| {
+        x.trim()
+            .parse::<u32>()
+            .map_err(|e| format!("invalid shard '{}': {}", s, e))
+    };
+    let (i, n) = (parse(i)?, parse(n)?);
+    if i == 0 || i > n {
+        return Err(format!("shard {} is not between 1 and {}", i, n));
+    }
+
+    Ok((i, n))
+}
+
+// The path of a case within the generated module, used to assign it to a shard
+fn case_path(module: &Ident, case: &TestCase) -> String {
+    format!("{}::{}", module, relative_case_path(case))
+}
+
+// The path of a case relative to the generated module
+fn relative_case_path(case: &TestCase) -> String {
+    let mut path: Vec<_> = case.module.iter().map(|m| m.to_string()).collect();
+    path.push(slugify_path(&case.name.value()));
+
+    path.join("::")
+}
+
+// Attributes from other crates are recognised by name only: anything ending in `test` (`test`,
+// `tokio::test`, `wasm_bindgen_test`...) along with `bench` and kani's `proof`.
+fn is_test_attr(attr: &Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|seg| {
+        let name = seg.ident.to_string();
+        name.ends_with("test") || name == "bench" || name == "proof"
+    })
+}
+
+// A `#[test]` placed above the test_case attributes is expanded first, which leaves us with a
+// function that has no attributes at all once the cases have been removed. rustc reports that
+// "functions used as tests can not have any arguments" which doesn't point at the real problem, so
+// the ordering is reported here as well. Without a test attribute at all the generated functions
+// would never be run, so that is reported too unless every case provides its own.
+fn missing_test_attr(_fn: &ItemFn, cases: &[TestCase]) -> Option<&'static str> {
+    if _fn.attrs.iter().any(is_test_attr) || cases.iter().all(|c| c.attrs.iter().any(is_test_attr))
+    {
+        return None;
+    }
+
+    match _fn.attrs.is_empty() {
+        true => Some("no test attribute found below the test_case attributes: move #[test] below all test_case attributes"),
+        false => Some("no test attribute found below the test_case attributes: add #[test] (or an alternative such as #[tokio::test]) below them so that the test cases are run"),
+    }
+}
+
+// Test attributes are recognised by name so a custom harness may use one that we don't know
+// about, which means that a missing test attribute is only a warning. Proc macros can't emit
+// warnings on stable, so the generated module uses a deprecated item carrying the message instead.
+fn missing_test_attr_warning(_fn: &ItemFn, cases: &[TestCase]) -> Option<proc_macro2::TokenStream> {
+    let msg = missing_test_attr(_fn, cases)?;
+
+    Some(quote_spanned! { _fn.sig.ident.span()=>
+        const _: () = {
+            #[deprecated(note = #msg)]
+            struct MissingTestAttribute;
+            let _ = MissingTestAttribute;
+        };
+    })
+}
+
+// Each case binds its arguments using the types of the parameters inside of a new function that
+// has no generics of its own, so generic parameters (including `impl Trait` arguments) would only
+// surface as unresolved names inside of the generated module.
+fn check_not_generic(_fn: &ItemFn) -> Result<()> {
+    let generics = &_fn.sig.generics;
+    if !generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            generics,
+            "test_case test functions can not be generic: give each argument an explicit type (use type_cases to run a test for several types)",
+        ));
+    }
+
+    let impl_trait = _fn.sig.inputs.iter().find_map(|fnarg| match fnarg {
+        FnArg::Typed(pt) if matches!(*pt.ty, Type::ImplTrait(_)) => Some(&pt.ty),
+        _ => None,
+    });
+    match impl_trait {
+        Some(ty) => Err(Error::new_spanned(
+            ty,
+            "impl Trait arguments are not supported for test cases: give the argument an explicit type",
+        )),
+        None => Ok(()),
+    }
+}
+
+// Case names that differ only in punctuation or case (such as "a b" and "A-B") produce the same
+// test function, so these are reported here against both cases rather than leaving rustc to report
+// a duplicate definition inside of the generated module.
+fn check_unique_names(cases: &[TestCase]) -> Result<()> {
+    let paths: Vec<_> = cases.iter().map(relative_case_path).collect();
+    let mut res: Result<()> = Ok(());
+
+    for (i, (case, path)) in cases.iter().zip(paths.iter()).enumerate() {
+        if let Some(j) = paths[..i].iter().position(|p| p == path) {
+            let first = &cases[j].name;
+            let mut e = Error::new(
+                case.name.span(),
+                format!(
+                    "test case name {:?} collides with {:?}: both are named `{}`",
+                    case.name.value(),
+                    first.value(),
+                    path
+                ),
+            );
+            e.combine(Error::new(
+                first.span(),
+                format!("`{}` is first defined here", path),
+            ));
+            match &mut res {
+                Ok(_) => res = Err(e),
+                Err(errs) => errs.combine(e),
+            }
+        }
+    }
+
+    res
+}
+
+// Cases are assigned to shards by a stable hash of their path so that the assignment does not
+// depend on the order the cases are written in or on which other cases exist
+fn in_shard(path: &str, (i, n): (u32, u32)) -> bool {
+    fnv1a(path) % n == i - 1
+}
+
+// Rendered test cases along with the path of the (nested) module they should be placed in
+type ModuleCases = Vec<(Vec<Ident>, proc_macro2::TokenStream)>;
+
+// Cases are written out inside of a module named after the original test function, with any cases
+// that specified a module being placed in the corresponding nested modules.
+fn render_module(
+    module: &Ident,
+    header: Vec<proc_macro2::TokenStream>,
+    cases: ModuleCases,
+) -> proc_macro2::TokenStream {
+    let mut items = Vec::new();
+    let mut children: Vec<(Ident, ModuleCases)> = Vec::new();
+
+    for (mut path, tokens) in cases.into_iter() {
+        if path.is_empty() {
+            items.push(tokens);
+            continue;
+        }
+
+        let child = path.remove(0);
+        match children.iter_mut().find(|(ident, _)| *ident == child) {
+            Some((_, child_cases)) => child_cases.push((path, tokens)),
+            None => children.push((child, vec![(path, tokens)])),
+        }
+    }
+
+    let nested = children
+        .into_iter()
+        .map(|(ident, child_cases)| render_module(&ident, Vec::new(), child_cases));
+
+    // kani proof harnesses are gated on cfg(kani), which is unknown to rustc outside of kani
+    let kani_allow = match cfg!(feature = "kani") {
+        true => Some(quote! { #[allow(unexpected_cfgs)] }),
+        false => None,
+    };
+
+    quote! {
+        #kani_allow
         mod #module {
             #[allow(unused_imports)]
             use super::*;
 
-            #(#resolved_cases)*
+            #(#header)*
+            #(#items)*
+            #(#nested)*
         }
-    })
+    }
 }
 
 // Glob up any other `test_case` attribute macros underneath us and parse them as additional
@@ -86,33 +507,53 @@ fn extract_other_cases(cases: &mut Vec<TestCase>, attrs: &[Attribute]) -> Result
 // names we've been given to generate the new test case names. Any existing attrs _other_ than ours
 // are preserved and the original function is updated for each case to bind the function parameters
 // explicitly at the top.
-fn resolve_test_case(mut _fn: ItemFn, case: TestCase) -> proc_macro2::TokenStream {
-    let TestCase { span, args, name } = case;
+fn resolve_test_case(
+    mut _fn: ItemFn,
+    prelude: &[Stmt],
+    case: TestCase,
+) -> proc_macro2::TokenStream {
+    let TestCase {
+        span,
+        args,
+        name,
+        attrs,
+        ..
+    } = case;
     let inputs = _fn.sig.inputs.clone();
 
-    // Explicitly bail on mismatched number of arguments rather than silently dropping from the
-    // shorter side of the `zip` used for generating the variable bindings.
-    if args.len() != inputs.len() {
-        return Error::new(span, "wrong number of arguments").into_compile_error();
+    // Explicitly bail on the wrong number of arguments rather than silently dropping them from
+    // the `zip` used for generating the variable bindings. Arguments marked as #[temp_dir] or
+    // #[fixture] are never given a value by the case.
+    let n_params = inputs
+        .iter()
+        .filter(|fnarg| !is_temp_dir(fnarg) && !is_fixture(fnarg))
+        .count();
+    if args.len() != n_params {
+        return wrong_arg_count(span, &args, n_params).into_compile_error();
     }
 
     // Strip the original function arguments so that `_fn` will be valid as a test function
     _fn.sig.inputs.clear();
 
-    let res: Result<Vec<Stmt>> = inputs
+    let mut args = args.into_iter();
+    let res: Result<Vec<Vec<Stmt>>> = inputs
         .iter()
-        .zip(args)
-        .map(|(fnarg, val)| match fnarg {
+        .enumerate()
+        .map(|(ix, fnarg)| match fnarg {
+            FnArg::Typed(pt) if is_temp_dir(fnarg) => temp_dir_binding(ix, pt),
+            FnArg::Typed(pt) if is_fixture(fnarg) => {
+                Ok(vec![syn::parse2(fixture_binding(&pt.pat, &pt.ty)?)?])
+            }
             FnArg::Typed(pt) => {
                 let PatType { attrs, pat, ty, .. } = pt;
                 if !attrs.is_empty() {
-                    Err(Error::new_spanned(
+                    return Err(Error::new_spanned(
                         fnarg,
-                        "attributes on function arguments are not supported",
-                    ))
-                } else {
-                    syn::parse2(quote! { let #pat: #ty = #val; })
+                        "attributes on function arguments other than #[temp_dir] and #[fixture] are not supported",
+                    ));
                 }
+                let val = args.next().expect("argument count checked above");
+                Ok(vec![syn::parse2(case_binding(pat, ty, &val))?])
             }
 
             FnArg::Receiver(_) => Err(Error::new_spanned(
@@ -124,14 +565,31 @@ fn resolve_test_case(mut _fn: ItemFn, case: TestCase) -> proc_macro2::TokenStrea
 
     match res {
         // Add variable bindings (in place of function parameters) to the top of the function body
-        // and set the name of this test case to be the one we were given
-        Ok(mut stmts) => {
-            let as_written = _fn.block.stmts.clone();
-            stmts.extend(as_written);
-            _fn.block.stmts = stmts;
+        // and set the name of this test case to be the one we were given. The original statements
+        // (and the braces of the body) are left in place rather than being re-parsed so that their
+        // spans are untouched: panic locations, coverage and line!() all refer to the user's
+        // source.
+        Ok(bindings) => {
+            let mut stmts = prelude.to_vec();
+            stmts.extend(bindings.into_iter().flatten());
             _fn.sig.ident = slugify_ident(name);
+            _fn.block.stmts.splice(0..0, stmts);
+            let proof = match cfg!(feature = "kani") {
+                true => kani_proof(&_fn),
+                false => None,
+            };
+            if cfg!(feature = "tracing") {
+                _fn.block.stmts = with_case_tracing(&_fn, _fn.block.stmts.clone());
+            }
+            if cfg!(feature = "insta") {
+                _fn.block.stmts = with_snapshot_suffix(&_fn, _fn.block.stmts.clone());
+            }
+            _fn.attrs.extend(attrs);
 
-            _fn.into_token_stream()
+            quote! {
+                #_fn
+                #proof
+            }
         }
 
         // Something was invalid (in terms of what we support) about the original function args so
@@ -140,8 +598,158 @@ fn resolve_test_case(mut _fn: ItemFn, case: TestCase) -> proc_macro2::TokenStrea
     }
 }
 
+fn is_temp_dir(fnarg: &FnArg) -> bool {
+    let temp_dir_attr: Path = parse_quote!(temp_dir);
+
+    match fnarg {
+        FnArg::Typed(pt) => matches!(pt.attrs.as_slice(), [a] if a.path() == &temp_dir_attr),
+        FnArg::Receiver(_) => false,
+    }
+}
+
+fn is_fixture(fnarg: &FnArg) -> bool {
+    let fixture_attr: Path = parse_quote!(fixture);
+
+    match fnarg {
+        FnArg::Typed(pt) => matches!(pt.attrs.as_slice(), [a] if a.path() == &fixture_attr),
+        FnArg::Receiver(_) => false,
+    }
+}
+
+// Arguments marked with #[temp_dir] are given the path of a new temporary directory for each case,
+// with the TempDir guard being held until the end of the test so that the directory is removed
+// once the test completes. The argument can be either a &Path or a PathBuf.
+fn temp_dir_binding(ix: usize, pt: &PatType) -> Result<Vec<Stmt>> {
+    if !cfg!(feature = "temp_dir") {
+        return Err(Error::new_spanned(
+            &pt.attrs[0],
+            "#[temp_dir] arguments require the `temp_dir` feature of simple_test_case to be enabled",
+        ));
+    }
+
+    let PatType { pat, ty, .. } = pt;
+    let guard = format_ident!("__temp_dir_guard_{}", ix);
+    let owned = matches!(&**ty, syn::Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "PathBuf"));
+    let path = match owned {
+        true => quote! { #guard.path().to_path_buf() },
+        false => quote! { #guard.path() },
+    };
+
+    Ok(vec![
+        parse_quote! {
+            let #guard = ::tempfile::TempDir::new().unwrap_or_else(|e| {
+                panic!("unable to create a temporary directory: {}", e)
+            });
+        },
+        parse_quote! {
+            #[allow(unused_variables)]
+            let #pat: #ty = #path;
+        },
+    ])
+}
+
+// Run the body of the test case with the insta snapshot suffix set to the name of the case so that
+// snapshots taken in each case are stored separately.
+fn with_snapshot_suffix(_fn: &ItemFn, stmts: Vec<Stmt>) -> Vec<Stmt> {
+    let ident = _fn.sig.ident.to_string();
+    let suffix = ident.strip_prefix("r#").unwrap_or(&ident);
+    let bind = match _fn.sig.asyncness {
+        Some(_) => quote! { __insta_settings.bind_async(async move { #(#stmts)* }).await },
+        None => quote! { __insta_settings.bind(|| { #(#stmts)* }) },
+    };
+
+    parse_quote! {
+        let mut __insta_settings = ::insta::Settings::clone_current();
+        __insta_settings.set_snapshot_suffix(#suffix);
+        #bind
+    }
+}
+
+// Run the body of the test case with a tracing subscriber writing to the libtest output capture
+// so that logs emitted by the case are only shown if it fails. Everything is recorded inside of a
+// span named after the case so that interleaved output from shared code can be attributed to it.
+fn with_case_tracing(_fn: &ItemFn, stmts: Vec<Stmt>) -> Vec<Stmt> {
+    let ident = _fn.sig.ident.to_string();
+    let name = ident.strip_prefix("r#").unwrap_or(&ident);
+    let run = match _fn.sig.asyncness {
+        Some(_) => quote! {
+            ::tracing::instrument::WithSubscriber::with_subscriber(
+                ::tracing::Instrument::instrument(async move { #(#stmts)* }, __tracing_span),
+                __tracing_dispatch,
+            )
+            .await
+        },
+        None => quote! {
+            ::tracing::dispatcher::with_default(&__tracing_dispatch, || {
+                __tracing_span.in_scope(|| { #(#stmts)* })
+            })
+        },
+    };
+
+    // The span has to be created with the subscriber as the default, otherwise it is disabled
+    parse_quote! {
+        let __tracing_dispatch = ::tracing::Dispatch::new(
+            ::tracing_subscriber::fmt()
+                .with_test_writer()
+                .with_max_level(::tracing::Level::TRACE)
+                .finish(),
+        );
+        let __tracing_span = ::tracing::dispatcher::with_default(&__tracing_dispatch, || {
+            ::tracing::info_span!("case", name = #name)
+        });
+        #run
+    }
+}
+
+// A kani proof harness running the same concrete inputs as the test case, so that case tables can
+// be reused as inputs for verification. Harnesses must be synchronous functions returning (), so
+// no harness is generated for async tests or those returning a Result. The test attributes of the
+// original function are dropped while anything else (such as kani::unwind) is kept.
+fn kani_proof(_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    if _fn.sig.asyncness.is_some() || _fn.sig.output != ReturnType::Default {
+        return None;
+    }
+
+    let mut proof = _fn.clone();
+    let ident = proof.sig.ident.to_string();
+    proof.sig.ident = format_ident!("{}_proof", ident.strip_prefix("r#").unwrap_or(&ident));
+    proof.attrs.retain(|a| {
+        let last = a.path().segments.last().map(|s| s.ident.to_string());
+        !matches!(last.as_deref(), Some("test" | "ignore" | "should_panic"))
+    });
+
+    Some(quote! {
+        #[cfg(kani)]
+        #[kani::proof]
+        #proof
+    })
+}
+
+// Names without any ASCII letters or digits slugify to nothing but underscores (or to nothing at
+// all) which either isn't a valid identifier or gives a test name that is impossible to filter on,
+// so a name based on the case arguments is suggested instead.
+fn check_name(args: &Punctuated<Expr, Token![,]>, name: &LitStr) -> syn::parse::Result<()> {
+    if name.value().chars().any(|c| c.is_ascii_alphanumeric()) {
+        return Ok(());
+    }
+
+    let mut suggestion = slugify_label(&quote!(#args).to_string()).replace('_', " ");
+    if suggestion.is_empty() {
+        suggestion = "case".to_string();
+    }
+
+    Err(Error::new(
+        name.span(),
+        format!(
+            "test case name {:?} does not contain any letters or digits to name the test with: try a name such as {:?}",
+            name.value(),
+            suggestion
+        ),
+    ))
+}
+
 fn slugify_ident(name: LitStr) -> Ident {
-    Ident::new(&slugify_path(&name.value()), name.span())
+    slug_ident(&slugify_path(&name.value()), name.span())
 }
 
 #[cfg(test)]
@@ -149,6 +757,319 @@ mod tests {
     use super::*;
     use syn::{parse_quote, ExprLit, Lit};
 
+    #[test]
+    fn snapshot_suffix_is_the_case_name() {
+        let _fn: ItemFn = parse_quote! {
+            fn r#type() {
+                assert_snapshot!(render());
+            }
+        };
+        let stmts = with_snapshot_suffix(&_fn, _fn.block.stmts.clone());
+        let expected: Stmt = parse_quote!(__insta_settings.set_snapshot_suffix("type"););
+
+        assert_eq!(stmts.len(), 3);
+        assert_eq!(stmts[1], expected);
+    }
+
+    #[test]
+    fn tracing_span_is_named_after_the_case() {
+        let _fn: ItemFn = parse_quote! {
+            async fn r#type() {
+                tracing::info!("running");
+            }
+        };
+        let stmts = with_case_tracing(&_fn, _fn.block.stmts.clone());
+        let expected: Stmt = parse_quote!(
+            let __tracing_span = ::tracing::dispatcher::with_default(&__tracing_dispatch, || {
+                ::tracing::info_span!("case", name = "type")
+            });
+        );
+
+        assert_eq!(stmts.len(), 3);
+        assert_eq!(stmts[1], expected);
+    }
+
+    #[test]
+    fn kani_proofs_drop_test_attributes() {
+        let _fn: ItemFn = parse_quote! {
+            #[test]
+            #[should_panic]
+            #[kani::unwind(4)]
+            fn r#type() {
+                let x: u8 = 1;
+                assert_eq!(x, 2);
+            }
+        };
+        let expected = quote! {
+            #[cfg(kani)]
+            #[kani::proof]
+            #[kani::unwind(4)]
+            fn type_proof() {
+                let x: u8 = 1;
+                assert_eq!(x, 2);
+            }
+        };
+
+        assert_eq!(kani_proof(&_fn).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn kani_proofs_are_not_generated_for_async_tests() {
+        let _fn: ItemFn = parse_quote! {
+            async fn case() {}
+        };
+
+        assert!(kani_proof(&_fn).is_none());
+    }
+
+    #[test]
+    fn shards_are_parsed() {
+        assert_eq!(parse_shard("2/8"), Ok((2, 8)));
+        assert_eq!(parse_shard(" 1 / 1 "), Ok((1, 1)));
+
+        for invalid in ["0/8", "9/8", "2", "a/8", "2/-8"] {
+            assert!(parse_shard(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn every_case_is_in_exactly_one_shard() {
+        let n = 4;
+
+        for i in 0..100 {
+            let path = format!("example::case_{}", i);
+            let shards: Vec<u32> = (1..=n).filter(|&s| in_shard(&path, (s, n))).collect();
+
+            assert_eq!(shards.len(), 1, "{}", path);
+        }
+    }
+
+    #[test]
+    fn temp_dir_arguments_are_not_given_a_value() {
+        let _fn: ItemFn = parse_quote! {
+            fn f(n: u8, #[temp_dir] dir: &Path) {}
+        };
+        let case: TestCase = parse_quote!(1; "one");
+        let rendered = resolve_test_case(_fn, &[], case).to_string();
+
+        if cfg!(feature = "temp_dir") {
+            assert!(rendered.contains("__temp_dir_guard_1"), "{}", rendered);
+            assert!(
+                rendered.contains(&quote!(let dir: &Path = __temp_dir_guard_1.path();).to_string())
+            );
+        } else {
+            assert!(
+                rendered.contains("require the `temp_dir` feature"),
+                "{}",
+                rendered
+            );
+        }
+    }
+
+    #[test]
+    fn extra_arguments_are_reported() {
+        let _fn: ItemFn = parse_quote! {
+            fn f(a: u8, b: u8) {}
+        };
+        let case: TestCase = parse_quote!(1, 2, 3, 4; "too many");
+        let rendered = resolve_test_case(_fn, &[], case).to_string();
+
+        assert!(
+            rendered.contains("expected 2 but found 4 (remove 2 arguments)"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn missing_arguments_are_reported() {
+        let _fn: ItemFn = parse_quote! {
+            fn f(a: u8, b: u8, c: u8, #[fixture] db: Database) {}
+        };
+        let case: TestCase = parse_quote!(1; "too few");
+        let rendered = resolve_test_case(_fn, &[], case).to_string();
+
+        assert!(
+            rendered.contains("expected 3 but found 1 (missing 2 arguments)"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn missing_arguments_are_not_treated_as_fixtures() {
+        let _fn: ItemFn = parse_quote! {
+            fn f(a: u8, b: u8) {}
+        };
+        let case: TestCase = parse_quote!(1; "one");
+        let rendered = resolve_test_case(_fn, &[], case).to_string();
+
+        assert!(
+            rendered.contains("wrong number of arguments: expected 2 but found 1"),
+            "{}",
+            rendered
+        );
+        assert!(
+            !rendered.contains("__simple_test_case_setup"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn fixture_arguments_are_not_given_a_value() {
+        let _fn: ItemFn = parse_quote! {
+            fn f(a: u8, #[fixture] db: Database) {}
+        };
+        let case: TestCase = parse_quote!(1; "one");
+        let rendered = resolve_test_case(_fn, &[], case).to_string();
+        let binding = quote! {
+            let (__fixture_guard_db, db): (_, Database) = db::__simple_test_case_setup();
+        };
+
+        assert!(rendered.contains(&binding.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn names_without_letters_or_digits_are_an_error() {
+        let err = |tokens: proc_macro2::TokenStream| {
+            syn::parse2::<TestCase>(tokens)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            err(quote!("a", 3; "!!!")),
+            "test case name \"!!!\" does not contain any letters or digits to name the test with: try a name such as \"a 3\""
+        );
+        assert_eq!(
+            err(quote!(-(); "  ")),
+            "test case name \"  \" does not contain any letters or digits to name the test with: try a name such as \"case\""
+        );
+        assert!(syn::parse2::<TestCase>(quote!(1; "_1")).is_ok());
+    }
+
+    #[test]
+    fn test_attr_above_cases_is_reported() {
+        let bare: ItemFn = parse_quote! { fn double(n: usize) {} };
+        let ordered: ItemFn = parse_quote! { #[test] fn double(n: usize) {} };
+
+        let cases: Vec<TestCase> = vec![parse_quote!(1; "one")];
+
+        assert_eq!(
+            missing_test_attr(&bare, &cases),
+            Some("no test attribute found below the test_case attributes: move #[test] below all test_case attributes")
+        );
+        assert_eq!(missing_test_attr(&ordered, &cases), None);
+    }
+
+    #[test]
+    fn missing_test_attr_is_reported() {
+        let cases: Vec<TestCase> = vec![parse_quote!(1; "one"), parse_quote!(2; "two")];
+        let per_case: Vec<TestCase> = vec![
+            parse_quote!(1; "one"; #[tokio::test]),
+            parse_quote!(2; "two"; #[test]),
+        ];
+        let untested: ItemFn = parse_quote! { #[should_panic] fn double(n: usize) {} };
+
+        assert_eq!(
+            missing_test_attr(&untested, &cases),
+            Some("no test attribute found below the test_case attributes: add #[test] (or an alternative such as #[tokio::test]) below them so that the test cases are run")
+        );
+        assert_eq!(missing_test_attr(&untested, &per_case), None);
+
+        for attr in [
+            quote!(#[tokio::test(flavor = "multi_thread")]),
+            quote!(#[wasm_bindgen_test]),
+            quote!(#[kani::proof]),
+            quote!(#[test_log::test]),
+        ] {
+            let _fn: ItemFn = parse_quote! { #attr fn double(n: usize) {} };
+            assert_eq!(missing_test_attr(&_fn, &cases), None, "{}", attr);
+        }
+    }
+
+    #[test]
+    fn generic_functions_are_an_error() {
+        let err = |_fn: ItemFn| check_not_generic(&_fn).unwrap_err().to_string();
+
+        assert_eq!(
+            err(parse_quote! { fn case<T: Default>(t: T) {} }),
+            "test_case test functions can not be generic: give each argument an explicit type (use type_cases to run a test for several types)"
+        );
+        assert_eq!(
+            err(parse_quote! { fn case<'a>(s: &'a str) {} }),
+            "test_case test functions can not be generic: give each argument an explicit type (use type_cases to run a test for several types)"
+        );
+        assert_eq!(
+            err(parse_quote! { fn case(n: usize, t: impl Default) {} }),
+            "impl Trait arguments are not supported for test cases: give the argument an explicit type"
+        );
+        assert!(check_not_generic(&parse_quote! { fn case(s: &str) {} }).is_ok());
+    }
+
+    #[test]
+    fn non_snake_case_modules_are_allowed() {
+        let allow = |ident: Ident| module_allow(&ident).map(|t| t.to_string());
+        let expected = Some(quote!(#[allow(non_snake_case)]).to_string());
+
+        assert_eq!(allow(parse_quote!(TestHTTP2Frames)), expected);
+        assert_eq!(allow(parse_quote!(r#Type)), expected);
+        assert_eq!(allow(parse_quote!(double)), None);
+        assert_eq!(allow(parse_quote!(r#type)), None);
+    }
+
+    #[test]
+    fn lint_levels_and_cfgs_are_repeated_on_the_module() {
+        let _fn: ItemFn = parse_quote! {
+            #[test]
+            #[allow(unused_mut, clippy::identity_op)]
+            #[expect(dead_code)]
+            #[cfg(feature = "x")]
+            #[cfg_attr(miri, ignore)]
+            #[cfg_attr(miri, ignore, allow(unused_variables))]
+            #[deny(warnings)]
+            fn case(n: usize) {}
+        };
+        let attrs: Vec<_> = module_attrs(&_fn.attrs)
+            .into_iter()
+            .map(|a| a.to_token_stream().to_string())
+            .collect();
+
+        assert_eq!(
+            attrs,
+            vec![
+                quote!(#[allow(unused_mut, clippy::identity_op)]).to_string(),
+                quote!(#[cfg(feature = "x")]).to_string(),
+                quote!(#[cfg_attr(miri, allow(unused_variables))]).to_string(),
+                quote!(#[deny(warnings)]).to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn colliding_case_names_are_reported() {
+        let cases: Vec<TestCase> = vec![
+            parse_quote!(1; "a b"),
+            parse_quote!(2; "A-B"),
+            parse_quote!(3; "a b"; module = nested),
+        ];
+        let errs: Vec<_> = check_unique_names(&cases)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        assert_eq!(
+            errs,
+            vec![
+                "test case name \"A-B\" collides with \"a b\": both are named `a_b`",
+                "`a_b` is first defined here",
+            ]
+        );
+    }
+
     #[test]
     fn parse_test_case() {
         let input: proc_macro2::TokenStream = parse_quote! {
@@ -179,5 +1100,67 @@ mod tests {
         }
 
         assert_eq!(parsed.name.value(), "name here");
+        assert!(parsed.attrs.is_empty());
+    }
+
+    #[test]
+    fn parse_test_case_with_attrs() {
+        let input: proc_macro2::TokenStream = parse_quote! {
+            1, 2; "name here"; #[ignore = "slow"], ignore, miri_only, skip_miri
+        };
+
+        let parsed: TestCase = syn::parse2(input).unwrap();
+        let expected: Vec<Attribute> = vec![
+            parse_quote!(#[ignore = "slow"]),
+            parse_quote!(#[ignore]),
+            parse_quote!(#[cfg(miri)]),
+            parse_quote!(#[cfg_attr(miri, ignore)]),
+        ];
+
+        assert_eq!(parsed.attrs, expected);
+    }
+
+    #[cfg(feature = "serial")]
+    #[test]
+    fn parse_test_case_with_serial() {
+        let input: proc_macro2::TokenStream = parse_quote! {
+            1, 2; "name here"; serial, serial(db, fs)
+        };
+
+        let parsed: TestCase = syn::parse2(input).unwrap();
+        let expected: Vec<Attribute> = vec![
+            parse_quote!(#[::serial_test::serial]),
+            parse_quote!(#[::serial_test::serial(db, fs)]),
+        ];
+
+        assert_eq!(parsed.attrs, expected);
+    }
+
+    #[test]
+    fn tags_are_nested_modules_after_the_case_module() {
+        let input: proc_macro2::TokenStream = parse_quote! {
+            1, 2; "name here"; tags = [slow, network], module = parsing, tags = [slow]
+        };
+
+        let parsed: TestCase = syn::parse2(input).unwrap();
+        let expected: Vec<Ident> = vec![
+            parse_quote!(parsing),
+            parse_quote!(tag_network),
+            parse_quote!(tag_slow),
+        ];
+
+        assert_eq!(parsed.module, expected);
+    }
+
+    #[test]
+    fn parse_test_case_with_module() {
+        let input: proc_macro2::TokenStream = parse_quote! {
+            1, 2; "name here"; module = parsing::numbers
+        };
+
+        let parsed: TestCase = syn::parse2(input).unwrap();
+        let expected: Vec<Ident> = vec![parse_quote!(parsing), parse_quote!(numbers)];
+
+        assert_eq!(parsed.module, expected);
     }
 }
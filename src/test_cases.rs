@@ -0,0 +1,209 @@
+use crate::boundary_cases::ExplicitCase;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    braced, bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Error, Expr, Ident, ItemFn, LitStr, Result, Token,
+};
+
+// test_cases! { #[test] fn name(..) { .. } cases: [ (exprs, ...) => "name", ... ] }
+//
+// Each case is written out as a test_case attribute placed above all of the attributes of the
+// function so that the ordering requirements of test_case are always met.
+struct TestCases {
+    original: ItemFn,
+    cases: CaseList,
+}
+
+impl Parse for TestCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let original: ItemFn = input.parse()?;
+        let label: Ident = input.parse()?;
+        if label != "cases" {
+            return Err(Error::new(label.span(), "expected `cases: [...]`"));
+        }
+        let _: Token![:] = input.parse()?;
+
+        let cases = parse_case_list(input)?;
+        if cases.is_empty() {
+            return Err(Error::new(
+                original.sig.ident.span(),
+                "test_cases! requires at least one case",
+            ));
+        }
+        if !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+        }
+
+        Ok(Self { original, cases })
+    }
+}
+
+// A list of cases of the form [ (exprs, ...) => "name", ... ] mapping case arguments to case names
+pub(crate) type CaseList = Vec<(Punctuated<Expr, Token![,]>, LitStr)>;
+
+pub(crate) fn parse_case_list(input: ParseStream<'_>) -> Result<CaseList> {
+    let content;
+    bracketed!(content in input);
+    let mut cases = Vec::new();
+
+    while !content.is_empty() {
+        let args;
+        parenthesized!(args in content);
+        let args = Punctuated::parse_terminated(&args)?;
+        if args.is_empty() {
+            return Err(content.error("test cases must provide at least one argument"));
+        }
+        let _: Token![=>] = content.parse()?;
+        let name: LitStr = content.parse()?;
+        cases.push((args, name));
+
+        if !content.is_empty() {
+            let _: Token![,] = content.parse()?;
+        }
+    }
+
+    Ok(cases)
+}
+
+pub(crate) fn inner(input: TokenStream) -> TokenStream {
+    let TestCases { original, cases } = parse_macro_input!(input as TestCases);
+
+    let case_attrs = cases.iter().map(|(args, name)| {
+        let args = args.iter();
+        quote! { #[simple_test_case::test_case(#(#args),*; #name)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+// param_test! { #[test] fn name(..) { .. } cases { (exprs, ...; "name"; opts), ... } }
+//
+// The same as test_cases! but with each case written using the test_case attribute syntax, so
+// that per-case options can be given and cases can be moved between the two forms unchanged.
+struct ParamTest {
+    original: ItemFn,
+    cases: Vec<ExplicitCase>,
+}
+
+impl Parse for ParamTest {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let original: ItemFn = input.parse()?;
+        let label: Ident = input.parse()?;
+        if label != "cases" {
+            return Err(Error::new(label.span(), "expected `cases { ... }`"));
+        }
+
+        let content;
+        braced!(content in input);
+        let cases = parse_explicit_cases(&content)?;
+
+        if cases.is_empty() {
+            return Err(Error::new(
+                original.sig.ident.span(),
+                "param_test! requires at least one case",
+            ));
+        }
+
+        Ok(Self { original, cases })
+    }
+}
+
+// A comma separated list of cases of the form (exprs, ...; "name"; opts)
+pub(crate) fn parse_explicit_cases(input: ParseStream<'_>) -> Result<Vec<ExplicitCase>> {
+    let mut cases = Vec::new();
+
+    while !input.is_empty() {
+        let case;
+        parenthesized!(case in input);
+        cases.push(case.parse()?);
+
+        if !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+        }
+    }
+
+    Ok(cases)
+}
+
+pub(crate) fn param_test_inner(input: TokenStream) -> TokenStream {
+    let ParamTest { original, cases } = parse_macro_input!(input as ParamTest);
+
+    let case_attrs = cases.iter().map(|case| {
+        let args = case.args.iter();
+        let (name, rest) = (&case.name, &case.rest);
+        quote! { #[simple_test_case::test_case(#(#args),*; #name #rest)] }
+    });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn parse_test_cases() {
+        let parsed: TestCases = parse_quote! {
+            #[test]
+            fn double(n: usize, expected: usize) {
+                assert_eq!(n * 2, expected);
+            }
+
+            cases: [
+                (1, 2) => "small",
+                (100, 200) => "large",
+            ]
+        };
+        let names: Vec<_> = parsed.cases.iter().map(|(_, name)| name.value()).collect();
+
+        assert_eq!(parsed.original.sig.ident, "double");
+        assert_eq!(names, vec!["small", "large"]);
+        assert_eq!(parsed.cases[1].0.len(), 2);
+    }
+
+    #[test]
+    fn cases_are_required() {
+        let res = syn::parse2::<TestCases>(quote! {
+            #[test]
+            fn double(n: usize) {}
+
+            cases: []
+        });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn parse_param_test() {
+        let parsed: ParamTest = parse_quote! {
+            #[test]
+            fn double(n: usize, expected: usize) {
+                assert_eq!(n * 2, expected);
+            }
+
+            cases {
+                (1, 2; "small"),
+                (100, 200; "large"; ignore),
+            }
+        };
+        let names: Vec<_> = parsed.cases.iter().map(|c| c.name.value()).collect();
+
+        assert_eq!(names, vec!["small", "large"]);
+        assert_eq!(
+            parsed.cases[1].rest.to_string(),
+            quote!(; ignore).to_string()
+        );
+        assert!(syn::parse2::<ParamTest>(quote! { fn double(n: usize) {} cases {} }).is_err());
+    }
+}
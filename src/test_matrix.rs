@@ -0,0 +1,316 @@
+use crate::util::slugify_label;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, ToTokens};
+use std::collections::HashMap;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, Expr, ExprArray, ExprLit, ExprUnary, FnArg, Ident, ItemFn, Lit, LitStr, Pat, Result,
+    Token, UnOp,
+};
+
+// test_matrix([values for arg 1, ...], [values for arg 2, ...], ...) generating a case for every
+// combination of values. Each list can optionally be named after the argument it provides values
+// for and each value can be given an explicit label to use in case names:
+//   test_matrix(flavor = [("fast", Config::fast()), ("safe", Config::safe())], [true, false])
+struct TestMatrix {
+    span: Span,
+    axes: Vec<Axis>,
+}
+
+struct Axis {
+    name: Option<Ident>,
+    values: Vec<(Option<LitStr>, Expr)>,
+}
+
+impl Parse for Axis {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let name = if input.peek(Ident) && input.peek2(Token![=]) {
+            let name: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            Some(name)
+        } else {
+            None
+        };
+
+        let array: ExprArray = input.parse()?;
+        if array.elems.is_empty() {
+            return Err(Error::new(
+                array.span(),
+                "test_matrix value lists must not be empty",
+            ));
+        }
+        let values = array.elems.into_iter().map(labelled_value).collect();
+
+        Ok(Self { name, values })
+    }
+}
+
+// A two element tuple whose first element is a string literal is a labelled value: ("label", expr)
+fn labelled_value(expr: Expr) -> (Option<LitStr>, Expr) {
+    match expr {
+        Expr::Tuple(tuple) if tuple.elems.len() == 2 => match (&tuple.elems[0], &tuple.elems[1]) {
+            (
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(label),
+                    ..
+                }),
+                value,
+            ) => (Some(label.clone()), value.clone()),
+            _ => (None, Expr::Tuple(tuple)),
+        },
+        expr => (None, expr),
+    }
+}
+
+impl Parse for TestMatrix {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let axes: Punctuated<Axis, Token![,]> = Punctuated::parse_terminated(input)?;
+        let axes: Vec<Axis> = axes.into_iter().collect();
+
+        if axes.is_empty() {
+            return Err(Error::new(
+                span,
+                "test_matrix requires a list of values for each argument of the test function",
+            ));
+        }
+
+        Ok(Self { span, axes })
+    }
+}
+
+// The label used for a value within a case name. String literals use their contents rather than
+// the quoted literal and negative numbers are given a `neg_` prefix so that `-1` and `1` remain
+// distinct once slugified.
+fn value_label(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => slugify_label(&s.value()),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => format!("neg_{}", value_label(expr)),
+        expr => slugify_label(&expr.to_token_stream().to_string()),
+    }
+}
+
+// Labels for each value in an axis, which must be non-empty and distinct from one another in order
+// for every combination of values to result in a unique case name.
+fn axis_labels(values: &[(Option<LitStr>, Expr)]) -> Result<Vec<String>> {
+    let mut seen: HashMap<String, &Expr> = HashMap::new();
+    let mut labels = Vec::with_capacity(values.len());
+
+    for (explicit, value) in values.iter() {
+        let (label, span) = match explicit {
+            Some(lit) => (slugify_label(&lit.value()), lit.span()),
+            None => (value_label(value), value.span()),
+        };
+        if label.is_empty() {
+            return Err(Error::new(
+                span,
+                "unable to derive a case name for this test_matrix value",
+            ));
+        }
+        if let Some(other) = seen.insert(label.clone(), value) {
+            return Err(Error::new(
+                span,
+                format!(
+                    "test_matrix values `{}` and `{}` both result in the case name `{}`",
+                    other.to_token_stream(),
+                    value.to_token_stream(),
+                    label
+                ),
+            ));
+        }
+        labels.push(label);
+    }
+
+    Ok(labels)
+}
+
+// The cartesian product of the axes in row major order (the last axis varies fastest), with each
+// combination given as the indices of the chosen values.
+fn combinations(lens: &[usize]) -> Vec<Vec<usize>> {
+    let mut combos = vec![Vec::new()];
+
+    for &len in lens.iter() {
+        combos = combos
+            .into_iter()
+            .flat_map(|combo| {
+                (0..len).map(move |i| {
+                    let mut combo = combo.clone();
+                    combo.push(i);
+                    combo
+                })
+            })
+            .collect();
+    }
+
+    combos
+}
+
+// Named axes must match the name of the argument they provide values for so that reordering the
+// arguments of the test function can't silently swap the values being passed.
+fn check_axis_names(axes: &[Axis], _fn: &ItemFn) -> Result<()> {
+    for (axis, fnarg) in axes.iter().zip(_fn.sig.inputs.iter()) {
+        let name = match &axis.name {
+            Some(name) => name,
+            None => continue,
+        };
+        let matches = match fnarg {
+            FnArg::Typed(pt) => matches!(&*pt.pat, Pat::Ident(p) if p.ident == *name),
+            FnArg::Receiver(_) => false,
+        };
+
+        if !matches {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "test_matrix values for `{}` do not match the test function argument `{}`",
+                    name,
+                    fnarg.to_token_stream()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let TestMatrix { span, axes } = parse_macro_input!(args as TestMatrix);
+    let original = parse_macro_input!(input as ItemFn);
+
+    // Any trailing arguments without a list of values are provided by fixtures
+    if axes.len() > original.sig.inputs.len() {
+        return TokenStream::from(
+            Error::new(
+                span,
+                format!(
+                    "test_matrix was given {} lists of values but the test function accepts {} arguments",
+                    axes.len(),
+                    original.sig.inputs.len()
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    if let Err(e) = check_axis_names(&axes, &original) {
+        return TokenStream::from(e.into_compile_error());
+    }
+
+    let labels = match axes
+        .iter()
+        .map(|axis| axis_labels(&axis.values))
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(labels) => labels,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let lens: Vec<usize> = axes.iter().map(|axis| axis.values.len()).collect();
+    let case_attrs: Vec<_> = combinations(&lens)
+        .into_iter()
+        .map(|combo| {
+            let args = combo
+                .iter()
+                .enumerate()
+                .map(|(axis, &i)| &axes[axis].values[i].1);
+            let name: Vec<&str> = combo
+                .iter()
+                .enumerate()
+                .map(|(axis, &i)| labels[axis][i].as_str())
+                .collect();
+            let name = name.join("_");
+
+            quote! {
+                #[simple_test_case::test_case(#(#args),*; #name)]
+            }
+        })
+        .collect();
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn combinations_cover_every_pair_of_values() {
+        assert_eq!(
+            combinations(&[2, 3]),
+            vec![
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 0],
+                vec![1, 1],
+                vec![1, 2],
+            ]
+        );
+    }
+
+    #[test]
+    fn values_are_labelled() {
+        let axis: Axis = parse_quote!([
+            1,
+            -1,
+            "hello world",
+            Mode::Strict,
+            true,
+            ("fast", Config::fast()),
+            (1, 2)
+        ]);
+        let labels = axis_labels(&axis.values).unwrap();
+
+        assert_eq!(
+            labels,
+            vec![
+                "1",
+                "neg_1",
+                "hello_world",
+                "mode_strict",
+                "true",
+                "fast",
+                "1_2"
+            ]
+        );
+    }
+
+    #[test]
+    fn clashing_labels_are_an_error() {
+        let axis: Axis = parse_quote!(["a b", "a-b"]);
+        let labelled: Axis = parse_quote!([("x", 1), ("x", 2)]);
+
+        assert!(axis_labels(&axis.values).is_err());
+        assert!(axis_labels(&labelled.values).is_err());
+    }
+
+    #[test]
+    fn axes_can_be_named() {
+        let matrix: TestMatrix = parse_quote!(flavor = [("fast", 1)], [true]);
+        let names: Vec<_> = matrix.axes.iter().map(|a| a.name.clone()).collect();
+        let matching: ItemFn = parse_quote!(
+            fn t(flavor: u8, flag: bool) {}
+        );
+        let mismatched: ItemFn = parse_quote!(
+            fn t(flag: bool, flavor: u8) {}
+        );
+
+        assert_eq!(names, vec![Some(parse_quote!(flavor)), None]);
+        assert!(check_axis_names(&matrix.axes, &matching).is_ok());
+        assert!(check_axis_names(&matrix.axes, &mismatched).is_err());
+    }
+}
@@ -0,0 +1,400 @@
+// A minimal TOML parser for reading test case registries at expansion time. Only the subset of
+// TOML needed for flat tables of test data is supported: [table] and [[array of tables]] headers
+// with bare names, bare or quoted keys, basic and literal strings, integers, floats, booleans,
+// arrays and inline tables. The document is returned as a json::Value so that the same conversion
+// into argument expressions can be used for both formats.
+use crate::json::Value;
+
+pub(crate) fn parse_toml(raw: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: raw.char_indices().peekable(),
+        raw,
+    };
+    let mut root: Vec<(String, Value)> = Vec::new();
+    // The table that key/value pairs are currently being added to: None for the root table
+    let mut current: Option<String> = None;
+
+    loop {
+        parser.skip_whitespace_and_comments(true);
+        let c = match parser.chars.peek() {
+            Some(&(_, c)) => c,
+            None => break,
+        };
+
+        if c == '[' {
+            parser.chars.next();
+            let is_array = parser.eat('[');
+            parser.skip_whitespace_and_comments(false);
+            let name = parser.key()?;
+            parser.expect(']')?;
+            if is_array {
+                parser.expect(']')?;
+            }
+            parser.end_of_line()?;
+
+            match (root.iter_mut().find(|(k, _)| *k == name), is_array) {
+                (None, true) => {
+                    root.push((name.clone(), Value::Array(vec![Value::Object(vec![])])))
+                }
+                (None, false) => root.push((name.clone(), Value::Object(vec![]))),
+                (Some((_, Value::Array(tables))), true) => tables.push(Value::Object(vec![])),
+                (Some(_), _) => return Err(parser.error(&format!("duplicate table '{}'", name))),
+            }
+            current = Some(name);
+            continue;
+        }
+
+        let (key, value) = parser.key_value()?;
+        parser.end_of_line()?;
+
+        let table = match &current {
+            None => &mut root,
+            Some(name) => match root.iter_mut().find(|(k, _)| k == name) {
+                Some((_, Value::Object(fields))) => fields,
+                Some((_, Value::Array(tables))) => match tables.last_mut() {
+                    Some(Value::Object(fields)) => fields,
+                    _ => unreachable!("arrays of tables only contain tables"),
+                },
+                _ => unreachable!("the current table is always present"),
+            },
+        };
+        if table.iter().any(|(k, _)| *k == key) {
+            return Err(parser.error(&format!("duplicate key '{}'", key)));
+        }
+        table.push((key, value));
+    }
+
+    Ok(Value::Object(root))
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    raw: &'a str,
+}
+
+impl Parser<'_> {
+    fn error_at(&self, offset: usize, msg: &str) -> String {
+        let line = self.raw[..offset].matches('\n').count() + 1;
+        format!("line {}: {}", line, msg)
+    }
+
+    fn error(&mut self, msg: &str) -> String {
+        let offset = self.chars.peek().map(|&(i, _)| i).unwrap_or(self.raw.len());
+        self.error_at(offset, msg)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        match self.chars.peek() {
+            Some(&(_, c)) if c == expected => {
+                self.chars.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self, newlines: bool) {
+        while let Some(&(_, c)) = self.chars.peek() {
+            match c {
+                '#' => {
+                    while matches!(self.chars.peek(), Some((_, c)) if *c != '\n') {
+                        self.chars.next();
+                    }
+                }
+                '\n' | '\r' if !newlines => break,
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace_and_comments(false);
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => {
+                Err(self.error_at(i, &format!("expected '{}', found '{}'", expected, c)))
+            }
+            None => Err(self.error(&format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip_whitespace_and_comments(false);
+        match self.chars.peek() {
+            None | Some((_, '\n' | '\r')) => Ok(()),
+            Some(&(_, c)) => Err(self.error(&format!("expected a new line, found '{}'", c))),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        self.skip_whitespace_and_comments(false);
+        match self.chars.peek().map(|&(_, c)| c) {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let mut key = String::new();
+                while let Some(&(_, c)) = self.chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        break;
+                    }
+                    key.push(c);
+                    self.chars.next();
+                }
+                match self.chars.peek() {
+                    Some((_, '.')) => Err(self.error("dotted keys are not supported")),
+                    _ if key.is_empty() => Err(self.error("expected a key")),
+                    _ => Ok(key),
+                }
+            }
+        }
+    }
+
+    fn key_value(&mut self) -> Result<(String, Value), String> {
+        let key = self.key()?;
+        self.expect('=')?;
+        let value = self.value()?;
+
+        Ok((key, value))
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace_and_comments(false);
+        match self.chars.peek().map(|&(_, c)| c) {
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('"') => self.basic_string().map(Value::Str),
+            Some('\'') => self.literal_string().map(Value::Str),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some(c) if matches!(c, '-' | '+') || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(self.error(&format!("unexpected character '{}'", c))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        for expected in keyword.chars() {
+            match self.chars.next() {
+                Some((_, c)) if c == expected => (),
+                _ => return Err(self.error(&format!("invalid literal: expected {}", keyword))),
+            }
+        }
+
+        Ok(value)
+    }
+
+    // Numbers are kept as the text that was written (minus any underscores and leading '+') so
+    // that they can be emitted as Rust literals without losing precision
+    fn number(&mut self) -> Result<Value, String> {
+        let mut n = String::new();
+        while let Some(&(_, c)) = self.chars.peek() {
+            if !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.' | '_')) {
+                break;
+            }
+            if c != '_' {
+                n.push(c);
+            }
+            self.chars.next();
+        }
+        let n = n.strip_prefix('+').unwrap_or(&n).to_string();
+        let digits = n.strip_prefix('-').unwrap_or(&n);
+
+        let valid = match digits.get(..2) {
+            Some("0x") => u128::from_str_radix(&digits[2..], 16).is_ok(),
+            Some("0o") => u128::from_str_radix(&digits[2..], 8).is_ok(),
+            Some("0b") => u128::from_str_radix(&digits[2..], 2).is_ok(),
+            _ => matches!(n.parse::<f64>(), Ok(f) if f.is_finite()),
+        };
+
+        match valid {
+            true => Ok(Value::Number(n)),
+            false => Err(self.error(&format!("invalid number: {}", n))),
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(s),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '"')) => s.push('"'),
+                    Some((_, '\\')) => s.push('\\'),
+                    Some((_, 'b')) => s.push('\u{8}'),
+                    Some((_, 'f')) => s.push('\u{c}'),
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 'r')) => s.push('\r'),
+                    Some((_, 't')) => s.push('\t'),
+                    Some((_, 'u')) => s.push(self.unicode_escape(4)?),
+                    Some((_, 'U')) => s.push(self.unicode_escape(8)?),
+                    Some((i, c)) => {
+                        return Err(self.error_at(i, &format!("invalid escape sequence \\{}", c)))
+                    }
+                    None => break,
+                },
+                Some((_, '\n')) => break,
+                Some((_, c)) => s.push(c),
+                None => break,
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    fn literal_string(&mut self) -> Result<String, String> {
+        self.expect('\'')?;
+        let mut s = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '\'')) => return Ok(s),
+                Some((_, '\n')) | None => return Err(self.error("unterminated string")),
+                Some((_, c)) => s.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, len: usize) -> Result<char, String> {
+        let mut code = 0;
+        for _ in 0..len {
+            match self.chars.next().and_then(|(_, c)| c.to_digit(16)) {
+                Some(d) => code = code * 16 + d,
+                None => return Err(self.error("invalid unicode escape")),
+            }
+        }
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+
+        loop {
+            self.skip_whitespace_and_comments(true);
+            if self.eat(']') {
+                return Ok(Value::Array(values));
+            }
+            values.push(self.value()?);
+            self.skip_whitespace_and_comments(true);
+            match self.chars.next() {
+                Some((_, ',')) => (),
+                Some((_, ']')) => return Ok(Value::Array(values)),
+                _ => return Err(self.error("expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace_and_comments(false);
+        if self.eat('}') {
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            let (key, value) = self.key_value()?;
+            if fields.iter().any(|(k, _)| *k == key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            fields.push((key, value));
+            self.skip_whitespace_and_comments(false);
+            match self.chars.next() {
+                Some((_, ',')) => (),
+                Some((_, '}')) => return Ok(Value::Object(fields)),
+                _ => return Err(self.error("expected ',' or '}' in inline table")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(s: &str) -> Value {
+        Value::Str(s.to_string())
+    }
+
+    fn n(n: &str) -> Value {
+        Value::Number(n.to_string())
+    }
+
+    #[test]
+    fn toml_is_parsed() {
+        let raw = r#"
+# A comment
+title = 'literal \n'
+
+[[case]]
+name = "a\tb" # trailing comment
+args = [
+  1_000,
+  -1.5e3, # comment inside an array
+  0xff,
+]
+
+[[case]]
+"quoted key" = { ok = true, v = [] }
+
+[meta]
+count = +2
+"#;
+
+        assert_eq!(
+            parse_toml(raw),
+            Ok(Value::Object(vec![
+                ("title".to_string(), s("literal \\n")),
+                (
+                    "case".to_string(),
+                    Value::Array(vec![
+                        Value::Object(vec![
+                            ("name".to_string(), s("a\tb")),
+                            (
+                                "args".to_string(),
+                                Value::Array(vec![n("1000"), n("-1.5e3"), n("0xff")])
+                            ),
+                        ]),
+                        Value::Object(vec![(
+                            "quoted key".to_string(),
+                            Value::Object(vec![
+                                ("ok".to_string(), Value::Bool(true)),
+                                ("v".to_string(), Value::Array(vec![])),
+                            ])
+                        )]),
+                    ])
+                ),
+                (
+                    "meta".to_string(),
+                    Value::Object(vec![("count".to_string(), n("2"))])
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        let cases = [
+            "a = 1\na = 2",
+            "a = [1, 2",
+            "a = \"unterminated",
+            "a = 1 b = 2",
+            "a.b = 1",
+            "[t]\n[t]",
+            "[t]\n[[t]]",
+            "a = nan",
+            "a = 0xzz",
+            "= 1",
+        ];
+
+        for raw in cases {
+            assert!(parse_toml(raw).is_err(), "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::util::slugify_label;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashMap;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, GenericParam, ItemFn, Result, ReturnType, Token, Type,
+};
+
+// type_cases(u8, u16, ...) applied to a test function with a single type parameter
+struct TypeCases {
+    span: Span,
+    types: Vec<Type>,
+}
+
+impl Parse for TypeCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let span = input.span();
+        let types: Punctuated<Type, Token![,]> = Punctuated::parse_terminated(input)?;
+        if types.is_empty() {
+            return Err(Error::new(span, "type_cases requires at least one type"));
+        }
+
+        Ok(Self {
+            span,
+            types: types.into_iter().collect(),
+        })
+    }
+}
+
+// Each type is named after its slugified tokens, so `Vec<u8>` results in a case named `case_vec_u8`
+fn case_names(types: &[Type]) -> Result<Vec<String>> {
+    let mut seen: HashMap<String, &Type> = HashMap::new();
+    let mut names = Vec::with_capacity(types.len());
+
+    for ty in types.iter() {
+        let name = format!("case_{}", slugify_label(&ty.to_token_stream().to_string()));
+        if let Some(other) = seen.insert(name.clone(), ty) {
+            return Err(Error::new(
+                ty.span(),
+                format!(
+                    "type_cases types `{}` and `{}` both result in the case name `{}`",
+                    other.to_token_stream(),
+                    ty.to_token_stream(),
+                    name
+                ),
+            ));
+        }
+        names.push(name);
+    }
+
+    Ok(names)
+}
+
+fn check_signature(span: Span, _fn: &ItemFn) -> Result<()> {
+    let sig = &_fn.sig;
+    let n_types = sig
+        .generics
+        .params
+        .iter()
+        .filter(|p| matches!(p, GenericParam::Type(_)))
+        .count();
+
+    if n_types != 1 || sig.generics.params.len() != 1 {
+        return Err(Error::new(
+            span,
+            "type_cases test functions must have exactly one generic type parameter",
+        ));
+    }
+    if !sig.inputs.is_empty() {
+        return Err(Error::new(
+            sig.inputs.span(),
+            "type_cases test functions can not accept arguments",
+        ));
+    }
+
+    Ok(())
+}
+
+// The original generic function is kept as written inside of a module named after it and each case
+// calls it with one of the given types. Any attributes on the original function (such as `#[test]`)
+// are moved onto the cases.
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let TypeCases { span, types } = parse_macro_input!(args as TypeCases);
+    let mut original = parse_macro_input!(input as ItemFn);
+
+    if let Err(e) = check_signature(span, &original) {
+        return TokenStream::from(e.into_compile_error());
+    }
+    let names = match case_names(&types) {
+        Ok(names) => names,
+        Err(e) => return TokenStream::from(e.into_compile_error()),
+    };
+
+    let attrs = std::mem::take(&mut original.attrs);
+    original.attrs.push(syn::parse_quote!(#[allow(dead_code)]));
+    let ident = &original.sig.ident;
+    let asyncness = &original.sig.asyncness;
+    let output = &original.sig.output;
+    let call = match asyncness {
+        Some(_) => quote! { .await },
+        None => quote! {},
+    };
+    let ret = match output {
+        ReturnType::Default => quote! { ; },
+        ReturnType::Type(..) => quote! {},
+    };
+
+    let cases = types.iter().zip(names).map(|(ty, name)| {
+        let case = format_ident!("{}", name, span = ty.span());
+        quote! {
+            #(#attrs)*
+            #asyncness fn #case() #output {
+                #ident::<#ty>()#call #ret
+            }
+        }
+    });
+
+    TokenStream::from(quote! {
+        mod #ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #original
+
+            #(#cases)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn cases_are_named_after_types() {
+        let parsed: TypeCases = parse_quote!(u8, Vec<u8>, [u8; 4]);
+
+        assert_eq!(
+            case_names(&parsed.types).unwrap(),
+            vec!["case_u8", "case_vec_u8", "case_u8_4"]
+        );
+    }
+
+    #[test]
+    fn clashing_case_names_are_an_error() {
+        let parsed: TypeCases = parse_quote!(Vec<u8>, vec<u8>);
+
+        assert!(case_names(&parsed.types).is_err());
+    }
+
+    #[test]
+    fn a_single_type_parameter_is_required() {
+        let fns: Vec<ItemFn> = vec![
+            parse_quote!(
+                fn t() {}
+            ),
+            parse_quote!(
+                fn t<A, B>() {}
+            ),
+            parse_quote!(
+                fn t<const N: usize>() {}
+            ),
+            parse_quote!(
+                fn t<T>(t: T) {}
+            ),
+        ];
+
+        for f in fns.iter() {
+            assert!(check_signature(Span::call_site(), f).is_err());
+        }
+        assert!(check_signature(
+            Span::call_site(),
+            &parse_quote!(
+                fn t<T>() {}
+            )
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Error, FnArg, Ident, ItemFn, Result, Token, Type,
+};
+
+// Strings that commonly break text handling code. Lone surrogates can't be represented in a Rust
+// string so they are given in their escaped form, as they would appear in JSON.
+const CORPUS: &[(&str, &str)] = &[
+    ("empty", ""),
+    ("space", " "),
+    ("whitespace", "\t\n\r\u{b}\u{c}\u{85}\u{a0}\u{2028}\u{3000}"),
+    ("nul", "a\0b"),
+    ("crlf", "one\r\ntwo\r\n"),
+    ("control", "\u{7}\u{8}\u{1b}[31mred\u{1b}[0m"),
+    ("quotes", "\"'`\\"),
+    ("bom", "\u{feff}text"),
+    ("zero_width", "a\u{200b}b\u{200c}c\u{200d}d\u{2060}e"),
+    ("rtl_override", "\u{202e}fdp.exe"),
+    ("rtl_text", "مرحبا بالعالم"),
+    ("mixed_direction", "abc \u{5d0}\u{5d1}\u{5d2} 123"),
+    ("combining", "e\u{301}\u{327}"),
+    (
+        "zalgo",
+        "Z\u{36b}\u{343}\u{36a}a\u{310}\u{34c}l\u{357}\u{34a}g\u{34c}\u{35f}o",
+    ),
+    ("precomposed_and_decomposed", "\u{e9}e\u{301}"),
+    ("cjk", "日本語のテキスト"),
+    ("non_bmp", "𝕳𝖊𝖑𝖑𝖔 𐍈"),
+    ("emoji_modifier", "👍🏽"),
+    ("emoji_zwj_sequence", "👨\u{200d}👩\u{200d}👧\u{200d}👦"),
+    ("flag", "🇬🇧"),
+    ("case_mapping", "ßİıǅﬁ"),
+    ("replacement_char", "\u{fffd}"),
+    ("noncharacters", "\u{fffe}\u{ffff}"),
+    ("max_char", "\u{10ffff}"),
+    ("escaped_lone_surrogates", "\\ud800 \\udfff"),
+];
+
+// Long strings are generated rather than written out in the corpus
+const LONG_LEN: usize = 65_536;
+
+fn corpus() -> Vec<(&'static str, String)> {
+    let mut corpus: Vec<_> = CORPUS.iter().map(|(n, s)| (*n, s.to_string())).collect();
+    corpus.push(("long_ascii", "a".repeat(LONG_LEN)));
+    corpus.push(("long_multibyte", "é".repeat(LONG_LEN / 2)));
+
+    corpus
+}
+
+// unicode_cases with an optional `skip = [name, ...]` for entries that should not be used
+struct UnicodeCases {
+    skip: Vec<Ident>,
+}
+
+impl Parse for UnicodeCases {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut skip = Vec::new();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "skip" => {
+                    let content;
+                    bracketed!(content in input);
+                    skip.extend(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+                }
+                other => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown unicode_cases option: {}", other),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(Self { skip })
+    }
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let UnicodeCases { skip } = parse_macro_input!(args as UnicodeCases);
+    let original = parse_macro_input!(input as ItemFn);
+    let corpus = corpus();
+
+    let str_ty: Type = parse_quote!(&str);
+    let string_ty: Type = parse_quote!(String);
+    let to_string = match original.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(pt)] if *pt.ty == str_ty => false,
+        [FnArg::Typed(pt)] if *pt.ty == string_ty => true,
+        _ => {
+            return TokenStream::from(
+                Error::new_spanned(
+                    &original.sig,
+                    "unicode_cases test functions must accept a single &str or String argument",
+                )
+                .into_compile_error(),
+            )
+        }
+    };
+
+    if let Some(s) = skip.iter().find(|s| !corpus.iter().any(|(n, _)| *s == n)) {
+        let names: Vec<_> = corpus.iter().map(|(n, _)| *n).collect();
+        return TokenStream::from(
+            Error::new(
+                s.span(),
+                format!(
+                    "unknown unicode case: {} (expected one of {})",
+                    s,
+                    names.join(", ")
+                ),
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let case_attrs = corpus
+        .iter()
+        .filter(|(name, _)| !skip.iter().any(|s| s == name))
+        .map(|(name, s)| {
+            let arg = if to_string {
+                quote! { #s.to_string() }
+            } else {
+                quote! { #s }
+            };
+            quote! { #[simple_test_case::test_case(#arg; #name)] }
+        });
+
+    TokenStream::from(quote! {
+        #(#case_attrs)*
+        #original
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_names_are_unique() {
+        let mut names: Vec<_> = corpus().into_iter().map(|(n, _)| n).collect();
+        let n = names.len();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(names.len(), n);
+    }
+}
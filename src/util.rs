@@ -1,3 +1,14 @@
+use proc_macro2::{Ident, Span};
+use quote::{quote, quote_spanned, ToTokens};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, spanned::Spanned, Error, Expr, FnArg,
+    ItemFn, Pat, Token, Type,
+};
+
 pub(crate) fn slugify_path(p: &str) -> String {
     let mut s: String = p
         .to_ascii_lowercase()
@@ -12,6 +23,345 @@ pub(crate) fn slugify_path(p: &str) -> String {
     s
 }
 
+// Slugs used as part of a larger case name have runs of underscores collapsed and any leading or
+// trailing underscores removed: `Vec<u8>` becomes `vec_u8` rather than `vec_u8_`.
+pub(crate) fn slugify_label(s: &str) -> String {
+    let slug = slugify_path(s);
+    let mut label = String::with_capacity(slug.len());
+    for part in slug.split('_').filter(|p| !p.is_empty()) {
+        if !label.is_empty() {
+            label.push('_');
+        }
+        label.push_str(part);
+    }
+
+    label
+}
+
+// The macros that read cases from a data file look up the value for each argument of the test
+// function by name, so the arguments must be simple identifiers. Raw identifiers are matched
+// without their `r#` prefix so that `r#type` is given the value of a `type` field.
+pub(crate) fn param_names_and_types(
+    _fn: &ItemFn,
+    macro_name: &str,
+) -> syn::Result<Vec<(String, Type)>> {
+    _fn.sig
+        .inputs
+        .iter()
+        .map(|fnarg| match fnarg {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(p) => Ok((p.ident.unraw().to_string(), (*pt.ty).clone())),
+                _ => Err(Error::new_spanned(
+                    fnarg,
+                    format!(
+                        "{} test function arguments must be simple identifiers",
+                        macro_name
+                    ),
+                )),
+            },
+            FnArg::Receiver(_) => Err(Error::new_spanned(
+                fnarg,
+                "self is not permitted for test cases",
+            )),
+        })
+        .collect()
+}
+
+// The test_case attribute for a single case read from a data file
+pub(crate) fn case_attr<T: ToTokens>(args: &[T], name: &str) -> proc_macro2::TokenStream {
+    quote! { #[simple_test_case::test_case(#(#args),*; #name)] }
+}
+
+// Convert an identifier to snake_case, splitting words at a lowercase letter or digit followed by
+// an uppercase letter and at the last letter of an acronym: `TestHTTP2Frames` becomes
+// `test_http2_frames`.
+pub(crate) fn snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut snake = String::with_capacity(s.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 && !snake.ends_with('_') {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+// Slugified names can collide with Rust keywords (e.g. a directory of `.in` files), so keywords are
+// emitted as raw identifiers. The few keywords that can't be used as raw identifiers are given a
+// trailing underscore instead.
+pub(crate) fn slug_ident(slug: &str, span: Span) -> Ident {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+
+    match slug {
+        "crate" | "self" | "super" | "_" => Ident::new(&format!("{}_", slug), span),
+        s if KEYWORDS.contains(&s) => Ident::new_raw(s, span),
+        s => Ident::new(s, span),
+    }
+}
+
+// Expand `$NAME` and `${NAME}` references to environment variables (as seen by the compiler when
+// expanding the macro) within a path. `$$` can be used for a literal `$`.
+pub(crate) fn expand_env(s: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let name: String = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                expanded.push('$');
+                continue;
+            }
+            Some('{') => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                if name.is_empty() {
+                    return Err(format!("empty environment variable reference in '{}'", s));
+                }
+                name
+            }
+            _ => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                if name.is_empty() {
+                    return Err(format!("invalid environment variable reference in '{}'", s));
+                }
+                name
+            }
+        };
+
+        match std::env::var(&name) {
+            Ok(val) => expanded.push_str(&val),
+            Err(_) if name == "WORKSPACE_ROOT" => expanded.push_str(&workspace_root()?),
+            Err(_) => {
+                return Err(format!(
+                    "environment variable {} referenced in '{}' is not set",
+                    name, s
+                ))
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
+// The root of the cargo workspace containing the crate being compiled: the closest ancestor of
+// the crate's manifest directory that has a Cargo.toml with a [workspace] section. Crates that are
+// not part of a workspace are their own workspace root.
+pub(crate) fn workspace_root() -> Result<String, String> {
+    let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => std::path::PathBuf::from(dir),
+        Err(_) => std::env::current_dir().map_err(|e| e.to_string())?,
+    };
+
+    let root = manifest_dir
+        .ancestors()
+        .find(|dir| {
+            std::fs::read_to_string(dir.join("Cargo.toml"))
+                .map(|toml| toml.lines().any(|l| l.trim() == "[workspace]"))
+                .unwrap_or(false)
+        })
+        .unwrap_or(&manifest_dir);
+
+    Ok(root.to_string_lossy().into_owned())
+}
+
+// IO errors on their own (e.g. "Permission denied (os error 13)") don't say which path was being
+// accessed or what was being done with it, so we wrap them with both while keeping the error kind.
+pub(crate) fn io_context<'a>(
+    op: &'a str,
+    path: &'a Path,
+) -> impl FnOnce(io::Error) -> io::Error + 'a {
+    move |e| {
+        io::Error::new(
+            e.kind(),
+            format!("unable to {} {}: {}", op, path.display(), e),
+        )
+    }
+}
+
+// Relative fixture paths are resolved against the current directory of the compiler
+pub(crate) fn current_dir() -> io::Result<PathBuf> {
+    std::env::current_dir().map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("unable to determine the current directory: {}", e),
+        )
+    })
+}
+
+// Read a file (relative to the root of the cargo workspace) at expansion time, returning its
+// contents along with the absolute path so that callers can include it for rebuild tracking.
+pub(crate) fn read_fixture_file(span: Span, path: &str) -> syn::Result<(String, String)> {
+    let err = |e: io::Error| {
+        Error::new(
+            span,
+            format!("Error loading test cases from {}: {}", path, e),
+        )
+    };
+    let root = current_dir().map_err(err)?;
+    let abs_path = root.join(expand_env(path).map_err(|e| Error::new(span, e))?);
+    let raw = std::fs::read_to_string(&abs_path)
+        .map_err(io_context("read", &abs_path))
+        .map_err(err)?;
+
+    Ok((raw, abs_path.to_string_lossy().into_owned()))
+}
+
+// A 32-bit FNV-1a hash that is stable across compiler versions and platforms (unlike the hashers
+// provided by the standard library)
+pub(crate) fn fnv1a(bytes: impl AsRef<[u8]>) -> u32 {
+    bytes.as_ref().iter().fold(0x811c9dc5_u32, |hash, b| {
+        (hash ^ u32::from(*b)).wrapping_mul(0x01000193)
+    })
+}
+
+// A short hex encoded hash for disambiguating names
+pub(crate) fn stable_hash(bytes: impl AsRef<[u8]>) -> String {
+    format!("{:08x}", fnv1a(bytes))
+}
+
+// String arguments are passed a value directly and anything else is parsed from the value using
+// its FromStr implementation when the test is run, panicking with a message naming the source of
+// the value if it is invalid.
+pub(crate) fn from_str_value(source: &str, value: &str, ty: &Type) -> proc_macro2::TokenStream {
+    let str_ty: Type = parse_quote!(&str);
+    let string_ty: Type = parse_quote!(String);
+
+    if *ty == str_ty {
+        return quote! { #value };
+    } else if *ty == string_ty {
+        return quote! { #value.to_string() };
+    }
+
+    quote! {
+        <#ty as ::std::str::FromStr>::from_str(#value).unwrap_or_else(|e| {
+            panic!("unable to parse the value of {} ({:?}): {}", #source, #value, e)
+        })
+    }
+}
+
+// Cases with too many arguments point at the extra arguments so that they don't need to be found
+// by counting, while cases with too few arguments fall back to the given span (typically the case
+// name).
+pub(crate) fn wrong_arg_count(
+    span: Span,
+    args: &Punctuated<Expr, Token![,]>,
+    expected: usize,
+) -> Error {
+    let found = args.len();
+    let msg = format!(
+        "wrong number of arguments: expected {} but found {}",
+        expected, found
+    );
+
+    if found > expected {
+        let extra: Vec<_> = args.iter().skip(expected).collect();
+        let n = extra.len();
+        let s = if n == 1 { "" } else { "s" };
+        Error::new_spanned(
+            quote! { #(#extra),* },
+            format!("{} (remove {} argument{})", msg, n, s),
+        )
+    } else {
+        let n = expected - found;
+        let s = if n == 1 { "" } else { "s" };
+        Error::new(span, format!("{} (missing {} argument{})", msg, n, s))
+    }
+}
+
+// The statement binding a case argument to a parameter is spanned on the argument so that errors
+// reported against the statement as a whole point at the case rather than at the attribute. The
+// trailing semicolon takes the span of the last token of the argument so that suggestions made at
+// the end of the statement (such as using `let...else`) are placed after the argument. Test
+// functions may deliberately ignore some of their arguments, so the bindings may go unused.
+pub(crate) fn case_binding(pat: &Pat, ty: &Type, val: &Expr) -> proc_macro2::TokenStream {
+    let end = val
+        .to_token_stream()
+        .into_iter()
+        .last()
+        .map_or_else(|| val.span(), |tt| tt.span());
+    let semi = quote_spanned!(end=> ;);
+
+    quote_spanned! { val.span()=>
+        #[allow(unused_variables)]
+        let #pat: #ty = #val #semi
+    }
+}
+
+// Render a list of values as an argument of the given type: a Vec is built using vec!, a slice
+// reference borrows an array and anything else (typically a fixed size array) is given the array
+// itself.
+pub(crate) fn sequence_arg<'a>(
+    values: impl IntoIterator<Item = &'a Expr>,
+    ty: &Type,
+) -> proc_macro2::TokenStream {
+    let values = values.into_iter();
+
+    match ty {
+        Type::Reference(r) if matches!(*r.elem, Type::Slice(_)) => quote! { &[#(#values),*] },
+        Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Vec") => {
+            quote! { vec![#(#values),*] }
+        }
+        _ => quote! { [#(#values),*] },
+    }
+}
+
+// Parse a human readable size such as "512", "64KB" or "1.5 MB" into a number of bytes. Units are
+// case insensitive and treated as powers of 1024.
+pub(crate) fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (n, unit) = s.split_at(split);
+    let n: f64 = n
+        .parse()
+        .map_err(|_| format!("invalid size '{}': expected a number followed by a unit", s))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        other => {
+            return Err(format!(
+                "invalid size unit '{}': expected B, KB, MB or GB",
+                other
+            ))
+        }
+    };
+
+    Ok((n * multiplier as f64) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -30,4 +380,132 @@ mod tests {
     fn punctuation() {
         assert_eq!(&slugify_path("some-file_path.txt"), "some_file_path_txt");
     }
+
+    #[test]
+    fn keywords_are_made_into_valid_idents() {
+        let ident = |s| slug_ident(s, Span::call_site()).to_string();
+
+        assert_eq!(ident("json"), "json");
+        assert_eq!(ident("in"), "r#in");
+        assert_eq!(ident("self"), "self_");
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("64KB"), Ok(64 * 1024));
+        assert_eq!(parse_size("1.5 mb"), Ok(3 * 512 * 1024));
+        assert_eq!(parse_size("2GiB"), Ok(2 * 1024 * 1024 * 1024));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("10 parsecs").is_err());
+    }
+
+    #[test]
+    fn workspace_root_defaults_to_the_manifest_dir() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+        assert_eq!(workspace_root(), Ok(manifest_dir.clone()));
+        assert_eq!(
+            expand_env("${WORKSPACE_ROOT}/tests"),
+            Ok(format!("{}/tests", manifest_dir))
+        );
+    }
+
+    #[test]
+    fn env_vars_are_expanded() {
+        let name = std::env::var("CARGO_PKG_NAME").unwrap();
+
+        assert_eq!(
+            expand_env("$CARGO_PKG_NAME/a/${CARGO_PKG_NAME}/$$").unwrap(),
+            format!("{}/a/{}/$", name, name)
+        );
+    }
+
+    #[test]
+    fn missing_env_vars_are_an_error() {
+        assert!(expand_env("$SIMPLE_TEST_CASE_NOT_SET/data").is_err());
+        assert!(expand_env("${}/data").is_err());
+    }
+
+    #[test]
+    fn io_errors_name_the_operation_and_path() {
+        let path = Path::new("tests/does_not_exist");
+        let e = std::fs::read_dir(path)
+            .map_err(io_context("read directory", path))
+            .unwrap_err();
+
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+        assert!(
+            e.to_string()
+                .starts_with("unable to read directory tests/does_not_exist: "),
+            "{}",
+            e
+        );
+    }
+
+    #[test]
+    fn extra_arguments_are_counted() {
+        let args: Punctuated<Expr, Token![,]> = parse_quote!(1, 2, 3);
+
+        assert_eq!(
+            wrong_arg_count(Span::call_site(), &args, 1).to_string(),
+            "wrong number of arguments: expected 1 but found 3 (remove 2 arguments)"
+        );
+        assert_eq!(
+            wrong_arg_count(Span::call_site(), &args, 4).to_string(),
+            "wrong number of arguments: expected 4 but found 3 (missing 1 argument)"
+        );
+    }
+
+    #[test]
+    fn raw_param_names_are_unraw() {
+        let _fn: ItemFn = parse_quote! { fn example(r#type: &str, n: usize) {} };
+        let names: Vec<_> = param_names_and_types(&_fn, "json_cases")
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        assert_eq!(names, vec!["type", "n"]);
+    }
+
+    #[test]
+    fn param_patterns_are_an_error() {
+        let _fn: ItemFn = parse_quote! { fn example((a, b): (u8, u8)) {} };
+
+        match param_names_and_types(&_fn, "csv_cases") {
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "csv_cases test function arguments must be simple identifiers"
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn identifiers_are_converted_to_snake_case() {
+        for (ident, expected) in [
+            ("TestHTTP2Frames", "test_http2_frames"),
+            ("parseJSON", "parse_json"),
+            ("HTTPServer", "http_server"),
+            ("Already_Snake", "already_snake"),
+            ("snake_case_2", "snake_case_2"),
+        ] {
+            assert_eq!(snake_case(ident), expected);
+        }
+    }
+
+    #[test]
+    fn case_bindings_bind_the_argument() {
+        let (pat, ty, val): (Pat, Type, Expr) = (
+            parse_quote!((a, b)),
+            parse_quote!((u8, u8)),
+            parse_quote!((1, 2)),
+        );
+
+        assert_eq!(
+            case_binding(&pat, &ty, &val).to_string(),
+            quote!(#[allow(unused_variables)] let (a, b): (u8, u8) = (1, 2);).to_string()
+        );
+    }
 }
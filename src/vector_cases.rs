@@ -0,0 +1,262 @@
+use crate::util::{
+    case_attr, from_str_value, param_names_and_types, read_fixture_file, sequence_arg,
+    slugify_label,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Error, Expr, ItemFn, LitStr, Result, Type, TypeArray, TypePath,
+};
+
+// A single block of `Key = value` lines from a test vector file, along with the parameters of the
+// `[section]` it appears in
+#[derive(Debug, PartialEq, Eq)]
+struct Vector {
+    line: usize,
+    section: String,
+    fields: Vec<(String, String)>,
+}
+
+fn key_value(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim().to_string(), value.trim().to_string()))
+}
+
+// Files are made up of blocks of `Key = value` lines separated by blank lines, optionally grouped
+// into sections by `[name]` or `[Key = value]` headers (with several headers in a row forming a
+// single section). Lines starting with `#` are comments.
+fn parse_vectors(raw: &str) -> std::result::Result<Vec<Vector>, String> {
+    let mut vectors = Vec::new();
+    let mut section: Vec<String> = Vec::new();
+    let mut section_fields: Vec<(String, String)> = Vec::new();
+    let mut in_headers = false;
+    let mut current: Option<Vector> = None;
+
+    for (i, line) in raw.lines().enumerate() {
+        let n = i + 1;
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.is_empty() {
+            vectors.extend(current.take());
+            in_headers = false;
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: unterminated section header", n))?;
+            vectors.extend(current.take());
+            if !in_headers {
+                section.clear();
+                section_fields.clear();
+                in_headers = true;
+            }
+            section.push(header.trim().to_string());
+            section_fields.extend(key_value(header));
+            continue;
+        }
+
+        in_headers = false;
+        let (key, value) = key_value(trimmed)
+            .ok_or_else(|| format!("line {}: expected `Key = value` but found {:?}", n, trimmed))?;
+        let vector = current.get_or_insert_with(|| Vector {
+            line: n,
+            section: section.join(" "),
+            fields: section_fields.clone(),
+        });
+        vector.fields.retain(|(k, _)| !k.eq_ignore_ascii_case(&key));
+        vector.fields.push((key, value));
+    }
+    vectors.extend(current);
+
+    Ok(vectors)
+}
+
+fn decode_hex(s: &str) -> std::result::Result<Vec<u8>, String> {
+    if s.len() % 2 == 1 {
+        return Err(format!("odd number of hex digits in {:?}", s));
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| format!("invalid hex string {:?}", s))
+        })
+        .collect()
+}
+
+// Hex encoded values are decoded for &[u8], Vec<u8> and [u8; N] arguments
+fn is_bytes(ty: &Type) -> bool {
+    let is_u8 = |ty: &Type| matches!(ty, Type::Path(TypePath { path, .. }) if path.is_ident("u8"));
+
+    match ty {
+        Type::Reference(r) => matches!(&*r.elem, Type::Slice(s) if is_u8(&s.elem)),
+        Type::Array(TypeArray { elem, .. }) => is_u8(elem),
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| {
+            s.ident == "Vec"
+                && matches!(
+                    &s.arguments,
+                    syn::PathArguments::AngleBracketed(args)
+                        if matches!(args.args.first(), Some(syn::GenericArgument::Type(t)) if is_u8(t))
+                )
+        }),
+        _ => false,
+    }
+}
+
+// Fields are passed to the argument with the same name (ignoring case) so that `Msg` and `MD` are
+// given to `msg` and `md`. Fields that are not used by the test function are ignored.
+fn vector_attr(
+    span: Span,
+    path: &str,
+    n: usize,
+    vector: &Vector,
+    params: &[(String, Type)],
+) -> Result<proc_macro2::TokenStream> {
+    let err = |msg: String| Error::new(span, format!("{} line {}: {}", path, vector.line, msg));
+    let mut args = Vec::with_capacity(params.len());
+
+    for (param, ty) in params.iter() {
+        let (key, value) = vector
+            .fields
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(param))
+            .ok_or_else(|| err(format!("no value for '{}'", param)))?;
+
+        if is_bytes(ty) {
+            let bytes: Vec<Expr> = decode_hex(value)
+                .map_err(|e| err(format!("{}: {}", key, e)))?
+                .into_iter()
+                .map(|b| parse_quote!(#b))
+                .collect();
+            args.push(sequence_arg(&bytes, ty));
+        } else {
+            let source = format!("{} in {} line {}", key, path, vector.line);
+            args.push(from_str_value(&source, value, ty));
+        }
+    }
+
+    let case = [slugify_label(&vector.section), format!("vector_{}", n)]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    Ok(case_attr(&args, &case))
+}
+
+pub(crate) fn inner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(args as LitStr);
+    let original = parse_macro_input!(input as ItemFn);
+    let span = path.span();
+    let path = path.value();
+
+    let res = param_names_and_types(&original, "vector_cases").and_then(|params| {
+        let (raw, abs_path) = read_fixture_file(span, &path)?;
+        let vectors = parse_vectors(&raw)
+            .map_err(|e| Error::new(span, format!("invalid test vector file {}: {}", path, e)))?;
+        if vectors.is_empty() {
+            return Err(Error::new(
+                span,
+                format!("no test vectors found in {}", path),
+            ));
+        }
+
+        let case_attrs = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| vector_attr(span, &path, i + 1, v, &params))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            // Ensure that we are recompiled if the file changes
+            const _: &str = include_str!(#abs_path);
+
+            #(#case_attrs)*
+            #original
+        })
+    });
+
+    match res {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.into_compile_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &str = "\
+#  CAVS 11.0
+#  \"SHA-256 ShortMsg\" information
+
+[L = 32]
+
+Len = 0
+Msg = 00
+MD = e3b0c442
+
+Len = 8
+Msg = d3
+MD = 28969cdf
+
+[ENCRYPT]
+[KEYLEN = 128]
+COUNT = 0
+KEY = 00ff
+";
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn vectors_are_parsed_with_their_section() {
+        let vectors = parse_vectors(RAW).unwrap();
+
+        assert_eq!(vectors.len(), 3);
+        assert_eq!(vectors[1].line, 10);
+        assert_eq!(vectors[1].section, "L = 32");
+        assert_eq!(
+            vectors[1].fields,
+            fields(&[("L", "32"), ("Len", "8"), ("Msg", "d3"), ("MD", "28969cdf")])
+        );
+        assert_eq!(vectors[2].section, "ENCRYPT KEYLEN = 128");
+        assert_eq!(
+            vectors[2].fields,
+            fields(&[("KEYLEN", "128"), ("COUNT", "0"), ("KEY", "00ff")])
+        );
+    }
+
+    #[test]
+    fn invalid_files_are_an_error() {
+        assert!(parse_vectors("[L = 32\nMsg = 00\n").is_err());
+        assert!(parse_vectors("Msg 00\n").is_err());
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+        assert_eq!(decode_hex("00fF"), Ok(vec![0, 255]));
+    }
+
+    #[test]
+    fn byte_types_are_detected() {
+        let bytes: Vec<Type> = vec![
+            parse_quote!(&[u8]),
+            parse_quote!(Vec<u8>),
+            parse_quote!([u8; 4]),
+        ];
+        let other: Vec<Type> = vec![parse_quote!(&str), parse_quote!(Vec<u16>), parse_quote!(u8)];
+
+        assert!(bytes.iter().all(is_bytes));
+        assert!(!other.iter().any(is_bytes));
+    }
+}
@@ -0,0 +1,55 @@
+use simple_test_case::archive_cases;
+
+#[archive_cases("tests/fixtures.tar")]
+#[test]
+fn tar_entries(path: &str, contents: &str) {
+    assert!(path.starts_with("fixtures/"));
+    assert!(contents.starts_with('{'));
+}
+
+#[archive_cases("tests/fixtures.tar")]
+#[test]
+fn tar_entries_as_bytes(path: &str, contents: &[u8]) {
+    assert!(path.ends_with(".json"));
+    assert_eq!(contents.last(), Some(&b'\n'));
+}
+
+#[cfg(feature = "gzip")]
+#[archive_cases("tests/fixtures.tar.gz")]
+#[test]
+fn compressed_tar_entries(path: &str, contents: &[u8]) {
+    assert!(path.ends_with(".json"));
+    assert_eq!(contents.last(), Some(&b'\n'));
+}
+
+#[cfg(feature = "gzip")]
+#[archive_cases("tests/fixtures_deflated.zip")]
+#[test]
+fn deflated_zip_entries(path: &str, contents: &str) {
+    match path {
+        "fixtures/one.json" => assert_eq!(contents, "{\"a\": 1}\n"),
+        _ => assert!(contents.starts_with("{\"items\": [0, 1, 2,")),
+    }
+}
+
+#[archive_cases("tests/fixtures.zip")]
+#[test]
+fn zip_entries(contents: &str) {
+    assert!(contents.trim_end().ends_with('}'));
+}
+
+#[archive_cases("tests/fixtures.zip")]
+#[test]
+fn archives_match(path: &str, contents: &str) {
+    let expected = match path {
+        "fixtures/one.json" => "{\"a\": 1}\n".to_string(),
+        _ => format!(
+            "{{\"items\": [{}]}}\n",
+            (0..500)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    assert_eq!(contents, expected);
+}
@@ -0,0 +1,34 @@
+use simple_test_case::async_test_case;
+
+async fn double(n: usize) -> usize {
+    n * 2
+}
+
+#[async_test_case(1, 2; "small")]
+#[async_test_case(100, 200; "large")]
+async fn default_runtime(a: usize, b: usize) {
+    assert_eq!(double(a).await, b)
+}
+
+#[async_test_case(1, 2; "small"; runtime = tokio_multi_thread)]
+#[async_test_case(100, 200; "large"; ignore)]
+async fn multi_thread_runtime(a: usize, b: usize) {
+    assert_eq!(double(a).await, b)
+}
+
+#[async_test_case(1, 2; "simple passing")]
+#[async_test_case(100, 200; "other simple passing"; runtime = tokio)]
+async fn returning_a_result(a: usize, b: usize) -> Result<(), (usize, usize)> {
+    let doubled = double(a).await;
+    if doubled != b {
+        Err((doubled, b))
+    } else {
+        Ok(())
+    }
+}
+
+#[async_test_case(1, 2; "small")]
+#[async_test_case(1, 3; "wrong"; #[should_panic])]
+async fn per_case_attributes(a: usize, b: usize) {
+    assert_eq!(double(a).await, b)
+}
@@ -0,0 +1,22 @@
+use simple_test_case::boundary_cases;
+
+#[boundary_cases]
+#[test]
+fn checked_add_never_panics(a: u8, b: i16) {
+    let _ = (a as i16).checked_add(b);
+}
+
+#[boundary_cases(skip = [nan])]
+#[test]
+fn floats(x: f64) {
+    assert!(x.abs() >= 0.0);
+}
+
+#[boundary_cases(n, skip = [max])]
+#[simple_test_case::test_case("hello"; "ascii")]
+#[simple_test_case::test_case("héllo"; "unicode")]
+#[test]
+fn explicit_cases_are_crossed(s: &str, n: usize) {
+    let truncated: String = s.chars().take(n).collect();
+    assert!(truncated.chars().count() <= n);
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "case_names")]
+use simple_test_case::test_case;
+
+#[test_case(1; "one")]
+#[test_case(2; "two"; module = even)]
+#[test_case(3; "three"; tags = [odd])]
+#[test]
+fn numbered(n: u8) {
+    assert!(n > 0);
+}
+
+#[test]
+fn case_names_are_listed() {
+    assert_eq!(
+        numbered::CASE_NAMES,
+        &["one", "even::two", "tag_odd::three"]
+    );
+}
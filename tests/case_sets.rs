@@ -0,0 +1,33 @@
+use simple_test_case::{define_cases, use_cases};
+
+fn double(n: usize) -> usize {
+    n * 2
+}
+
+mod sets {
+    use simple_test_case::define_cases;
+
+    define_cases!(large, [(1_000, 2_000) => "thousand", (1_000_000, 2_000_000) => "million"]);
+}
+
+define_cases!(small, [(1, 2) => "one", (2, 4) => "two"]);
+
+#[use_cases(small)]
+#[test]
+fn it_works(n: usize, expected: usize) {
+    assert_eq!(double(n), expected);
+}
+
+#[use_cases(small, sets::large)]
+#[test]
+fn it_works_with_multiple_sets(n: usize, expected: usize) {
+    assert_eq!(double(n), expected);
+}
+
+#[use_cases(small)]
+#[use_cases(sets::large)]
+#[simple_test_case::test_case(0, 0; "zero")]
+#[tokio::test]
+async fn it_works_with_stacked_attributes(n: usize, expected: usize) {
+    assert_eq!(double(n), expected);
+}
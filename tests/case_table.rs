@@ -0,0 +1,40 @@
+use simple_test_case::TestCases;
+
+#[allow(dead_code)]
+#[derive(TestCases)]
+#[case_table(rows = PARSE_CASES, run = check_parse, name = input)]
+struct ParseCase {
+    input: &'static str,
+    expected: Option<i64>,
+}
+
+const PARSE_CASES: &[ParseCase] = &[
+    ParseCase {
+        input: "42",
+        expected: Some(42),
+    },
+    ParseCase {
+        input: "-1",
+        expected: Some(-1),
+    },
+    ParseCase {
+        input: "nope",
+        expected: None,
+    },
+];
+
+fn check_parse(case: &ParseCase) {
+    assert_eq!(case.input.parse().ok(), case.expected);
+}
+
+#[derive(TestCases)]
+#[case_table(rows = DOUBLES, run = Double::check)]
+struct Double(usize, usize);
+
+static DOUBLES: [Double; 3] = [Double(1, 2), Double(2, 4), Double(3, 6)];
+
+impl Double {
+    fn check(&self) {
+        assert_eq!(self.0 * 2, self.1);
+    }
+}
@@ -59,3 +59,78 @@ async fn async_should_panic(a: usize, b: usize) {
     assert_eq!(double(a), b);
     panic!("should panic here");
 }
+
+#[test_case(1, 2; "small")]
+#[test_case(100, 200; "large"; ignore)]
+#[test_case(1, 3; "wrong"; #[should_panic])]
+#[test]
+fn per_case_attributes(a: usize, b: usize) {
+    assert_eq!(double(a), b)
+}
+
+#[test_case(1, 2; "everywhere"; skip_miri)]
+#[test_case(1, 3; "only under miri"; miri_only)]
+#[test]
+fn miri_case_subsets(a: usize, b: usize) {
+    assert_eq!(double(a), b)
+}
+
+#[test_case(1, 2; "small")]
+#[test_case(0, 0; "zero"; module = edge_cases)]
+#[test_case(usize::MAX / 2, usize::MAX - 1; "max"; module = edge_cases::large)]
+#[test]
+fn nested_modules(a: usize, b: usize) {
+    assert_eq!(double(a), b)
+}
+
+#[test_case(1, 2; "small")]
+#[test_case(100, 200; "large"; tags = [slow])]
+#[test_case(0, 0; "zero"; module = edge_cases, tags = [slow, fast])]
+#[test]
+fn tagged_cases(a: usize, b: usize) {
+    assert_eq!(double(a), b)
+}
+
+// Statements in the body of a case keep their original spans
+const ASSERT_LINE: u32 = line!() + 6;
+
+#[test_case(1; "first")]
+#[test_case(2; "second")]
+#[test]
+fn statement_spans_are_preserved(n: u32) {
+    assert_eq!(line!(), ASSERT_LINE, "case {}", n);
+}
+
+// Lint levels on the test function cover everything generated for it
+#[test_case(1; "one")]
+#[test]
+#[deny(unused_variables)]
+#[allow(clippy::identity_op)]
+fn lint_levels_are_respected(n: usize) {
+    assert_eq!(n * 1, 1);
+}
+
+// Arguments that the test function ignores are not reported as unused, and lint levels set using
+// cfg_attr are respected in the same way as those set directly
+#[test_case(1, "one"; "one")]
+#[test_case(2, "two"; "two")]
+#[test]
+#[cfg_attr(test, deny(unused_variables))]
+fn ignored_arguments_are_allowed(n: usize, label: &str) {
+    assert!(n > 0);
+}
+
+// Test functions that aren't snake_case don't trigger non_snake_case on the generated module
+#[test_case(2, 1; "first")]
+#[test]
+fn TestHTTP2Frames(frames: usize, streams: usize) {
+    assert!(frames > streams);
+}
+
+// The generated module is removed along with the cases when the test is disabled by a cfg
+#[test_case(1; "one")]
+#[test]
+#[cfg(any())]
+fn disabled_by_cfg(n: usize) {
+    assert_eq!(n, 2);
+}
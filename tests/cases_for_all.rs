@@ -0,0 +1,45 @@
+use simple_test_case::{cases_for_all, define_cases};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Memory,
+    Disk,
+}
+
+impl Backend {
+    fn round_trip(&self, s: &str) -> String {
+        s.to_string()
+    }
+}
+
+define_cases!(backends, [(Backend::Memory) => "memory", (Backend::Disk) => "disk"]);
+define_cases!(extra, [(Backend::Memory) => "memory_again"]);
+
+#[cases_for_all(backends)]
+mod storage {
+    use super::*;
+
+    fn check(backend: Backend) {
+        assert_eq!(backend.round_trip("data"), "data");
+    }
+
+    #[test]
+    fn round_trip(backend: Backend) {
+        check(backend);
+    }
+
+    #[simple_test_case::use_cases(extra)]
+    #[test]
+    fn round_trip_with_extra_cases(backend: Backend) {
+        check(backend);
+    }
+
+    mod nested {
+        use super::*;
+
+        #[tokio::test]
+        async fn async_round_trip(backend: Backend) {
+            check(backend);
+        }
+    }
+}
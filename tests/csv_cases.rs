@@ -0,0 +1,24 @@
+use simple_test_case::csv_cases;
+
+fn double(n: usize) -> usize {
+    n * 2
+}
+
+#[csv_cases("tests/vectors.csv", name = "description")]
+#[test]
+fn by_name(input: usize, expected: usize) {
+    assert_eq!(double(input), expected)
+}
+
+#[csv_cases("tests/vectors.csv", name = "description")]
+#[test]
+fn by_position(a: usize, b: usize) {
+    assert_eq!(double(a), b)
+}
+
+#[csv_cases("tests/vectors.csv")]
+#[test]
+fn with_strings(description: &str, input: usize, expected: String) {
+    assert!(!description.is_empty());
+    assert_eq!(double(input).to_string(), expected)
+}
@@ -36,3 +36,406 @@ async fn it_async_works(_path: &str, contents: &str) -> anyhow::Result<()> {
     assert_eq!(double(n), expected);
     Ok(())
 }
+
+simple_test_case::dir_fixtures!(mod fixtures = "tests/test_data");
+
+#[dir_cases("tests/test_data", fixtures = crate::fixtures)]
+#[test]
+fn it_works_with_shared_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[test]
+fn shared_fixtures_are_keyed_by_path() {
+    assert_eq!(fixtures::TESTS_TEST_DATA_ONE_TXT.trim(), "1:2");
+}
+
+#[dir_cases("tests/test_data_mixed", extensions = ["case"])]
+#[test]
+fn it_works_with_extension_filtering(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", strip_extension = true, strip_prefix = true)]
+#[test]
+fn it_works_with_short_names(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data_nested", recursive = true, strip_prefix = true)]
+#[test]
+fn it_works_recursively(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", matching = r"^(one|six)\.txt$")]
+#[test]
+fn it_works_with_matching(path: &str, contents: &str) -> anyhow::Result<()> {
+    assert!(!path.ends_with("kb.txt"));
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", max_size = "5B", oversized = "ignore")]
+#[test]
+fn it_ignores_oversized_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+// Oversized fixtures are read when the test is run, so the segments are taken from owned contents
+#[dir_cases(
+    "tests/test_data_split",
+    split = "\n---\n",
+    max_size = "5B",
+    oversized = "ignore"
+)]
+#[test]
+fn it_splits_oversized_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data_nested", recursive = true, nested = true)]
+#[test]
+fn it_works_with_nested_modules(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("$CARGO_MANIFEST_DIR/tests/test_data", strip_prefix = true)]
+#[test]
+fn it_works_with_env_vars(path: &str, contents: &str) -> anyhow::Result<()> {
+    assert!(path.starts_with(env!("CARGO_MANIFEST_DIR")));
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", args = (2, "extra"))]
+#[test]
+fn it_works_with_extra_args(
+    _path: &str,
+    contents: &str,
+    factor: usize,
+    label: &str,
+) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(label, "extra");
+    assert_eq!(n * factor, expected);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Strict,
+    Lenient,
+}
+
+#[dir_cases("tests/test_data", strip_prefix = true, each = [strict = Mode::Strict, lenient = Mode::Lenient])]
+#[test]
+fn it_works_with_each(_path: &str, contents: &str, mode: Mode) -> anyhow::Result<()> {
+    let (n, expected) = match mode {
+        Mode::Strict => parse_test_file(contents)?,
+        Mode::Lenient => parse_test_file(contents.trim())?,
+    };
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", stem = true)]
+#[test]
+fn it_works_with_stem(path: &str, stem: &str, contents: &str) -> anyhow::Result<()> {
+    assert!(path.ends_with(&format!("{}.txt", stem)));
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data_ignored", ignore_matching = "^_")]
+#[test]
+fn it_ignores_by_file_name(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+fn parse_or_panic(contents: &str) -> (usize, usize) {
+    parse_test_file(contents).expect("invalid test file")
+}
+
+#[dir_cases("tests/test_data", parse_with = parse_or_panic)]
+#[test]
+fn it_works_with_parse_with(_path: &str, (n, expected): (usize, usize)) {
+    assert_eq!(double(n), expected);
+}
+
+#[dir_cases("tests/test_data_ignored", matching = "^_", parse_with = parse_or_panic)]
+#[test]
+#[should_panic(expected = "unable to parse test fixture tests/test_data_ignored/_broken.txt")]
+fn parse_with_failures_name_the_fixture(_path: &str, _parsed: (usize, usize)) {}
+
+#[dir_cases(tagged(first = "tests/test_data", second = "tests/test_data_2"))]
+#[test]
+fn it_works_with_tagged_dirs(tag: &str, path: &str, contents: &str) -> anyhow::Result<()> {
+    match tag {
+        "first" => assert!(path.starts_with("tests/test_data/")),
+        "second" => assert!(path.starts_with("tests/test_data_2/")),
+        other => panic!("unexpected tag: {}", other),
+    }
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases(manifest = "tests/cases.list")]
+#[test]
+fn it_works_with_a_manifest(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", sample = 2, sample_unless = cfg(not(debug_assertions)))]
+#[test]
+fn it_samples_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", strip_prefix = true, prefix = "first")]
+#[dir_cases("tests/test_data_2", strip_prefix = true, prefix = "second")]
+#[test]
+fn it_works_with_stacked_attributes(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", workspace_root = true)]
+#[test]
+fn it_works_relative_to_the_workspace_root(path: &str, contents: &str) -> anyhow::Result<()> {
+    assert!(path.starts_with(env!("CARGO_MANIFEST_DIR")));
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data_nested", recursive = true, check_drift = true)]
+#[test]
+fn it_checks_for_drift(_path: &str, contents: &str) {
+    assert!(!contents.is_empty());
+}
+
+// README.md is not a fixture but it is part of the listing checked for drift
+#[dir_cases("tests/test_data_mixed", extensions = ["case"], check_drift = true)]
+#[test]
+fn it_checks_for_drift_with_non_fixture_files(_path: &str, contents: &str) {
+    assert!(!contents.is_empty());
+}
+
+#[dir_cases(
+    "tests/test_data_common",
+    recursive = true,
+    common = "_common.txt",
+    check_drift = true
+)]
+#[test]
+fn it_checks_for_drift_with_common_files(_path: &str, contents: &str, common: &str) {
+    assert!(!contents.is_empty());
+    assert!(!common.is_empty());
+}
+
+#[dir_cases("tests/test_data_common", recursive = true, common = "_common.txt")]
+#[test]
+fn it_works_with_a_common_file(_path: &str, contents: &str, common: &str) -> anyhow::Result<()> {
+    assert_eq!(common.trim(), "multiplier:2");
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Doubling {
+    n: usize,
+    expected: usize,
+}
+
+impl std::str::FromStr for Doubling {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (n, expected) = parse_test_file(s)?;
+
+        Ok(Self { n, expected })
+    }
+}
+
+#[dir_cases("tests/test_data")]
+#[test]
+fn it_works_with_from_str_contents(_path: &str, case: Doubling) {
+    assert_eq!(double(case.n), case.expected);
+}
+
+#[dir_cases("tests/test_data_ignored", matching = "^_")]
+#[test]
+#[should_panic(expected = "unable to parse test fixture tests/test_data_ignored/_broken.txt")]
+fn from_str_failures_name_the_fixture(_path: &str, _case: Doubling) {}
+
+#[dir_cases("tests/test_data", stem = true)]
+#[test]
+fn it_works_with_owned_strings(
+    path: String,
+    stem: String,
+    mut contents: String,
+) -> anyhow::Result<()> {
+    assert!(path.contains(&stem));
+    contents.truncate(contents.trim_end().len());
+    let (n, expected) = parse_test_file(&contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data_split", split = "\n---\n")]
+#[test]
+fn it_works_with_split_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases(
+    "tests/test_data_split_front_matter",
+    front_matter = [factor],
+    split = "%%\n"
+)]
+#[test]
+fn it_splits_the_body_following_front_matter(
+    _path: &str,
+    contents: &str,
+    factor: usize,
+) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(n * factor, expected);
+    Ok(())
+}
+
+#[dir_cases("tests/test_data", ignore_over = "5B")]
+#[test]
+fn it_ignores_fixtures_over_a_size(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[dir_cases(
+    "tests/test_data_mixed",
+    group_by_extension = true,
+    strip_prefix = true
+)]
+#[test]
+fn it_groups_cases_by_extension(path: &str, contents: &str) {
+    assert!(path.ends_with(".case") || path.ends_with(".md"));
+    assert!(!contents.is_empty());
+}
+
+#[dir_cases("tests/test_data_front_matter", front_matter = [n, expected])]
+#[test]
+fn it_works_with_front_matter(_path: &str, contents: &str, n: usize, expected: usize) {
+    assert_eq!(contents.trim(), format!("{} doubled", n));
+    assert_eq!(double(n), expected, "assertion");
+}
+
+#[dir_cases("tests/test_data_nested", recursive = true, path_arg = "name")]
+#[test]
+fn it_passes_paths_relative_to_the_directory(path: &str, contents: &str) {
+    assert!(!path.starts_with("tests/"), "{}", path);
+    assert_eq!(
+        std::fs::read_to_string(format!("tests/test_data_nested/{}", path)).unwrap(),
+        contents
+    );
+}
+
+#[dir_cases("tests/test_data", path_arg = "absolute")]
+#[test]
+fn it_passes_absolute_paths(path: &str, contents: &str) {
+    assert!(std::path::Path::new(path).is_absolute(), "{}", path);
+    assert_eq!(std::fs::read_to_string(path).unwrap(), contents);
+}
+
+#[cfg(feature = "gzip")]
+#[dir_cases("tests/test_data_gzip")]
+#[test]
+fn it_decompresses_gzip_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[dir_cases("tests/test_data_gzip")]
+#[test]
+fn it_decompresses_gzip_fixtures_as_bytes(_path: &str, contents: &[u8]) {
+    assert_eq!(contents, b"3:6\n");
+}
+
+#[cfg(feature = "yaml")]
+#[dir_cases("tests/test_data_formats", extensions = ["yaml"], format = "yaml")]
+#[test]
+fn it_deserializes_yaml_fixtures(_path: &str, case: Doubling) {
+    assert_eq!(double(case.n), case.expected);
+}
+
+#[cfg(feature = "toml")]
+#[dir_cases("tests/test_data_formats", extensions = ["toml"], format = "toml")]
+#[test]
+fn it_deserializes_toml_fixtures(_path: &str, case: Doubling) {
+    assert_eq!(double(case.n), case.expected);
+}
+
+#[cfg(feature = "zstd")]
+#[dir_cases("tests/test_data_zstd")]
+#[test]
+fn it_decompresses_zstd_fixtures(_path: &str, contents: &str) -> anyhow::Result<()> {
+    let (n, expected) = parse_test_file(contents)?;
+
+    assert_eq!(double(n), expected);
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use simple_test_case::dir_pair_cases;
+
+fn double(n: usize) -> usize {
+    n * 2
+}
+
+#[dir_pair_cases("tests/test_data_pairs")]
+#[test]
+fn it_works(path: &str, input: &str, expected: &str) -> anyhow::Result<()> {
+    assert!(path.ends_with(".in"));
+    let n: usize = input.trim().parse()?;
+
+    assert_eq!(double(n).to_string(), expected.trim());
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use simple_test_case::env_cases;
+
+// Cargo sets CARGO_PKG_VERSION_{MAJOR,MINOR,PATCH,PRE} when compiling the tests
+#[env_cases("CARGO_PKG_VERSION_")]
+#[test]
+fn version_parts(part: &str) {
+    assert!(part.is_empty() || part.parse::<u32>().is_ok());
+}
+
+#[env_cases("CARGO_PKG_VERSION_MAJOR")]
+#[test]
+fn prefixes_matching_the_full_name_are_skipped(major: u32) {
+    assert!(major < 100);
+}
+
+#[env_cases("CARGO_PKG_VERSION_MA")]
+#[test]
+fn values_are_parsed(major: u32) {
+    assert!(major < 100);
+}
@@ -0,0 +1,13 @@
+use simple_test_case::exhaustive_cases;
+
+#[exhaustive_cases]
+#[test]
+fn de_morgan(a: bool, b: bool, c: bool) {
+    assert_eq!(!(a && b && c), !a || !b || !c);
+}
+
+#[exhaustive_cases]
+#[test]
+fn single_flag(enabled: bool) {
+    assert_eq!(u8::from(enabled) == 1, enabled);
+}
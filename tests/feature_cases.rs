@@ -0,0 +1,14 @@
+use simple_test_case::feature_cases;
+
+#[feature_cases("tests/features")]
+#[test]
+fn addition(a: i64, b: i64, sum: i64) {
+    assert_eq!(a + b, sum);
+}
+
+#[feature_cases("tests/features")]
+#[test]
+fn cells_can_be_passed_as_strings(a: &str, sum: String) {
+    assert!(!a.is_empty());
+    assert!(sum.parse::<i64>().is_ok());
+}
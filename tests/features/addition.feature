@@ -0,0 +1,20 @@
+Feature: Addition
+  Adding numbers together using the calculator
+
+  Scenario Outline: adding two numbers
+    Given I have entered <a> and <b>
+    When I press add
+    Then the result should be <sum>
+
+    Examples:
+      | a | b  | sum | note      |
+      | 1 | 2  | 3   | small     |
+      | 0 | -1 | -1  | negatives |
+
+  Scenario Outline: adding zero
+    Given I have entered <a> and 0
+    Then the result should be <a>
+
+    Examples:
+      | a   | b | sum |
+      | 100 | 0 | 100 |
@@ -0,0 +1,7 @@
+Feature: Concatenation
+
+  # Numbers can be concatenated as well
+  Scenario: numbers as strings
+    Examples:
+      | a  | b | sum |
+      | 12 | 3 | 15  |
@@ -0,0 +1,79 @@
+use simple_test_case::{fixture, test_case, test_matrix};
+
+mod fixtures {
+    use simple_test_case::fixture;
+
+    #[fixture]
+    pub fn greeting() -> String {
+        "hello".to_string()
+    }
+}
+
+use fixtures::greeting;
+
+#[fixture]
+fn numbers() -> Vec<usize> {
+    vec![1, 2, 3]
+}
+
+#[test_case(0, 1; "first")]
+#[test_case(2, 3; "last")]
+#[test]
+fn it_injects_fixtures(ix: usize, expected: usize, #[fixture] numbers: Vec<usize>) {
+    assert_eq!(numbers[ix], expected);
+}
+
+#[test_case("hello world"; "imported")]
+#[test]
+fn it_injects_imported_fixtures(s: &str, #[fixture] mut greeting: String) {
+    greeting.push_str(" world");
+    assert_eq!(greeting, s);
+}
+
+#[test_case(1; "one")]
+#[test]
+fn it_injects_multiple_fixtures(
+    n: usize,
+    #[fixture] numbers: Vec<usize>,
+    #[fixture] greeting: String,
+) {
+    assert!(numbers.contains(&n));
+    assert_eq!(greeting, "hello");
+}
+
+#[test_matrix([1, 2], [true, false])]
+#[test]
+fn it_works_with_test_matrix(n: usize, flag: bool, #[fixture] numbers: Vec<usize>) {
+    assert_eq!(numbers.contains(&n), n < 4 || flag);
+}
+
+struct RemoveOnDrop(std::path::PathBuf);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[fixture(guard)]
+fn scratch_dir() -> (std::path::PathBuf, RemoveOnDrop) {
+    static N: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let n = N.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let dir = std::env::temp_dir().join(format!(
+        "simple_test_case_fixture_{}_{}",
+        std::process::id(),
+        n
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    (dir.clone(), RemoveOnDrop(dir))
+}
+
+#[test_case("a.txt"; "a")]
+#[test_case("b.txt"; "b")]
+#[test]
+fn it_holds_fixture_guards_for_the_test(name: &str, #[fixture] scratch_dir: std::path::PathBuf) {
+    assert!(scratch_dir.is_dir());
+    std::fs::write(scratch_dir.join(name), name).unwrap();
+    assert_eq!(std::fs::read_dir(&scratch_dir).unwrap().count(), 1);
+}
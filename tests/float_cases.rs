@@ -0,0 +1,20 @@
+use simple_test_case::float_cases;
+
+#[float_cases]
+#[test]
+fn classification_is_consistent(x: f64) {
+    let classes = [x.is_nan(), x.is_infinite(), x.is_finite()];
+    assert_eq!(classes.iter().filter(|&&c| c).count(), 1);
+}
+
+#[float_cases(skip = [nan, neg_nan])]
+#[test]
+fn round_trips_through_text(x: f32) {
+    assert_eq!(x.to_string().parse::<f32>().unwrap().to_bits(), x.to_bits());
+}
+
+#[float_cases(skip = [nan, neg_nan, infinity, neg_infinity, max, min])]
+#[test]
+fn subnormals_are_handled(x: f64) {
+    assert!(x.abs() <= 1.0 + f64::EPSILON);
+}
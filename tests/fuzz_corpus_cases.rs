@@ -0,0 +1,12 @@
+use simple_test_case::fuzz_corpus_cases;
+
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().map(|&b| u32::from(b)).sum()
+}
+
+#[fuzz_corpus_cases("tests/fuzz_corpus")]
+#[test]
+fn corpus_entries_are_replayed(data: &[u8]) {
+    assert!(!data.is_empty());
+    assert!(checksum(data) > 0);
+}
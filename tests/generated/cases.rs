@@ -0,0 +1,4 @@
+// Cases as a build script would write them to OUT_DIR
+(1, 2; "small"),
+(100, 200; "large"),
+(0, 1; "wrong"; #[should_panic]),
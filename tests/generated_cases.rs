@@ -0,0 +1,7 @@
+use simple_test_case::generated_cases;
+
+#[generated_cases(env!("CARGO_MANIFEST_DIR"), "tests/generated/cases.rs")]
+#[test]
+fn double(n: usize, expected: usize) {
+    assert_eq!(n * 2, expected);
+}
@@ -0,0 +1,56 @@
+use simple_test_case::{assert_golden, golden_cases, test_case};
+
+fn render(items: &[&str]) -> String {
+    items.iter().map(|s| format!("{}\n", s)).collect()
+}
+
+#[test]
+fn matching_output_passes() {
+    assert_golden!(render(&["a", "b"]), "tests/golden/lines.expected");
+}
+
+// Absolute paths are used as-is so failure cases are checked using temporary files to avoid
+// modifying the checked in golden files when running with UPDATE_GOLDEN=1
+fn temp_golden_file(name: &str, contents: Option<&str>) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("{}_{}.expected", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    if let Some(contents) = contents {
+        std::fs::write(&path, contents).unwrap();
+    }
+
+    path
+}
+
+#[test]
+#[should_panic(expected = "--- expected\n+++ actual\n  a\n- b\n+ c\n")]
+fn mismatched_output_fails() {
+    let path = temp_golden_file("mismatched", Some("a\nb\n"));
+    assert_golden!(render(&["a", "c"]), path);
+}
+
+#[test]
+#[should_panic(expected = "set UPDATE_GOLDEN=1 to create it")]
+fn missing_golden_file_fails() {
+    let path = temp_golden_file("missing", None);
+    assert_golden!("anything", path);
+}
+
+#[test_case(&["a", "b"], "lines"; "lines")]
+#[test]
+fn within_test_cases(items: &[&str], name: &str) {
+    assert_golden!(render(items), format!("tests/golden/{}.expected", name));
+}
+
+#[test_case(&["a", "b"]; "lines")]
+#[test_case(&["x"]; "single")]
+#[golden_cases("tests/golden")]
+#[test]
+fn golden_cases_are_named_after_the_case(items: &[&str]) -> String {
+    render(items)
+}
+
+#[golden_cases("tests/golden/", extension = "txt")]
+#[tokio::test]
+async fn golden_cases_without_test_cases() -> impl std::fmt::Display {
+    42
+}
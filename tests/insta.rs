@@ -0,0 +1,20 @@
+#![cfg(feature = "insta")]
+
+use simple_test_case::test_case;
+
+#[test_case("one"; "one")]
+#[test_case("two"; "two")]
+#[test]
+fn snapshot_suffix_is_the_case_name(name: &str) {
+    let settings = insta::Settings::clone_current();
+
+    assert_eq!(settings.snapshot_suffix(), Some(name));
+}
+
+#[test_case("first"; "first")]
+#[tokio::test]
+async fn snapshot_suffix_is_set_for_async_cases(name: &str) {
+    let settings = insta::Settings::clone_current();
+
+    assert_eq!(settings.snapshot_suffix(), Some(name));
+}
@@ -0,0 +1,20 @@
+use simple_test_case::json_cases;
+
+fn double(n: i64) -> i64 {
+    n * 2
+}
+
+#[json_cases("tests/vectors.json")]
+#[test]
+fn it_works(input: i64, expected: i64, digits: &[u8], label: Option<String>) {
+    assert_eq!(double(input), expected);
+    assert_eq!(digits.len(), input.abs().to_string().len());
+    assert_eq!(label.is_none(), input < 0);
+}
+
+#[json_cases("tests/vectors.json", name = "label")]
+#[test]
+fn it_works_with_a_name_field(expected: f64, digits: Vec<u64>) {
+    assert!(expected.abs() >= 2.0);
+    assert!(!digits.is_empty());
+}
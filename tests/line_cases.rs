@@ -0,0 +1,14 @@
+use simple_test_case::line_cases;
+
+#[line_cases("tests/numbers.txt")]
+#[test]
+fn parses(line: &str) {
+    assert!(line.parse::<usize>().is_ok())
+}
+
+#[line_cases("tests/numbers.txt")]
+#[test]
+fn with_line_number(line_number: usize, line: &str) {
+    assert!(line_number > 1);
+    assert!(line.parse::<usize>().is_ok())
+}
@@ -0,0 +1,19 @@
+use simple_test_case::locale_cases;
+
+#[locale_cases]
+#[test]
+fn inputs_are_non_empty(s: &str) {
+    assert!(!s.trim().is_empty());
+}
+
+#[locale_cases(kind = number, skip = [fullwidth_digits])]
+#[test]
+fn numbers_contain_a_digit(s: &str) {
+    assert!(s.chars().any(|c| c.is_numeric()));
+}
+
+#[locale_cases(kind = date)]
+#[test]
+fn dates_do_not_parse_as_floats(s: &str) {
+    assert!(s.parse::<f64>().is_err());
+}
@@ -0,0 +1,15 @@
+use simple_test_case::markdown_cases;
+
+#[markdown_cases("tests/examples.md", lang = "json")]
+#[test]
+fn json_blocks(block: &str) {
+    assert!(block.starts_with('{'));
+    assert!(block.trim_end().ends_with('}'));
+}
+
+#[markdown_cases("tests/examples.md")]
+#[test]
+fn all_blocks(line_number: usize, block: &str) {
+    assert!(line_number > 1);
+    assert!(!block.is_empty());
+}
@@ -0,0 +1,29 @@
+use simple_test_case::{once_setup, test_case};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static INITIALISED: AtomicUsize = AtomicUsize::new(0);
+
+fn init() {
+    INITIALISED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn initialised_count() -> usize {
+    INITIALISED.load(Ordering::SeqCst)
+}
+
+#[test_case(initialised_count(); "first")]
+#[test_case(initialised_count(); "second")]
+#[test_case(initialised_count(); "nested"; module = nested)]
+#[once_setup(init())]
+#[test]
+fn setup_runs_before_arguments_are_bound(count: usize) {
+    assert_eq!(count, 1);
+}
+
+static PLAIN: AtomicUsize = AtomicUsize::new(0);
+
+#[once_setup(PLAIN.fetch_add(1, Ordering::SeqCst))]
+#[test]
+fn plain_tests_are_supported() {
+    assert_eq!(PLAIN.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,21 @@
+use simple_test_case::path_cases;
+use std::path::{Path, PathBuf};
+
+#[path_cases]
+#[test]
+fn components_round_trip(p: &Path) {
+    let rebuilt: PathBuf = p.components().collect();
+    assert_eq!(rebuilt.components().count(), p.components().count());
+}
+
+#[path_cases(skip = [nul_byte, newline])]
+#[test]
+fn strings_are_accepted(s: &str) {
+    assert!(!s.contains('\0'));
+}
+
+#[path_cases]
+#[test]
+fn owned_paths_are_accepted(p: PathBuf) {
+    assert_eq!(p.to_str().map(|s| s.len()), Some(p.as_os_str().len()));
+}
@@ -0,0 +1,20 @@
+use simple_test_case::permutation_cases;
+
+#[permutation_cases([3, 1, 2])]
+#[test]
+fn sorting_is_order_independent(mut v: Vec<u8>) {
+    v.sort();
+    assert_eq!(v, vec![1, 2, 3]);
+}
+
+#[permutation_cases(["a", "b", "c", "d"])]
+#[test]
+fn slices_are_supported(s: &[&str]) {
+    assert_eq!(s.concat().len(), 4);
+}
+
+#[permutation_cases([1u64, 2, 3, 4, 5, 6, 7, 8, 9, 10], max = 50)]
+#[test]
+fn large_lists_are_sampled(a: [u64; 10]) {
+    assert_eq!(a.iter().sum::<u64>(), 55);
+}
@@ -0,0 +1,8 @@
+use simple_test_case::proptest_regressions;
+
+#[proptest_regressions("tests/proptest-regressions/split.txt")]
+#[test]
+fn split_lengths(s: String, parts: Vec<usize>) {
+    let lengths: Vec<usize> = s.split(',').map(|p| p.len()).collect();
+    assert_eq!(lengths, parts);
+}
@@ -0,0 +1,36 @@
+use simple_test_case::random_cases;
+
+// A small xorshift generator so that the tests don't need a dependency on rand
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn pair(seed: u64) -> (u32, u32) {
+    let mut state = seed | 1;
+    (next(&mut state) as u32, next(&mut state) as u32)
+}
+
+fn small(seed: u64) -> u8 {
+    (seed % 100) as u8
+}
+
+#[random_cases(count = 8, seed = 0xDEADBEEF)]
+#[test]
+fn seeds_are_passed_directly(seed: u64) {
+    assert_ne!(seed, 0xDEADBEEF);
+}
+
+#[random_cases(count = 4, seed = 42, gen = small)]
+#[test]
+fn generated_values_are_passed_to_a_single_argument(n: u8) {
+    assert!(n < 100);
+}
+
+#[random_cases(count = 16, seed = 7, gen = pair)]
+#[test]
+fn tuples_are_passed_to_multiple_arguments(a: u32, b: u32) {
+    assert_eq!(a.wrapping_add(b), b.wrapping_add(a));
+}
@@ -0,0 +1,14 @@
+use simple_test_case::range_cases;
+
+#[range_cases(0..=16)]
+#[test]
+fn it_works(n: u8) {
+    assert!(n <= 16);
+}
+
+#[range_cases(-4..4, step = 2)]
+#[test]
+fn it_works_with_negative_values_and_steps(n: i32) {
+    assert_eq!(n % 2, 0);
+    assert!((-4..4).contains(&n));
+}
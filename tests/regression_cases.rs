@@ -0,0 +1,12 @@
+use simple_test_case::regression_cases;
+
+#[regression_cases("tests/regressions.toml")]
+#[test]
+fn split_fields(input: &str, expected: Vec<&str>) {
+    let fields: Vec<&str> = match input {
+        "" => vec![],
+        input => input.split(',').collect(),
+    };
+
+    assert_eq!(fields, expected);
+}
@@ -0,0 +1,19 @@
+use simple_test_case::subset_cases;
+
+#[subset_cases([1, 2, 4, 8])]
+#[test]
+fn sums_are_distinct_bit_patterns(v: Vec<u8>) {
+    assert_eq!(v.iter().sum::<u8>().count_ones() as usize, v.len());
+}
+
+#[subset_cases(["read", "write", "exec"], min_size = 1)]
+#[test]
+fn non_empty_subsets(perms: &[&str]) {
+    assert!(!perms.is_empty());
+}
+
+#[subset_cases([1u32, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20], min_size = 2, max_size = 2)]
+#[test]
+fn pairs(pair: [u32; 2]) {
+    assert!(pair[0] < pair[1]);
+}
@@ -0,0 +1,82 @@
+use simple_test_case::suite;
+
+#[suite]
+mod engine_tests {
+    use simple_test_case::test_case;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct Engine {
+        offset: i64,
+    }
+
+    #[shared]
+    fn engine() -> Engine {
+        STARTED.fetch_add(1, Ordering::SeqCst);
+        Engine { offset: 10 }
+    }
+
+    #[test_case(1, 11; "one")]
+    #[test_case(-10, 0; "negative")]
+    #[test]
+    fn offsets_are_applied(n: i64, expected: i64, #[fixture] engine: &Engine) {
+        assert_eq!(n + engine.offset, expected);
+        assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test_case(2; "two")]
+    #[test]
+    fn engine_is_shared_between_functions(n: i64, #[fixture] engine: &Engine) {
+        assert_eq!(engine.offset * n, 20);
+        assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[suite]
+mod hooks {
+    use simple_test_case::test_case;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static EVENTS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: &'static str) {
+        EVENTS.with(|e| e.borrow_mut().push(event));
+    }
+
+    #[setup]
+    fn start() {
+        EVENTS.with(|e| e.borrow_mut().clear());
+        record("setup");
+    }
+
+    #[teardown]
+    fn stop() {
+        record("teardown");
+        let events = EVENTS.with(|e| e.borrow().clone());
+        assert!(matches!(
+            events.as_slice(),
+            ["setup", "body", "teardown"] | ["setup", "teardown"]
+        ));
+    }
+
+    #[test_case(1; "one")]
+    #[test_case(2; "two")]
+    #[test]
+    fn hooks_run_around_each_case(n: u8) {
+        record("body");
+        EVENTS.with(|e| assert_eq!(*e.borrow(), vec!["setup", "body"]));
+        assert!(n > 0);
+    }
+
+    #[test_case(0; "zero")]
+    #[test]
+    #[should_panic(expected = "zero")]
+    fn teardown_runs_on_panic(n: u8) {
+        if n == 0 {
+            panic!("zero");
+        }
+    }
+}
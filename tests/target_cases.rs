@@ -0,0 +1,24 @@
+use simple_test_case::target_cases;
+
+fn separator() -> char {
+    std::path::MAIN_SEPARATOR
+}
+
+#[target_cases(unix, windows)]
+#[test_case('/'; "forward slash")]
+#[test_case('\\'; "backslash")]
+#[test]
+fn separators(sep: char, target: &str) {
+    let is_main = match target {
+        "windows" => sep == '\\',
+        _ => sep == '/',
+    };
+    assert_eq!(separator() == sep, is_main);
+}
+
+#[target_cases(unix, windows, linux)]
+#[test]
+fn target_names_are_passed(target: &str) {
+    let expected = if cfg!(windows) { "windows" } else { "unix" };
+    assert!(target == expected || target == std::env::consts::OS);
+}
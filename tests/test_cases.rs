@@ -0,0 +1,71 @@
+use simple_test_case::{param_test, test_cases};
+
+fn double(n: usize) -> usize {
+    n * 2
+}
+
+test_cases! {
+    #[test]
+    fn it_works(n: usize, expected: usize) {
+        assert_eq!(double(n), expected);
+    }
+
+    cases: [
+        (1, 2) => "small",
+        (100, 200) => "large",
+    ]
+}
+
+test_cases! {
+    #[tokio::test]
+    #[should_panic(expected = "always panics")]
+    async fn it_works_with_other_attributes(n: usize) {
+        assert!(n > 0);
+        panic!("always panics");
+    }
+
+    cases: [(1) => "one"]
+}
+
+macro_rules! doubling_test {
+    ($name:ident, $($n:expr => $case:literal),+) => {
+        test_cases! {
+            #[test]
+            fn $name(n: usize) {
+                assert_eq!(double(n) / 2, n);
+            }
+
+            cases: [$(($n) => $case),+]
+        }
+    };
+}
+
+doubling_test!(it_works_from_other_macros, 3 => "three", 4 => "four");
+
+param_test! {
+    #[test]
+    fn param_test_works(n: usize, expected: usize) {
+        assert_eq!(double(n), expected);
+    }
+
+    cases {
+        (1, 2; "small"),
+        (100, 200; "large"),
+        (1, 3; "wrong"; #[should_panic]),
+    }
+}
+
+macro_rules! attached_test {
+    ($name:ident, $($n:expr => $case:literal),+) => {
+        param_test! {
+            #[test]
+            fn $name(n: usize) {
+                assert_eq!(double(n) / 2, n);
+            }
+
+            cases { $(($n; $case)),+ }
+        }
+    };
+}
+
+attached_test!(param_test_works_from_other_macros, 3 => "three", 4 => "four");
@@ -0,0 +1,48 @@
+use simple_test_case::test_matrix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Strict,
+    Lenient,
+}
+
+fn parse(s: &str, mode: Mode) -> Option<i64> {
+    match mode {
+        Mode::Strict => s.parse().ok(),
+        Mode::Lenient => s.trim().parse().ok(),
+    }
+}
+
+#[test_matrix([1, -1, 42], [Mode::Strict, Mode::Lenient])]
+#[test]
+fn it_works(n: i64, mode: Mode) {
+    assert_eq!(parse(&n.to_string(), mode), Some(n));
+}
+
+#[test_matrix(["a b", "a_c"], [true])]
+#[simple_test_case::test_case("extra", false; "explicit")]
+#[test]
+fn it_can_be_combined_with_test_case(s: &str, flag: bool) {
+    assert!(!s.is_empty());
+    assert_eq!(flag, s != "extra");
+}
+
+#[test_matrix([1, 2], [3, 4])]
+#[tokio::test]
+async fn it_works_with_async_tests(a: usize, b: usize) {
+    assert!(a < b);
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    retries: usize,
+}
+
+#[test_matrix(
+    config = [("none", Config { retries: 0 }), ("some", Config { retries: 3 })],
+    verbose = [true, false]
+)]
+#[test]
+fn it_works_with_labelled_values(config: Config, verbose: bool) {
+    assert!(config.retries <= 3 || verbose);
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "tracing")]
+
+use simple_test_case::test_case;
+
+fn current_span_name() -> Option<&'static str> {
+    tracing::Span::current().metadata().map(|m| m.name())
+}
+
+#[test_case(1; "one")]
+#[test_case(2; "two")]
+#[test]
+fn cases_run_inside_a_span(n: u8) {
+    tracing::info!(n, "running case");
+
+    assert_eq!(current_span_name(), Some("case"));
+}
+
+#[test_case(1; "one")]
+#[tokio::test]
+async fn async_cases_run_inside_a_span(n: u8) {
+    tracing::info!(n, "running case");
+
+    assert_eq!(current_span_name(), Some("case"));
+}
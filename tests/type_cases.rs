@@ -0,0 +1,25 @@
+use simple_test_case::type_cases;
+use std::{fmt::Debug, str::FromStr};
+
+#[type_cases(u8, u16, u32, u64, i64)]
+#[test]
+fn it_works<T: FromStr + ToString + From<u8> + PartialEq + Debug>() {
+    let n = T::from(42);
+    assert_eq!(n.to_string().parse::<T>().ok(), Some(n));
+}
+
+#[type_cases(Vec<u8>, String)]
+#[test]
+fn it_works_with_results<T: Default + PartialEq>() -> Result<(), String> {
+    if T::default() == T::default() {
+        Ok(())
+    } else {
+        Err("default values differ".to_string())
+    }
+}
+
+#[type_cases(u32, i32)]
+#[tokio::test]
+async fn it_works_with_async_tests<T: Default + Into<i64>>() {
+    assert_eq!(T::default().into(), 0);
+}
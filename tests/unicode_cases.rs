@@ -0,0 +1,14 @@
+use simple_test_case::unicode_cases;
+
+#[unicode_cases]
+#[test]
+fn chars_round_trip(s: &str) {
+    let collected: String = s.chars().collect();
+    assert_eq!(collected, s);
+}
+
+#[unicode_cases(skip = [long_ascii, long_multibyte])]
+#[test]
+fn owned_strings_are_supported(s: String) {
+    assert!(s.len() < 1024);
+}
@@ -0,0 +1,15 @@
+use simple_test_case::vector_cases;
+
+#[vector_cases("tests/vectors.rsp")]
+#[test]
+fn byte_sums(len: usize, msg: &[u8], sum: u8, r#mod: u16) {
+    let msg = &msg[..len / 8];
+    let expected = msg.iter().map(|b| *b as u16).sum::<u16>() % r#mod;
+    assert_eq!(sum as u16, expected);
+}
+
+#[vector_cases("tests/vectors.rsp")]
+#[test]
+fn owned_and_string_values(msg: Vec<u8>, len: String) {
+    assert_eq!(msg.len() * 8, len.parse::<usize>().unwrap().max(8));
+}